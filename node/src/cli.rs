@@ -7,6 +7,63 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	#[clap(flatten)]
+	pub eth_rpc: EthRpcParams,
+
+	/// Seal blocks without BABE and GRANDPA on a development chain: `instant` for a block per
+	/// transaction, `manual` for blocks on `engine_createBlock` only, or a number of
+	/// milliseconds for a block at that interval.
+	///
+	/// `engine_createBlock` and `engine_finalizeBlock` are served in every mode.
+	#[arg(long, value_name = "instant|manual|MILLIS")]
+	pub sealing: Option<Sealing>,
+}
+
+/// How blocks are sealed on a development chain, in place of BABE slots and GRANDPA finality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal and finalize a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal blocks only when asked to through `engine_createBlock`.
+	Manual,
+	/// Seal and finalize a block every so many milliseconds.
+	Interval(u64),
+}
+
+impl std::str::FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Self::Instant),
+			"manual" => Ok(Self::Manual),
+			millis => match millis.parse() {
+				Ok(millis) if millis > 0 => Ok(Self::Interval(millis)),
+				_ => Err(format!(
+					"expected `instant`, `manual` or a number of milliseconds, got `{millis}`"
+				)),
+			},
+		}
+	}
+}
+
+/// Options of the Ethereum JSON-RPC server.
+#[derive(Debug, Clone, clap::Args)]
+pub struct EthRpcParams {
+	/// Serve the Ethereum JSON-RPC (`eth_*`, `net_*` and `web3_*`) on this port.
+	///
+	/// The server is disabled unless a port is given. 8545 is the port wallets expect.
+	#[arg(long, value_name = "PORT")]
+	pub eth_rpc_port: Option<u16>,
+
+	/// Listen to Ethereum JSON-RPC requests on all interfaces instead of localhost only.
+	#[arg(long)]
+	pub eth_rpc_external: bool,
+
+	/// Index the Ethereum receipts of the last `N` blocks at startup, in addition to new ones.
+	#[arg(long, value_name = "N")]
+	pub eth_index_last_n_blocks: Option<u32>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -2,6 +2,7 @@ use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
 	chain_spec,
 	cli::{Cli, Subcommand},
+	eth_rpc::EthRpcConfig,
 	service,
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
@@ -119,9 +120,10 @@ pub fn run() -> sc_cli::Result<()> {
 							);
 						}
 
-						cmd.run_with_spec::<sp_runtime::traits::HashingFor<Block>, ()>(Some(
-							config.chain_spec,
-						))
+						cmd.run_with_spec::<
+							sp_runtime::traits::HashingFor<Block>,
+							kora_zk_crypto::HostFunctions,
+						>(Some(config.chain_spec))
 					},
 					BenchmarkCmd::Block(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
@@ -180,17 +182,28 @@ pub fn run() -> sc_cli::Result<()> {
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
+				let eth_rpc = EthRpcConfig::new(&cli.eth_rpc, &config);
+				let sealing = cli.sealing;
+				if sealing.is_some() &&
+					config.chain_spec.chain_type() != sc_service::ChainType::Development
+				{
+					return Err(sc_cli::Error::Input(
+						"--sealing is only available on development chains".into(),
+					));
+				}
 				match config.network.network_backend.unwrap_or_default() {
 					sc_network::config::NetworkBackendType::Libp2p => service::new_full::<
 						sc_network::NetworkWorker<
 						kora_chain_runtime::opaque::Block,
 							<kora_chain_runtime::opaque::Block as sp_runtime::traits::Block>::Hash,
 						>,
-					>(config)
+					>(config, eth_rpc, sealing)
 					.map_err(sc_cli::Error::Service),
 					sc_network::config::NetworkBackendType::Litep2p =>
-						service::new_full::<sc_network::Litep2pNetworkBackend>(config)
-							.map_err(sc_cli::Error::Service),
+						service::new_full::<sc_network::Litep2pNetworkBackend>(
+							config, eth_rpc, sealing,
+						)
+						.map_err(sc_cli::Error::Service),
 				}
 			})
 		},
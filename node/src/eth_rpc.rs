@@ -0,0 +1,119 @@
+//! The Ethereum JSON-RPC server of the node.
+//!
+//! `pallet-revive-eth-rpc` serves the `eth_*`, `net_*` and `web3_*` namespaces expected by
+//! MetaMask, ethers.js and Foundry, translating them into `ReviveApi` calls and `eth_transact`
+//! extrinsics. It reaches the node through its own Substrate RPC endpoint. A mapping-sync task
+//! follows best and finalized blocks and indexes their Ethereum transactions, receipts and logs
+//! into a SQLite database in the chain's directory, so they can be looked up by Ethereum hash.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use jsonrpsee::{server::Server, RpcModule};
+use pallet_revive::evm::Account;
+use pallet_revive_eth_rpc::{
+	client::{connect, Client, SubscriptionType, SubstrateBlockNumber},
+	DebugRpcServer, DebugRpcServerImpl, EthRpcServer, EthRpcServerImpl, ReceiptExtractor,
+	ReceiptProvider, SubxtBlockInfoProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl,
+};
+use sc_service::{Configuration, TaskManager};
+
+use crate::cli::EthRpcParams;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Settings of the Ethereum JSON-RPC server.
+pub struct EthRpcConfig {
+	/// Address the server listens on.
+	pub addr: SocketAddr,
+	/// Substrate RPC endpoint of this node.
+	pub node_rpc_url: String,
+	/// SQLite database the receipts are indexed into.
+	pub database_url: String,
+	/// Number of past blocks to index at startup.
+	pub index_last_n_blocks: Option<SubstrateBlockNumber>,
+	/// Whether to expose a well-known development account through `eth_accounts`.
+	pub dev_accounts: bool,
+}
+
+impl EthRpcConfig {
+	/// The server settings requested by `params`, or `None` if the server is disabled.
+	pub fn new(params: &EthRpcParams, config: &Configuration) -> Option<Self> {
+		let port = params.eth_rpc_port?;
+		let ip = if params.eth_rpc_external { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+		let database = config.base_path.config_dir(config.chain_spec.id()).join("eth-rpc.db");
+
+		Some(Self {
+			addr: SocketAddr::new(ip.into(), port),
+			node_rpc_url: format!("ws://{}", node_rpc_addr(config)),
+			database_url: format!("sqlite://{}?mode=rwc", database.display()),
+			index_last_n_blocks: params.eth_index_last_n_blocks,
+			dev_accounts: config.chain_spec.chain_type() == sc_service::ChainType::Development,
+		})
+	}
+}
+
+/// Address of the first Substrate RPC endpoint of this node, with a wildcard IP replaced by the
+/// loopback address of the same family.
+fn node_rpc_addr(config: &Configuration) -> SocketAddr {
+	let addr = match &config.rpc.addr {
+		Some(endpoints) if !endpoints.is_empty() => endpoints[0].listen_addr,
+		_ => SocketAddr::new(Ipv4Addr::LOCALHOST.into(), config.rpc.port),
+	};
+	let ip = match addr.ip() {
+		IpAddr::V4(ip) if ip.is_unspecified() => Ipv4Addr::LOCALHOST.into(),
+		IpAddr::V6(ip) if ip.is_unspecified() => Ipv6Addr::LOCALHOST.into(),
+		ip => ip,
+	};
+	SocketAddr::new(ip, addr.port())
+}
+
+/// Spawns the server and its mapping-sync task.
+pub fn spawn(config: EthRpcConfig, task_manager: &TaskManager) {
+	task_manager.spawn_handle().spawn("eth-rpc", Some("eth-rpc"), async move {
+		if let Err(err) = run(config).await {
+			log::error!(target: "eth-rpc", "Ethereum JSON-RPC server stopped: {err}");
+		}
+	});
+}
+
+async fn run(config: EthRpcConfig) -> Result<(), Error> {
+	let (api, rpc_client, rpc) = connect(&config.node_rpc_url).await?;
+	let block_provider = SubxtBlockInfoProvider::new(api.clone(), rpc.clone()).await?;
+	let receipt_extractor = ReceiptExtractor::new(api.clone(), None).await?;
+	let receipt_provider = ReceiptProvider::new(
+		&config.database_url,
+		block_provider.clone(),
+		receipt_extractor,
+		None,
+	)
+	.await?;
+	let client = Client::new(api, rpc_client, rpc, block_provider, receipt_provider).await?;
+
+	let server = Server::builder().build(config.addr).await?;
+	log::info!(target: "eth-rpc", "Ethereum JSON-RPC listening on {}", server.local_addr()?);
+	let handle = server.start(rpc_module(client.clone(), config.dev_accounts)?);
+
+	let best = client.subscribe_and_cache_new_blocks(SubscriptionType::BestBlocks);
+	let finalized = client.subscribe_and_cache_new_blocks(SubscriptionType::FinalizedBlocks);
+	match config.index_last_n_blocks {
+		Some(n) => {
+			futures::join!(best, finalized, client.subscribe_and_cache_blocks(n));
+		},
+		None => {
+			futures::join!(best, finalized);
+		},
+	}
+
+	handle.stop()?;
+	Ok(())
+}
+
+fn rpc_module(client: Client, dev_accounts: bool) -> Result<RpcModule<()>, Error> {
+	let accounts = if dev_accounts { vec![Account::default()] } else { vec![] };
+
+	let mut module = RpcModule::new(());
+	module.merge(EthRpcServerImpl::new(client.clone()).with_accounts(accounts).into_rpc())?;
+	module.merge(SystemHealthRpcServerImpl::new(client.clone()).into_rpc())?;
+	module.merge(DebugRpcServerImpl::new(client).into_rpc())?;
+	Ok(module)
+}
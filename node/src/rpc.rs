@@ -21,6 +21,7 @@
 
 use std::sync::Arc;
 
+use frame_support::traits::fungible::NativeOrWithId;
 use jsonrpsee::RpcModule;
 use kora_chain_runtime::{self, opaque::{
 	Block, Hash
@@ -28,6 +29,9 @@ use kora_chain_runtime::{self, opaque::{
 
 use sc_client_api::AuxStore;
 use sc_consensus_babe::BabeWorkerHandle;
+use sc_consensus_beefy::communication::notification::{
+	BeefyBestBlockStream, BeefyVersionedFinalityProofStream,
+};
 use sc_consensus_grandpa::{
 	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
 };
@@ -39,8 +43,15 @@ use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
+use sp_consensus_beefy::AuthorityIdBound;
 use sp_keystore::KeystorePtr;
 
+mod asset_conversion;
+mod assets;
+mod contracts;
+
+type EventRecord = frame_system::EventRecord<kora_chain_runtime::RuntimeEvent, Hash>;
+
 /// Extra dependencies for BABE.
 pub struct BabeDeps {
 	/// A handle to the BABE worker for issuing requests.
@@ -63,8 +74,18 @@ pub struct GrandpaDeps<B> {
 	pub finality_provider: Arc<FinalityProofProvider<B, Block>>,
 }
 
+/// Extra dependencies for BEEFY.
+pub struct BeefyDeps<AuthorityId: AuthorityIdBound> {
+	/// Receives notifications about finality proof events from BEEFY.
+	pub beefy_finality_proof_stream: BeefyVersionedFinalityProofStream<Block, AuthorityId>,
+	/// Receives notifications about best block events from BEEFY.
+	pub beefy_best_block_stream: BeefyBestBlockStream<Block>,
+	/// Executor to drive the subscription manager in the BEEFY RPC handler.
+	pub subscription_executor: SubscriptionTaskExecutor,
+}
+
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC, B> {
+pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
@@ -77,12 +98,14 @@ pub struct FullDeps<C, P, SC, B> {
 	pub babe: BabeDeps,
 	/// GRANDPA specific dependencies.
 	pub grandpa: GrandpaDeps<B>,
+	/// BEEFY specific dependencies.
+	pub beefy: BeefyDeps<AuthorityId>,
 	/// Backend.
 	pub backend: Arc<B>,
 }
 
 /// Instantiate all Full RPC extensions.
-pub fn create_full<C, P, SC, B>(
+pub fn create_full<C, P, SC, B, AuthorityId>(
 	FullDeps {
 		client,
 		pool,
@@ -90,8 +113,9 @@ pub fn create_full<C, P, SC, B>(
 		chain_spec,
 		babe,
 		grandpa,
+		beefy,
 		backend,
-	}: FullDeps<C, P, SC, B>,
+	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>
@@ -105,14 +129,35 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
+	C::Api: mmr_rpc::MmrRuntimeApi<Block, Hash, BlockNumber>,
+	C::Api: kora_chain_runtime::apis::AssetsApi<Block, AccountId, Balance, u32>,
+	C::Api: kora_chain_runtime::apis::AssetConversionRouteApi<
+		Block,
+		Balance,
+		NativeOrWithId<u32>,
+	>,
+	C::Api: pallet_contracts::ContractsApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+		Hash,
+		EventRecord,
+	>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>,
+	AuthorityId: AuthorityIdBound,
 {
+	use asset_conversion::{AssetConversion, AssetConversionApiServer};
+	use assets::{Assets, AssetsApiServer};
+	use contracts::{Contracts, ContractsApiServer};
+	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
+	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
 	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_rpc::{
 		dev::{Dev, DevApiServer},
@@ -134,6 +179,11 @@ where
 
 	io.merge(System::new(client.clone(), pool).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(Assets::<_, Block, Balance>::new(client.clone()).into_rpc())?;
+	io.merge(AssetConversion::<_, Block, Balance>::new(client.clone()).into_rpc())?;
+	io.merge(
+		Contracts::<_, Block, Balance, BlockNumber, EventRecord>::new(client.clone()).into_rpc(),
+	)?;
 	io.merge(
 		Babe::new(client.clone(), babe_worker_handle.clone(), keystore, select_chain).into_rpc(),
 	)?;
@@ -152,6 +202,21 @@ where
 		SyncState::new(chain_spec, client.clone(), shared_authority_set, babe_worker_handle)?
 			.into_rpc(),
 	)?;
+	io.merge(
+		Beefy::<Block, AuthorityId>::new(
+			beefy.beefy_finality_proof_stream,
+			beefy.beefy_best_block_stream,
+			beefy.subscription_executor,
+		)?
+		.into_rpc(),
+	)?;
+	io.merge(
+		Mmr::new(
+			client.clone(),
+			backend.offchain_storage().ok_or("Backend doesn't provide an offchain storage")?,
+		)
+		.into_rpc(),
+	)?;
 
 	io.merge(StateMigration::new(client.clone(), backend).into_rpc())?;
 	
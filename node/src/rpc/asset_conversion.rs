@@ -0,0 +1,233 @@
+//! The `assetConversion_*` RPC namespace.
+//!
+//! Serves the `AssetConversionRouteApi` runtime API as JSON: the best path through the
+//! `AssetConversion` pools to swap one asset for another, with the amounts, fee and price impact
+//! a front-end needs to build a `swap_exact_tokens_for_tokens` or `swap_tokens_for_exact_tokens`
+//! call.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use frame_support::traits::fungible::NativeOrWithId;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::error::ErrorObject};
+use kora_chain_runtime::apis::{self, AssetConversionRouteApi as AssetConversionRouteRuntimeApi};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+
+/// The native token or an asset of `Assets`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Asset<AssetId> {
+	/// The native token.
+	Native,
+	/// The asset with this id.
+	WithId(AssetId),
+}
+
+impl<AssetId: Ord> From<Asset<AssetId>> for NativeOrWithId<AssetId> {
+	fn from(asset: Asset<AssetId>) -> Self {
+		match asset {
+			Asset::Native => Self::Native,
+			Asset::WithId(id) => Self::WithId(id),
+		}
+	}
+}
+
+impl<AssetId: Ord> From<NativeOrWithId<AssetId>> for Asset<AssetId> {
+	fn from(asset: NativeOrWithId<AssetId>) -> Self {
+		match asset {
+			NativeOrWithId::Native => Self::Native,
+			NativeOrWithId::WithId(id) => Self::WithId(id),
+		}
+	}
+}
+
+/// Which side of a swap is fixed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SwapKind {
+	/// The amount in is exact, as in `swap_exact_tokens_for_tokens`.
+	ExactIn,
+	/// The amount out is exact, as in `swap_tokens_for_exact_tokens`.
+	ExactOut,
+}
+
+/// A swap route through the pools.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapRoute<AssetId> {
+	/// The assets to swap through, from the asset in to the asset out.
+	pub path: Vec<Asset<AssetId>>,
+	/// The amount of the asset in swapped.
+	pub amount_in: NumberOrHex,
+	/// The amount of the asset out received.
+	pub amount_out: NumberOrHex,
+	/// The liquidity provider fees of all pools along the path, in the asset in.
+	pub fee: NumberOrHex,
+	/// How much worse than the pools' current prices the swap is, fees excluded, in parts per
+	/// million.
+	pub price_impact: u32,
+}
+
+impl<AssetId: Ord, Balance: Into<NumberOrHex>>
+	From<apis::SwapRoute<Balance, NativeOrWithId<AssetId>>> for SwapRoute<AssetId>
+{
+	fn from(route: apis::SwapRoute<Balance, NativeOrWithId<AssetId>>) -> Self {
+		Self {
+			path: route.path.into_iter().map(Into::into).collect(),
+			amount_in: route.amount_in.into(),
+			amount_out: route.amount_out.into(),
+			fee: route.fee.into(),
+			price_impact: route.price_impact.deconstruct(),
+		}
+	}
+}
+
+/// Asset conversion RPC methods.
+#[rpc(server)]
+pub trait AssetConversionApi<BlockHash, AssetId> {
+	/// Returns the best route through the pools to swap `asset_in` for `asset_out`, where
+	/// `amount` is the exact amount in or out depending on `kind`, or `null` if no pools with
+	/// enough liquidity connect the assets.
+	#[method(name = "assetConversion_bestRoute")]
+	fn best_route(
+		&self,
+		asset_in: Asset<AssetId>,
+		asset_out: Asset<AssetId>,
+		amount: NumberOrHex,
+		kind: SwapKind,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<SwapRoute<AssetId>>>;
+}
+
+/// Serves the `assetConversion_*` RPC namespace.
+pub struct AssetConversion<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> AssetConversion<C, Block, Balance> {
+	/// Creates a new instance of the AssetConversion RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The amount does not fit in a balance.
+	InvalidAmount,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidAmount => 2,
+		}
+	}
+}
+
+impl<C, Block, Balance, AssetId> AssetConversionApiServer<<Block as BlockT>::Hash, AssetId>
+	for AssetConversion<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AssetConversionRouteRuntimeApi<Block, Balance, NativeOrWithId<AssetId>>,
+	Balance: Codec + Into<NumberOrHex> + TryFrom<NumberOrHex> + Send + Sync + 'static,
+	AssetId: Codec + Ord + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+{
+	fn best_route(
+		&self,
+		asset_in: Asset<AssetId>,
+		asset_out: Asset<AssetId>,
+		amount: NumberOrHex,
+		kind: SwapKind,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<SwapRoute<AssetId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let amount = Balance::try_from(amount).map_err(|_| {
+			ErrorObject::owned(
+				Error::InvalidAmount.into(),
+				"Amount does not fit in a balance.",
+				Some(format!("{amount:?}")),
+			)
+		})?;
+		let route = api
+			.best_route(
+				at_hash,
+				asset_in.into(),
+				asset_out.into(),
+				amount,
+				kind == SwapKind::ExactIn,
+			)
+			.map_err(|e| {
+				ErrorObject::owned(
+					Error::RuntimeError.into(),
+					"Unable to query the best route.",
+					Some(e.to_string()),
+				)
+			})?;
+
+		Ok(route.map(Into::into))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use sp_runtime::Permill;
+
+	#[test]
+	fn request_parameters_parse_from_camel_case_json() {
+		assert_eq!(serde_json::from_value::<Asset<u32>>(json!("native")).unwrap(), Asset::Native);
+		assert_eq!(
+			serde_json::from_value::<Asset<u32>>(json!({ "withId": 7 })).unwrap(),
+			Asset::WithId(7)
+		);
+		assert!(serde_json::from_value::<Asset<u32>>(json!("Native")).is_err());
+		assert_eq!(
+			serde_json::from_value::<SwapKind>(json!("exactIn")).unwrap(),
+			SwapKind::ExactIn
+		);
+		assert_eq!(
+			serde_json::from_value::<SwapKind>(json!("exactOut")).unwrap(),
+			SwapKind::ExactOut
+		);
+		assert_eq!(NativeOrWithId::from(Asset::WithId(7u32)), NativeOrWithId::<u32>::WithId(7));
+	}
+
+	#[test]
+	fn swap_route_serializes_to_camel_case_json() {
+		let route: SwapRoute<u32> = apis::SwapRoute {
+			path: vec![
+				NativeOrWithId::WithId(1),
+				NativeOrWithId::Native,
+				NativeOrWithId::WithId(2),
+			],
+			amount_in: 1_000u128,
+			amount_out: u128::MAX,
+			fee: 6u128,
+			price_impact: Permill::from_parts(1_234),
+		}
+		.into();
+		assert_eq!(
+			serde_json::to_value(&route).unwrap(),
+			json!({
+				"path": [{ "withId": 1 }, "native", { "withId": 2 }],
+				"amountIn": 1_000,
+				"amountOut": "0xffffffffffffffffffffffffffffffff",
+				"fee": 6,
+				"priceImpact": 1_234,
+			})
+		);
+	}
+}
@@ -0,0 +1,436 @@
+//! The `assets_*` RPC namespace.
+//!
+//! Serves the `AssetsApi` runtime API as JSON: the balances of an account in `Assets` and in the
+//! liquidity pool tokens of `PoolAssets`, and the details, metadata, approvals and account
+//! status of an asset, so explorers do not need to decode the pallets' storage themselves.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use kora_chain_runtime::apis::{self, AssetsApi as AssetsRuntimeApi};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+
+/// The balance of an account in an asset.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalance<AssetId> {
+	/// The asset.
+	pub asset_id: AssetId,
+	/// The balance of the account.
+	pub balance: NumberOrHex,
+}
+
+/// An asset class.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDetails<AccountId> {
+	/// Can change the team and destroy the asset.
+	pub owner: AccountId,
+	/// Can mint tokens.
+	pub issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	pub admin: AccountId,
+	/// Can freeze tokens.
+	pub freezer: AccountId,
+	/// The total supply across all accounts.
+	pub supply: NumberOrHex,
+	/// The balance below which an account is reaped.
+	pub min_balance: NumberOrHex,
+	/// Whether holding the asset alone keeps an account alive.
+	pub is_sufficient: bool,
+	/// The number of accounts holding the asset.
+	pub holders: u32,
+	/// The number of accounts kept alive by the asset.
+	pub sufficients: u32,
+	/// The number of approvals.
+	pub approvals: u32,
+	/// Whether the asset is live, frozen or being destroyed.
+	pub status: AssetStatus,
+}
+
+impl<AccountId, Balance: Into<NumberOrHex>> From<apis::AssetDetails<AccountId, Balance>>
+	for AssetDetails<AccountId>
+{
+	fn from(details: apis::AssetDetails<AccountId, Balance>) -> Self {
+		Self {
+			owner: details.owner,
+			issuer: details.issuer,
+			admin: details.admin,
+			freezer: details.freezer,
+			supply: details.supply.into(),
+			min_balance: details.min_balance.into(),
+			is_sufficient: details.is_sufficient,
+			holders: details.accounts,
+			sufficients: details.sufficients,
+			approvals: details.approvals,
+			status: details.status.into(),
+		}
+	}
+}
+
+/// The state of an asset class.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetStatus {
+	/// The asset can be used.
+	Live,
+	/// The asset cannot be transferred.
+	Frozen,
+	/// The asset is being destroyed.
+	Destroying,
+}
+
+impl From<pallet_assets::AssetStatus> for AssetStatus {
+	fn from(status: pallet_assets::AssetStatus) -> Self {
+		match status {
+			pallet_assets::AssetStatus::Live => Self::Live,
+			pallet_assets::AssetStatus::Frozen => Self::Frozen,
+			pallet_assets::AssetStatus::Destroying => Self::Destroying,
+		}
+	}
+}
+
+/// The metadata of an asset, with the name and symbol decoded as UTF-8.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetMetadata {
+	/// The user friendly name of the asset.
+	pub name: String,
+	/// The ticker symbol of the asset.
+	pub symbol: String,
+	/// The number of decimals of the asset's balances.
+	pub decimals: u8,
+	/// Whether the metadata may no longer be changed by the owner.
+	pub is_frozen: bool,
+}
+
+impl From<apis::AssetMetadata> for AssetMetadata {
+	fn from(metadata: apis::AssetMetadata) -> Self {
+		Self {
+			name: String::from_utf8_lossy(&metadata.name).into_owned(),
+			symbol: String::from_utf8_lossy(&metadata.symbol).into_owned(),
+			decimals: metadata.decimals,
+			is_frozen: metadata.is_frozen,
+		}
+	}
+}
+
+/// An amount of an asset a delegate may transfer on behalf of its owner.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Approval<AccountId> {
+	/// The delegate.
+	pub delegate: AccountId,
+	/// The amount the delegate may transfer.
+	pub amount: NumberOrHex,
+}
+
+/// An account's holding of an asset.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetAccount {
+	/// The balance of the account.
+	pub balance: NumberOrHex,
+	/// Whether the account can send and receive the asset.
+	pub status: AccountStatus,
+}
+
+/// The state of an account's holding of an asset.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountStatus {
+	/// The account can send and receive the asset.
+	Liquid,
+	/// The account cannot send the asset.
+	Frozen,
+	/// The account can neither send nor receive the asset.
+	Blocked,
+}
+
+impl From<pallet_assets::AccountStatus> for AccountStatus {
+	fn from(status: pallet_assets::AccountStatus) -> Self {
+		match status {
+			pallet_assets::AccountStatus::Liquid => Self::Liquid,
+			pallet_assets::AccountStatus::Frozen => Self::Frozen,
+			pallet_assets::AccountStatus::Blocked => Self::Blocked,
+		}
+	}
+}
+
+/// Assets RPC methods.
+#[rpc(server)]
+pub trait AssetsApi<BlockHash, AccountId, AssetId> {
+	/// Returns the non-zero balances of an account in `Assets`.
+	#[method(name = "assets_accountBalances")]
+	fn account_balances(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<AssetBalance<AssetId>>>;
+
+	/// Returns the non-zero balances of an account in liquidity pool tokens.
+	#[method(name = "assets_poolAccountBalances")]
+	fn pool_account_balances(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<AssetBalance<AssetId>>>;
+
+	/// Returns the details of an asset, including its supply and holders count.
+	#[method(name = "assets_details")]
+	fn details(
+		&self,
+		asset: AssetId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<AssetDetails<AccountId>>>;
+
+	/// Returns the metadata of an asset.
+	#[method(name = "assets_metadata")]
+	fn metadata(&self, asset: AssetId, at: Option<BlockHash>) -> RpcResult<Option<AssetMetadata>>;
+
+	/// Returns the approvals an owner granted for an asset.
+	#[method(name = "assets_approvals")]
+	fn approvals(
+		&self,
+		asset: AssetId,
+		owner: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<Approval<AccountId>>>;
+
+	/// Returns the balance of an account in an asset and whether it is frozen or blocked.
+	#[method(name = "assets_account")]
+	fn account(
+		&self,
+		asset: AssetId,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<AssetAccount>>;
+}
+
+/// Serves the `assets_*` RPC namespace.
+pub struct Assets<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Assets<C, Block, Balance> {
+	/// Creates a new instance of the Assets RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(error: impl ToString) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query assets.",
+		Some(error.to_string()),
+	)
+}
+
+impl<C, Block, AccountId, Balance, AssetId>
+	AssetsApiServer<<Block as BlockT>::Hash, AccountId, AssetId> for Assets<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AssetsRuntimeApi<Block, AccountId, Balance, AssetId>,
+	AccountId: Codec + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+	Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+	AssetId: Codec + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+{
+	fn account_balances(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<AssetBalance<AssetId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let balances = api.account_balances(at_hash, account).map_err(runtime_error)?;
+		Ok(balances
+			.into_iter()
+			.map(|(asset_id, balance)| AssetBalance { asset_id, balance: balance.into() })
+			.collect())
+	}
+
+	fn pool_account_balances(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<AssetBalance<AssetId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let balances = api.pool_account_balances(at_hash, account).map_err(runtime_error)?;
+		Ok(balances
+			.into_iter()
+			.map(|(asset_id, balance)| AssetBalance { asset_id, balance: balance.into() })
+			.collect())
+	}
+
+	fn details(
+		&self,
+		asset: AssetId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<AssetDetails<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let details = api.asset_details(at_hash, asset).map_err(runtime_error)?;
+		Ok(details.map(Into::into))
+	}
+
+	fn metadata(
+		&self,
+		asset: AssetId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<AssetMetadata>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let metadata = api.asset_metadata(at_hash, asset).map_err(runtime_error)?;
+		Ok(metadata.map(Into::into))
+	}
+
+	fn approvals(
+		&self,
+		asset: AssetId,
+		owner: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<Approval<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let approvals = api.approvals(at_hash, asset, owner).map_err(runtime_error)?;
+		Ok(approvals
+			.into_iter()
+			.map(|(delegate, amount)| Approval { delegate, amount: amount.into() })
+			.collect())
+	}
+
+	fn account(
+		&self,
+		asset: AssetId,
+		who: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<AssetAccount>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let account = api.account(at_hash, asset, who).map_err(runtime_error)?;
+		Ok(account.map(|account| AssetAccount {
+			balance: account.balance.into(),
+			status: account.status.into(),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn asset_details_serialize_to_camel_case_json() {
+		let details: AssetDetails<u64> = apis::AssetDetails {
+			owner: 1,
+			issuer: 2,
+			admin: 3,
+			freezer: 4,
+			supply: u128::MAX,
+			min_balance: 10u128,
+			is_sufficient: true,
+			accounts: 5,
+			sufficients: 6,
+			approvals: 7,
+			status: pallet_assets::AssetStatus::Destroying,
+		}
+		.into();
+		assert_eq!(
+			serde_json::to_value(&details).unwrap(),
+			json!({
+				"owner": 1,
+				"issuer": 2,
+				"admin": 3,
+				"freezer": 4,
+				"supply": "0xffffffffffffffffffffffffffffffff",
+				"minBalance": 10,
+				"isSufficient": true,
+				"holders": 5,
+				"sufficients": 6,
+				"approvals": 7,
+				"status": "destroying",
+			})
+		);
+	}
+
+	#[test]
+	fn asset_metadata_is_decoded_lossily() {
+		let metadata: AssetMetadata = apis::AssetMetadata {
+			name: b"Kora \xffDollar".to_vec(),
+			symbol: b"KUSD".to_vec(),
+			decimals: 6,
+			is_frozen: false,
+		}
+		.into();
+		assert_eq!(
+			serde_json::to_value(&metadata).unwrap(),
+			json!({
+				"name": "Kora \u{fffd}Dollar",
+				"symbol": "KUSD",
+				"decimals": 6,
+				"isFrozen": false,
+			})
+		);
+	}
+
+	#[test]
+	fn holdings_serialize_to_camel_case_json() {
+		let balance = AssetBalance { asset_id: 7u32, balance: 500u128.into() };
+		assert_eq!(
+			serde_json::to_value(&balance).unwrap(),
+			json!({ "assetId": 7, "balance": 500 })
+		);
+
+		let approval = Approval { delegate: 2u64, amount: (1u128 << 64).into() };
+		assert_eq!(
+			serde_json::to_value(&approval).unwrap(),
+			json!({ "delegate": 2, "amount": "0x10000000000000000" })
+		);
+
+		for (status, name) in [
+			(pallet_assets::AccountStatus::Liquid, "liquid"),
+			(pallet_assets::AccountStatus::Frozen, "frozen"),
+			(pallet_assets::AccountStatus::Blocked, "blocked"),
+		] {
+			let account = AssetAccount { balance: 1u128.into(), status: status.into() };
+			assert_eq!(
+				serde_json::to_value(&account).unwrap(),
+				json!({ "balance": 1, "status": name })
+			);
+		}
+	}
+}
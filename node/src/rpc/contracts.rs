@@ -0,0 +1,581 @@
+//! The `contracts_*` RPC namespace.
+//!
+//! Dry-runs `pallet-contracts` calls, instantiations and code uploads through the `ContractsApi`
+//! runtime API and reads contract storage, taking and returning JSON so that tooling does not
+//! have to SCALE-encode `state_call` arguments itself. Balances are `NumberOrHex` both ways.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{
+		error::{ErrorCode, ErrorObject},
+		ErrorObjectOwned,
+	},
+};
+use pallet_contracts::{
+	Code, ContractAccessError, ContractResult, ContractsApi as ContractsRuntimeApi, Determinism,
+	ExecReturnValue, StorageDeposit,
+};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{traits::Block as BlockT, DispatchError, Weight};
+
+/// A contract call to dry-run.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CallRequest<AccountId> {
+	/// The caller.
+	pub origin: AccountId,
+	/// The contract to call.
+	pub dest: AccountId,
+	/// The value transferred to the contract.
+	pub value: NumberOrHex,
+	/// The gas limit, or the maximum block weight if omitted.
+	pub gas_limit: Option<Weight>,
+	/// The storage deposit limit, or no limit if omitted.
+	pub storage_deposit_limit: Option<NumberOrHex>,
+	/// The SCALE-encoded selector and arguments.
+	pub input_data: Bytes,
+}
+
+/// A contract instantiation to dry-run.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Hash> {
+	/// The caller.
+	pub origin: AccountId,
+	/// The value transferred to the new contract.
+	pub value: NumberOrHex,
+	/// The gas limit, or the maximum block weight if omitted.
+	pub gas_limit: Option<Weight>,
+	/// The storage deposit limit, or no limit if omitted.
+	pub storage_deposit_limit: Option<NumberOrHex>,
+	/// The code to instantiate.
+	pub code: CodeRequest<Hash>,
+	/// The SCALE-encoded constructor selector and arguments.
+	pub data: Bytes,
+	/// The salt of the new contract's address.
+	pub salt: Bytes,
+}
+
+/// The code of a contract to instantiate.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeRequest<Hash> {
+	/// A Wasm blob to upload along with the instantiation.
+	Upload(Bytes),
+	/// The hash of code already on chain.
+	Existing(Hash),
+}
+
+/// A code upload to dry-run.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CodeUploadRequest<AccountId> {
+	/// The uploader.
+	pub origin: AccountId,
+	/// The Wasm blob.
+	pub code: Bytes,
+	/// The storage deposit limit, or no limit if omitted.
+	pub storage_deposit_limit: Option<NumberOrHex>,
+	/// Whether the code may use indeterministic instructions.
+	#[serde(default)]
+	pub determinism: CodeDeterminism,
+}
+
+/// The determinism required of uploaded code.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeDeterminism {
+	/// Only deterministic instructions are allowed, as for code uploaded on chain.
+	#[default]
+	Enforced,
+	/// Indeterministic instructions are allowed, for code that is only ever dry-run.
+	Relaxed,
+}
+
+impl From<CodeDeterminism> for Determinism {
+	fn from(determinism: CodeDeterminism) -> Self {
+		match determinism {
+			CodeDeterminism::Enforced => Determinism::Enforced,
+			CodeDeterminism::Relaxed => Determinism::Relaxed,
+		}
+	}
+}
+
+/// The outcome of a dry-run call or instantiation.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecResponse<R> {
+	/// The weight consumed by the execution.
+	pub gas_consumed: Weight,
+	/// The gas limit needed for the execution to succeed on chain.
+	pub gas_required: Weight,
+	/// The storage deposit charged or refunded by the execution.
+	pub storage_deposit: StorageDepositResponse,
+	/// The debug buffer of the contract, decoded as UTF-8.
+	pub debug_message: String,
+	/// The returned value, or the error that aborted the execution.
+	pub result: Result<R, DispatchError>,
+}
+
+/// A storage deposit charged to or refunded to the origin.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageDepositResponse {
+	/// The deposit refunded to the origin.
+	Refund(NumberOrHex),
+	/// The deposit charged to the origin.
+	Charge(NumberOrHex),
+}
+
+/// The value returned by a contract.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnValue {
+	/// The raw return flags.
+	pub flags: u32,
+	/// Whether the contract reverted its state changes.
+	pub reverted: bool,
+	/// The SCALE-encoded return value.
+	pub data: Bytes,
+}
+
+impl From<ExecReturnValue> for ReturnValue {
+	fn from(value: ExecReturnValue) -> Self {
+		Self { flags: value.flags.bits(), reverted: value.did_revert(), data: value.data.into() }
+	}
+}
+
+/// The value returned by a contract constructor.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiateReturnValue<AccountId> {
+	/// The value returned by the constructor.
+	#[serde(flatten)]
+	pub result: ReturnValue,
+	/// The address of the new contract.
+	pub account_id: AccountId,
+}
+
+/// The outcome of a dry-run code upload.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeUploadResponse<Hash> {
+	/// The hash of the uploaded code.
+	pub code_hash: Hash,
+	/// The deposit reserved for the code.
+	pub deposit: NumberOrHex,
+}
+
+/// Contracts RPC methods.
+#[rpc(server)]
+pub trait ContractsApi<BlockHash, AccountId, Hash> {
+	/// Dry-runs a call to a contract.
+	///
+	/// Nothing is persisted, which makes this useful for reading a contract's state and for
+	/// finding the gas limit and storage deposit limit of an on-chain call.
+	#[method(name = "contracts_call")]
+	fn call(
+		&self,
+		request: CallRequest<AccountId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<ExecResponse<ReturnValue>>;
+
+	/// Dry-runs the instantiation of a contract.
+	#[method(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		request: InstantiateRequest<AccountId, Hash>,
+		at: Option<BlockHash>,
+	) -> RpcResult<ExecResponse<InstantiateReturnValue<AccountId>>>;
+
+	/// Dry-runs the upload of contract code.
+	#[method(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		request: CodeUploadRequest<AccountId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Result<CodeUploadResponse<Hash>, DispatchError>>;
+
+	/// Reads the value under a key of a contract's storage, if any.
+	#[method(name = "contracts_getStorage")]
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Bytes>>;
+}
+
+/// Serves the `contracts_*` RPC namespace.
+pub struct Contracts<C, Block, Balance, BlockNumber, EventRecord> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance, BlockNumber, EventRecord)>,
+}
+
+impl<C, Block, Balance, BlockNumber, EventRecord>
+	Contracts<C, Block, Balance, BlockNumber, EventRecord>
+{
+	/// Creates a new instance of the Contracts RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The contract does not exist or its storage cannot be read.
+	ContractAccessError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::ContractAccessError => 2,
+		}
+	}
+}
+
+fn runtime_error(error: impl ToString) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to dry-run the contract.",
+		Some(error.to_string()),
+	)
+}
+
+fn balance<Balance: TryFrom<NumberOrHex>>(value: NumberOrHex) -> RpcResult<Balance> {
+	value.try_into().map_err(|_| {
+		ErrorObject::owned(
+			ErrorCode::InvalidParams.code(),
+			format!("{value:?} doesn't fit in a balance"),
+			None::<()>,
+		)
+	})
+}
+
+fn exec_response<R, T, Balance: Into<NumberOrHex>, EventRecord>(
+	result: ContractResult<Result<R, DispatchError>, Balance, EventRecord>,
+	map: impl FnOnce(R) -> T,
+) -> ExecResponse<T> {
+	ExecResponse {
+		gas_consumed: result.gas_consumed,
+		gas_required: result.gas_required,
+		storage_deposit: match result.storage_deposit {
+			StorageDeposit::Refund(amount) => StorageDepositResponse::Refund(amount.into()),
+			StorageDeposit::Charge(amount) => StorageDepositResponse::Charge(amount.into()),
+		},
+		debug_message: String::from_utf8_lossy(&result.debug_message).into_owned(),
+		result: result.result.map(map),
+	}
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber, Hash, EventRecord>
+	ContractsApiServer<<Block as BlockT>::Hash, AccountId, Hash>
+	for Contracts<C, Block, Balance, BlockNumber, EventRecord>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash, EventRecord>,
+	AccountId: Codec + Clone + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+	Balance: Codec + TryFrom<NumberOrHex> + Into<NumberOrHex> + Send + Sync + 'static,
+	BlockNumber: Codec + Send + Sync + 'static,
+	Hash: Codec + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+	EventRecord: Codec + Send + Sync + 'static,
+{
+	fn call(
+		&self,
+		request: CallRequest<AccountId>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<ExecResponse<ReturnValue>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let CallRequest { origin, dest, value, gas_limit, storage_deposit_limit, input_data } =
+			request;
+		let storage_deposit_limit = storage_deposit_limit.map(balance).transpose()?;
+		let result = api
+			.call(
+				at_hash,
+				origin,
+				dest,
+				balance(value)?,
+				gas_limit,
+				storage_deposit_limit,
+				input_data.to_vec(),
+			)
+			.map_err(runtime_error)?;
+
+		Ok(exec_response(result, ReturnValue::from))
+	}
+
+	fn instantiate(
+		&self,
+		request: InstantiateRequest<AccountId, Hash>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<ExecResponse<InstantiateReturnValue<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let InstantiateRequest {
+			origin,
+			value,
+			gas_limit,
+			storage_deposit_limit,
+			code,
+			data,
+			salt,
+		} = request;
+		let code = match code {
+			CodeRequest::Upload(code) => Code::Upload(code.to_vec()),
+			CodeRequest::Existing(hash) => Code::Existing(hash),
+		};
+		let storage_deposit_limit = storage_deposit_limit.map(balance).transpose()?;
+		let result = api
+			.instantiate(
+				at_hash,
+				origin,
+				balance(value)?,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data.to_vec(),
+				salt.to_vec(),
+			)
+			.map_err(runtime_error)?;
+
+		Ok(exec_response(result, |value| InstantiateReturnValue {
+			result: value.result.into(),
+			account_id: value.account_id,
+		}))
+	}
+
+	fn upload_code(
+		&self,
+		request: CodeUploadRequest<AccountId>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Result<CodeUploadResponse<Hash>, DispatchError>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let CodeUploadRequest { origin, code, storage_deposit_limit, determinism } = request;
+		let storage_deposit_limit = storage_deposit_limit.map(balance).transpose()?;
+		let result = api
+			.upload_code(at_hash, origin, code.to_vec(), storage_deposit_limit, determinism.into())
+			.map_err(runtime_error)?;
+
+		Ok(result.map(|value| CodeUploadResponse {
+			code_hash: value.code_hash,
+			deposit: value.deposit.into(),
+		}))
+	}
+
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let value = api
+			.get_storage(at_hash, address, key.to_vec())
+			.map_err(|e| {
+				ErrorObject::owned(
+					Error::RuntimeError.into(),
+					"Unable to read contract storage.",
+					Some(e.to_string()),
+				)
+			})?
+			.map_err(|e| {
+				let reason = match e {
+					ContractAccessError::DoesntExist => "The contract does not exist.",
+					ContractAccessError::KeyDecodingFailed => "The key is not a valid storage key.",
+					ContractAccessError::MigrationInProgress =>
+						"Contracts storage is being migrated.",
+				};
+				ErrorObject::owned(Error::ContractAccessError.into(), reason, None::<()>)
+			})?;
+
+		Ok(value.map(Into::into))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Decode, Encode};
+	use serde_json::json;
+	use sp_core::H256;
+	use sp_runtime::{ModuleError, TokenError};
+
+	type ContractResultOf<T> = ContractResult<Result<T, DispatchError>, u128, ()>;
+
+	fn contract_result<T>(
+		debug_message: &[u8],
+		result: Result<T, DispatchError>,
+	) -> ContractResultOf<T> {
+		ContractResult {
+			gas_consumed: Weight::from_parts(1_000, 10),
+			gas_required: Weight::from_parts(2_000, 20),
+			storage_deposit: StorageDeposit::Charge(1 << 64),
+			debug_message: debug_message.to_vec(),
+			result,
+			events: None,
+		}
+	}
+
+	/// A value returned by a contract with the given raw flags.
+	fn returned(flags: u32, data: &[u8]) -> ExecReturnValue {
+		ExecReturnValue::decode(&mut &(flags, data.to_vec()).encode()[..]).unwrap()
+	}
+
+	#[test]
+	fn call_requests_are_read_from_camel_case_json() {
+		let request: CallRequest<u64> = serde_json::from_value(json!({
+			"origin": 1,
+			"dest": 2,
+			"value": "0x10",
+			"storageDepositLimit": 5,
+			"inputData": "0x0102",
+		}))
+		.unwrap();
+		assert_eq!((request.origin, request.dest), (1, 2));
+		assert_eq!(balance::<u128>(request.value).unwrap(), 16);
+		assert_eq!(request.gas_limit, None);
+		assert_eq!(request.storage_deposit_limit, Some(NumberOrHex::Number(5)));
+		assert_eq!(request.input_data, Bytes(vec![1, 2]));
+
+		let unknown_field = serde_json::from_value::<CallRequest<u64>>(json!({
+			"origin": 1,
+			"dest": 2,
+			"value": 0,
+			"inputData": "0x",
+			"gas": 1,
+		}));
+		assert!(unknown_field.is_err());
+	}
+
+	#[test]
+	fn instantiate_and_upload_requests_are_read_from_json() {
+		let request: InstantiateRequest<u64, H256> = serde_json::from_value(json!({
+			"origin": 1,
+			"value": 0,
+			"gasLimit": { "ref_time": 3, "proof_size": 4 },
+			"code": { "existing": H256::repeat_byte(7) },
+			"data": "0x00",
+			"salt": "0x",
+		}))
+		.unwrap();
+		assert_eq!(request.gas_limit, Some(Weight::from_parts(3, 4)));
+		assert!(
+			matches!(request.code, CodeRequest::Existing(hash) if hash == H256::repeat_byte(7))
+		);
+
+		let request: CodeUploadRequest<u64> =
+			serde_json::from_value(json!({ "origin": 1, "code": "0x0061736d" })).unwrap();
+		assert!(matches!(request.determinism, CodeDeterminism::Enforced));
+		let request: CodeUploadRequest<u64> = serde_json::from_value(json!({
+			"origin": 1,
+			"code": "0x0061736d",
+			"determinism": "relaxed",
+		}))
+		.unwrap();
+		assert!(matches!(request.determinism, CodeDeterminism::Relaxed));
+	}
+
+	#[test]
+	fn call_responses_decode_the_debug_message_lossily() {
+		let response = exec_response(
+			contract_result(b"panicked: \xff", Ok(returned(1, &[0x2a]))),
+			ReturnValue::from,
+		);
+		assert_eq!(
+			serde_json::to_value(&response).unwrap(),
+			json!({
+				"gasConsumed": { "ref_time": 1_000, "proof_size": 10 },
+				"gasRequired": { "ref_time": 2_000, "proof_size": 20 },
+				"storageDeposit": { "charge": "0x10000000000000000" },
+				"debugMessage": "panicked: \u{fffd}",
+				"result": { "Ok": { "flags": 1, "reverted": true, "data": "0x2a" } },
+			})
+		);
+	}
+
+	#[test]
+	fn failed_executions_report_the_dispatch_error() {
+		let module_error = DispatchError::Module(ModuleError {
+			index: 40,
+			error: [7, 0, 0, 0],
+			message: Some("CodeNotFound"),
+		});
+		let response = exec_response(
+			contract_result::<ExecReturnValue>(b"", Err(module_error)),
+			ReturnValue::from,
+		);
+		assert_eq!(
+			serde_json::to_value(&response.result).unwrap(),
+			json!({
+				"Err": {
+					"Module": { "index": 40, "error": [7, 0, 0, 0], "message": "CodeNotFound" },
+				},
+			})
+		);
+		assert_eq!(response.debug_message, "");
+
+		let token_error = DispatchError::Token(TokenError::FundsUnavailable);
+		let response = exec_response(
+			contract_result::<ExecReturnValue>(b"", Err(token_error)),
+			ReturnValue::from,
+		);
+		assert_eq!(
+			serde_json::to_value(&response.result).unwrap(),
+			json!({ "Err": { "Token": "FundsUnavailable" } })
+		);
+	}
+
+	#[test]
+	fn instantiate_responses_flatten_the_returned_value() {
+		let response = ExecResponse {
+			gas_consumed: Weight::zero(),
+			gas_required: Weight::zero(),
+			storage_deposit: StorageDepositResponse::Refund(5u128.into()),
+			debug_message: String::new(),
+			result: Ok(InstantiateReturnValue {
+				result: returned(0, &[]).into(),
+				account_id: 9u64,
+			}),
+		};
+		let json = serde_json::to_value(&response).unwrap();
+		assert_eq!(json["storageDeposit"], json!({ "refund": 5 }));
+		assert_eq!(
+			json["result"],
+			json!({ "Ok": { "flags": 0, "reverted": false, "data": "0x", "accountId": 9 } })
+		);
+	}
+
+	#[test]
+	fn code_upload_responses_serialize_to_json() {
+		let uploaded: Result<CodeUploadResponse<H256>, DispatchError> =
+			Ok(CodeUploadResponse { code_hash: H256::repeat_byte(1), deposit: 7u128.into() });
+		assert_eq!(
+			serde_json::to_value(&uploaded).unwrap(),
+			json!({ "Ok": { "codeHash": H256::repeat_byte(1), "deposit": 7 } })
+		);
+
+		let rejected: Result<CodeUploadResponse<H256>, DispatchError> =
+			Err(DispatchError::BadOrigin);
+		assert_eq!(serde_json::to_value(&rejected).unwrap(), json!({ "Err": "BadOrigin" }));
+	}
+}
@@ -1,29 +1,48 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use futures::FutureExt;
+use futures::{channel::mpsc, FutureExt, SinkExt, StreamExt};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::{
+	consensus::{babe::BabeConsensusDataProvider, timestamp::SlotTimestampProvider},
+	rpc::{ManualSeal, ManualSealApiServer},
+	EngineCommand, ManualSealParams,
+};
+use sc_network::{Event, NetworkEventStream};
+use sc_consensus_beefy::communication::{
+	beefy_peers_set_config, gossip_protocol_name,
+	request_response::BeefyJustifsRequestHandler,
+};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncConfig};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
+use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
 use kora_chain_runtime::{self, apis::RuntimeApi, opaque::Block};
 
 use std::{sync::Arc, time::Duration};
-use crate::rpc::{create_full, BabeDeps, FullDeps, GrandpaDeps};
+use crate::{
+	cli::Sealing,
+	eth_rpc::{self, EthRpcConfig},
+	rpc::{create_full, BabeDeps, BeefyDeps, FullDeps, GrandpaDeps},
+};
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
 const GRANDPA_JUSTIFICATION_PERIOD: u32 = 512;
 
+/// The minimum number of blocks between two blocks BEEFY votes on.
+const BEEFY_MIN_BLOCK_DELTA: u32 = 8;
+
 /// Host functions required for runtime and Substrate node.
 #[cfg(not(feature = "runtime-benchmarks"))]
-pub type HostFunctions = sp_io::SubstrateHostFunctions;
+pub type HostFunctions = (sp_io::SubstrateHostFunctions, kora_zk_crypto::HostFunctions);
 
 /// Host functions required for runtime and Substrate node.
 #[cfg(feature = "runtime-benchmarks")]
 pub type HostFunctions = (
 	sp_io::SubstrateHostFunctions,
+	kora_zk_crypto::HostFunctions,
 	frame_benchmarking::benchmarking::HostFunctions,
 );
 
@@ -35,6 +54,13 @@ type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 type FullGrandpaBlockImport =
 	sc_consensus_grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
+type FullBeefyBlockImport<InnerBlockImport> = sc_consensus_beefy::import::BeefyBlockImport<
+	Block,
+	FullBackend,
+	FullClient,
+	InnerBlockImport,
+	BeefyId,
+>;
 
 pub fn new_partial(config: &Configuration) -> Result<
 	sc_service::PartialComponents<
@@ -52,10 +78,15 @@ pub fn new_partial(config: &Configuration) -> Result<
 					+ Sync,
 			>,
 			(
-				sc_consensus_babe::BabeBlockImport<Block, FullClient, FullGrandpaBlockImport>,
+				sc_consensus_babe::BabeBlockImport<
+					Block,
+					FullClient,
+					FullBeefyBlockImport<FullGrandpaBlockImport>,
+				>,
 				sc_consensus_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 				sc_consensus_babe::BabeLink<Block>,
 				sc_consensus_babe::BabeWorkerHandle<Block>,
+				sc_consensus_beefy::BeefyVoterLinks<Block, BeefyId>,
 			),
 			SharedVoterState,
 			Option<Telemetry>,
@@ -111,9 +142,17 @@ pub fn new_partial(config: &Configuration) -> Result<
 	)?;
 	let justification_import = grandpa_block_import.clone();
 
+	let (beefy_block_import, beefy_voter_links, beefy_rpc_links) =
+		sc_consensus_beefy::beefy_block_import_and_links(
+			grandpa_block_import,
+			backend.clone(),
+			client.clone(),
+			config.prometheus_registry().cloned(),
+		);
+
 	let (block_import, babe_link) = sc_consensus_babe::block_import(
 		sc_consensus_babe::configuration(&*client)?,
-		grandpa_block_import,
+		beefy_block_import,
 		client.clone(),
 	)?;
 
@@ -142,10 +181,11 @@ pub fn new_partial(config: &Configuration) -> Result<
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
 		})?;
 
-	let import_setup = (block_import, grandpa_link, babe_link, babe_worker_handle.clone());
+	let import_setup =
+		(block_import, grandpa_link, babe_link, babe_worker_handle.clone(), beefy_voter_links);
 
 	let (rpc_extensions_builder, rpc_setup) = {
-		let (_, grandpa_link, _, _) = &import_setup;
+		let (_, grandpa_link, _, _, _) = &import_setup;
 		let justification_stream = grandpa_link.justification_stream();
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let shared_voter_state = SharedVoterState::empty();
@@ -178,9 +218,20 @@ pub fn new_partial(config: &Configuration) -> Result<
 						shared_voter_state: shared_voter_state.clone(),
 						shared_authority_set: shared_authority_set.clone(),
 						justification_stream: justification_stream.clone(),
-						subscription_executor: sc_rpc::SubscriptionTaskExecutor::from(spawn_handle),
+						subscription_executor: sc_rpc::SubscriptionTaskExecutor::from(
+							spawn_handle.clone(),
+						),
 						finality_provider: finality_proof_provider.clone(),
 					},
+					beefy: BeefyDeps::<BeefyId> {
+						beefy_finality_proof_stream: beefy_rpc_links
+							.from_voter_justif_stream
+							.clone(),
+						beefy_best_block_stream: beefy_rpc_links
+							.from_voter_best_beefy_stream
+							.clone(),
+						subscription_executor: sc_rpc::SubscriptionTaskExecutor::from(spawn_handle),
+					},
 					backend: rpc_backend.clone(),
 				};
 
@@ -207,6 +258,8 @@ pub fn new_full<
 	N: sc_network::NetworkBackend<Block, <Block as sp_runtime::traits::Block>::Hash>,
 >(
 	config: Configuration,
+	eth_rpc: Option<EthRpcConfig>,
+	sealing: Option<Sealing>,
 ) -> Result<TaskManager, ServiceError> {
 	let role = config.role;
 	let force_authoring = config.force_authoring;
@@ -216,6 +269,7 @@ pub fn new_full<
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
+	let enable_offchain_indexing = config.offchain_worker.indexing_enabled;
 
 	let sc_service::PartialComponents {
 		client,
@@ -250,6 +304,24 @@ pub fn new_full<
 		);
 	net_config.add_notification_protocol(grandpa_protocol_config);
 
+	let beefy_gossip_protocol_name =
+		gossip_protocol_name(&genesis_hash, config.chain_spec.fork_id());
+	let (beefy_notification_config, beefy_notification_service) =
+		beefy_peers_set_config::<_, N>(
+			beefy_gossip_protocol_name.clone(),
+			metrics.clone(),
+			Arc::clone(&peer_store_handle),
+		);
+	net_config.add_notification_protocol(beefy_notification_config);
+	let (beefy_justifications_handler, beefy_justifications_config) =
+		BeefyJustifsRequestHandler::new::<_, N>(
+			&genesis_hash,
+			config.chain_spec.fork_id(),
+			client.clone(),
+			prometheus_registry.clone(),
+		);
+	net_config.add_request_response_protocol(beefy_justifications_config);
+
 	let warp_sync = Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
 		backend.clone(),
 		import_setup.1.shared_authority_set().clone(),
@@ -270,6 +342,20 @@ pub fn new_full<
 			metrics,
 		})?;
 
+	// Commands of the manual-seal authorship task, sent through `engine_*` RPC among others.
+	let (mut command_sink, commands_stream) = mpsc::channel(1024);
+	let rpc_builder = {
+		let command_sink = sealing.map(|_| command_sink.clone());
+		move |spawn_handle| -> Result<_, ServiceError> {
+			let mut io = rpc_builder(spawn_handle)?;
+			if let Some(command_sink) = &command_sink {
+				io.merge(ManualSeal::new(command_sink.clone()).into_rpc())
+					.map_err(|e| ServiceError::Application(e.into()))?;
+			}
+			Ok(io)
+		}
+	};
+
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -285,9 +371,96 @@ pub fn new_full<
 		telemetry: telemetry.as_mut(),
 	})?;
 
-	let (block_import, grandpa_link, babe_link, _babe_worker_handle) = import_setup;
+	if let Some(eth_rpc) = eth_rpc {
+		eth_rpc::spawn(eth_rpc, &task_manager);
+	}
+
+	let (block_import, grandpa_link, babe_link, _babe_worker_handle, beefy_voter_links) =
+		import_setup;
+
+	if let Some(sealing) = sealing {
+		let proposer = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		// Blocks still carry BABE pre-digests and slot-aligned timestamps, so the runtime cannot
+		// tell them from blocks authored by BABE.
+		let consensus_data_provider = BabeConsensusDataProvider::new(
+			client.clone(),
+			keystore_container.keystore(),
+			babe_link.epoch_changes().clone(),
+			sc_consensus_babe::configuration(&*client)?.authorities,
+		)
+		.map_err(|e| ServiceError::Other(e.to_string()))?;
+
+		let seal_and_finalize = || EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		};
+		match sealing {
+			Sealing::Instant => {
+				let mut imports = transaction_pool.import_notification_stream();
+				task_manager.spawn_handle().spawn(
+					"instant-seal",
+					Some("block-authoring"),
+					async move {
+						while imports.next().await.is_some() {
+							if command_sink.send(seal_and_finalize()).await.is_err() {
+								break;
+							}
+						}
+					},
+				);
+			},
+			Sealing::Interval(millis) => {
+				task_manager.spawn_handle().spawn(
+					"interval-seal",
+					Some("block-authoring"),
+					async move {
+						loop {
+							futures_timer::Delay::new(Duration::from_millis(millis)).await;
+							if command_sink.send(seal_and_finalize()).await.is_err() {
+								break;
+							}
+						}
+					},
+				);
+			},
+			Sealing::Manual => {},
+		}
+
+		let client_clone = client.clone();
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import,
+			env: proposer,
+			client: client.clone(),
+			pool: transaction_pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(consensus_data_provider)),
+			create_inherent_data_providers: move |_parent, ()| {
+				let client = client_clone.clone();
+				async move {
+					let timestamp = SlotTimestampProvider::new_babe(client)?;
+					let slot =
+						sp_consensus_babe::inherents::InherentDataProvider::new(timestamp.slot());
 
-	if let sc_service::config::Role::Authority { .. } = &role {
+					Ok::<_, Box<dyn std::error::Error + Send + Sync>>((slot, timestamp))
+				}
+			},
+		});
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"manual-seal",
+			Some("block-authoring"),
+			manual_seal,
+		);
+	} else if let sc_service::config::Role::Authority { .. } = &role {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
@@ -336,7 +509,58 @@ pub fn new_full<
 		);
 	}
 
-	// Note: Authority discovery is not included in this minimal BABE implementation
+	if role.is_authority() {
+		// Publish the addresses of this validator in the DHT under its authority discovery key,
+		// and look up those of the other validators, so they do not only meet through bootnodes.
+		let dht_event_stream =
+			network.event_stream("authority-discovery").filter_map(|e| async move {
+				match e {
+					Event::Dht(e) => Some(e),
+					_ => None,
+				}
+			});
+		let (authority_discovery_worker, _service) = sc_authority_discovery::new_worker_and_service(
+			client.clone(),
+			Arc::new(network.clone()),
+			Box::pin(dht_event_stream),
+			sc_authority_discovery::Role::PublishAndDiscover(keystore_container.keystore()),
+			prometheus_registry.clone(),
+		);
+		task_manager.spawn_handle().spawn(
+			"authority-discovery-worker",
+			Some("networking"),
+			authority_discovery_worker.run(),
+		);
+	}
+
+	// BEEFY signs the MMR root of GRANDPA-finalized blocks, so light clients such as bridges can
+	// follow finality with one signature check per authority rather than GRANDPA justifications.
+	let beefy_params = sc_consensus_beefy::BeefyParams {
+		client: client.clone(),
+		backend: backend.clone(),
+		payload_provider: sp_consensus_beefy::mmr::MmrRootProvider::new(client.clone()),
+		runtime: client.clone(),
+		key_store: Some(keystore_container.keystore()),
+		network_params: sc_consensus_beefy::BeefyNetworkParams {
+			network: Arc::new(network.clone()),
+			sync: sync_service.clone(),
+			gossip_protocol_name: beefy_gossip_protocol_name,
+			justifications_protocol_name: beefy_justifications_handler.protocol_name(),
+			notification_service: beefy_notification_service,
+			_phantom: core::marker::PhantomData::<Block>,
+		},
+		min_block_delta: BEEFY_MIN_BLOCK_DELTA,
+		prometheus_registry: prometheus_registry.clone(),
+		links: beefy_voter_links,
+		on_demand_justifications_handler: beefy_justifications_handler,
+		is_authority: role.is_authority(),
+	};
+	// BEEFY is part of consensus: if it fails, take the node down so that it gets noticed.
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"beefy-gadget",
+		None,
+		sc_consensus_beefy::start_beefy_gadget::<_, _, _, _, _, _, _, BeefyId>(beefy_params),
+	);
 
 	let grandpa_config = sc_consensus_grandpa::Config {
 		gossip_duration: Duration::from_millis(333),
@@ -349,7 +573,7 @@ pub fn new_full<
 		protocol_name: grandpa_protocol_name,
 	};
 
-	if enable_grandpa {
+	if enable_grandpa && sealing.is_none() {
 		// start the full GRANDPA voter
 		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
 		// this point the full voter should provide better guarantees of block
@@ -378,6 +602,20 @@ pub fn new_full<
 		);
 	}
 
+	// MMR proofs of finalized blocks are served from the offchain database, which only gets the
+	// MMR nodes when offchain indexing is enabled.
+	if enable_offchain_indexing {
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"mmr-gadget",
+			None,
+			mmr_gadget::MmrGadget::start(
+				client.clone(),
+				backend.clone(),
+				sp_mmr_primitives::INDEXING_PREFIX.to_vec(),
+			),
+		);
+	}
+
 	if enable_offchain_worker {
 		let offchain_workers =
 			sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
@@ -0,0 +1,393 @@
+//! # Shielded Pool Pallet
+//!
+//! A pool of native and asset balances whose withdrawals cannot be linked to their deposits.
+//!
+//! ## Overview
+//!
+//! Depositors lock funds in the pool account together with a note commitment, which is appended
+//! to an incremental Merkle tree. Anyone knowing the secrets behind a note can later withdraw it
+//! to any account by proving, in zero knowledge, that the note is in the tree:
+//!
+//! - [`Pallet::deposit`] transfers `amount` of an [`Asset`] to the pool and inserts the leaf
+//!   `Poseidon(commitment, asset, amount)`, so a note can only be withdrawn for what was
+//!   deposited. The commitment is computed off-chain, typically as `Poseidon(nullifier, secret)`.
+//! - [`Pallet::withdraw`] checks a proof against the key [`Config::WithdrawKey`] in
+//!   [`Config::ProofVerifier`], marks its nullifier hash as spent and pays the note out. The
+//!   public inputs of the withdraw circuit are, in order:
+//!
+//!   1. a root of the tree among the last [`Config::RootHistorySize`] ones;
+//!   2. the nullifier hash of the note;
+//!   3. the asset, see [`asset_scalar`];
+//!   4. the amount as a 128-bit integer;
+//!   5. the recipient, see [`account_scalar`].
+//!
+//! The tree and its hashes follow circomlib, with zero leaves and `Poseidon(left, right)` nodes,
+//! so the `MerkleTreeChecker` templates can be used as-is. Keeping a history of roots lets
+//! proofs made against a slightly stale root still be accepted.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The FRAME macros clone `Copy` fields and convert dispatch results into their own type.
+#![allow(clippy::clone_on_copy, clippy::useless_conversion)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+mod zeros;
+pub use zeros::ZERO_HASHES;
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::traits::tokens::{fungible, fungibles};
+use kora_zk_hash::{poseidon, truncate_to_scalar, Scalar};
+use pallet_zk_verifier::{ProofVerifier, ProvingSystem, VkId};
+use scale_info::TypeInfo;
+use sp_runtime::{RuntimeDebug, SaturatedConversion};
+
+/// The number of public inputs of the withdraw circuit.
+pub const WITHDRAW_PUBLIC_INPUTS: u32 = 5;
+
+/// A balance the pool can hold.
+#[derive(
+	Clone,
+	Copy,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum Asset<AssetId> {
+	/// The native currency.
+	Native,
+	/// An asset of [`Config::Assets`].
+	Fungible(AssetId),
+}
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub type AssetIdOf<T> = <<T as Config>::Assets as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::AssetId;
+pub type AssetOf<T> = Asset<AssetIdOf<T>>;
+pub type EncodedProofOf<T> = frame_support::BoundedVec<u8, <T as Config>::MaxProofLen>;
+
+/// The scalar an asset is bound to in circuits: the Blake2-256 hash of its SCALE encoding with
+/// the top byte cleared.
+pub fn asset_scalar<AssetId: Encode>(asset: &Asset<AssetId>) -> Scalar {
+	truncate_to_scalar(sp_io::hashing::blake2_256(&asset.encode()))
+}
+
+/// The scalar an account is bound to in circuits: the Blake2-256 hash of its SCALE encoding with
+/// the top byte cleared.
+pub fn account_scalar<AccountId: Encode>(account: &AccountId) -> Scalar {
+	truncate_to_scalar(sp_io::hashing::blake2_256(&account.encode()))
+}
+
+/// The scalar of an amount, which must fit in 128 bits.
+pub fn amount_scalar(amount: u128) -> Scalar {
+	let mut scalar = [0u8; 32];
+	scalar[16..].copy_from_slice(&amount.to_be_bytes());
+	scalar
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::PostDispatchInfo,
+		pallet_prelude::*,
+		traits::tokens::{fungible::Mutate as _, fungibles::Mutate as _, Preservation},
+		PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{AccountIdConversion, Zero};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The pool's id, used to derive the account holding the deposits.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// The native currency.
+		type Currency: fungible::Mutate<Self::AccountId>;
+		/// The assets that can be deposited besides the native currency.
+		type Assets: fungibles::Mutate<Self::AccountId, Balance = BalanceOf<Self>>;
+		/// Verifier of the withdraw proofs.
+		type ProofVerifier: ProofVerifier;
+		/// Key of the withdraw circuit in [`Config::ProofVerifier`].
+		#[pallet::constant]
+		type WithdrawKey: Get<VkId>;
+		/// Depth of the commitment tree, which holds `2^TreeDepth` notes. At most 32.
+		#[pallet::constant]
+		type TreeDepth: Get<u32>;
+		/// Number of recent roots withdrawals may be proven against.
+		#[pallet::constant]
+		type RootHistorySize: Get<u32>;
+		/// Maximum length of an encoded proof in bytes.
+		#[pallet::constant]
+		type MaxProofLen: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Index the next commitment is inserted at.
+	#[pallet::storage]
+	pub type NextLeafIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The last left node hashed at each level of the tree, from the leaves up.
+	#[pallet::storage]
+	pub type FilledSubtrees<T: Config> = StorageMap<_, Twox64Concat, u32, Scalar, ValueQuery>;
+
+	/// The last [`Config::RootHistorySize`] roots, in a ring indexed by [`CurrentRootIndex`].
+	#[pallet::storage]
+	pub type RootHistory<T: Config> = StorageMap<_, Twox64Concat, u32, Scalar>;
+
+	/// Position of the current root in [`RootHistory`].
+	#[pallet::storage]
+	pub type CurrentRootIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The roots in [`RootHistory`], for constant time lookups.
+	#[pallet::storage]
+	pub type KnownRoots<T: Config> = StorageMap<_, Blake2_128Concat, Scalar, ()>;
+
+	/// Nullifier hashes of the withdrawn notes.
+	#[pallet::storage]
+	pub type Nullifiers<T: Config> = StorageMap<_, Blake2_128Concat, Scalar, ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A note was deposited and its leaf inserted at `leaf_index`, making `root` current.
+		Deposited {
+			who: T::AccountId,
+			asset: AssetOf<T>,
+			amount: BalanceOf<T>,
+			commitment: Scalar,
+			leaf_index: u32,
+			root: Scalar,
+		},
+		/// The note with `nullifier_hash` was withdrawn to `recipient`.
+		Withdrawn {
+			nullifier_hash: Scalar,
+			asset: AssetOf<T>,
+			amount: BalanceOf<T>,
+			recipient: T::AccountId,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The amount is zero.
+		ZeroAmount,
+		/// The commitment is not a canonical scalar.
+		InvalidCommitment,
+		/// The tree holds `2^TreeDepth` notes already.
+		TreeFull,
+		/// The root is not among the recent roots of the tree.
+		UnknownRoot,
+		/// The note was already withdrawn.
+		NullifierAlreadyUsed,
+		/// The proof does not prove the withdrawal of the note.
+		InvalidProof,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(
+				(1..=32).contains(&T::TreeDepth::get()),
+				"TreeDepth must be between 1 and 32"
+			);
+			assert!(T::RootHistorySize::get() > 0, "RootHistorySize must not be zero");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Deposit `amount` of `asset` into the pool as a note with `commitment`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::deposit())]
+		pub fn deposit(
+			origin: OriginFor<T>,
+			asset: AssetOf<T>,
+			amount: BalanceOf<T>,
+			commitment: Scalar,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			let leaf =
+				Self::leaf(&commitment, &asset, amount).ok_or(Error::<T>::InvalidCommitment)?;
+
+			Self::transfer(&asset, &who, &Self::account_id(), amount, Preservation::Preserve)?;
+			let (leaf_index, root) = Self::insert(leaf)?;
+			Self::deposit_event(Event::Deposited {
+				who,
+				asset,
+				amount,
+				commitment,
+				leaf_index,
+				root,
+			});
+			Ok(())
+		}
+
+		/// Withdraw the note with `nullifier_hash` holding `amount` of `asset` to `recipient`.
+		///
+		/// `proof` shows that the note is in the tree with `root`. Anyone can submit it on behalf
+		/// of the recipient, which is bound to the proof.
+		#[pallet::call_index(1)]
+		#[pallet::weight(Pallet::<T>::withdraw_weight(None))]
+		pub fn withdraw(
+			origin: OriginFor<T>,
+			proof: EncodedProofOf<T>,
+			root: Scalar,
+			nullifier_hash: Scalar,
+			asset: AssetOf<T>,
+			amount: BalanceOf<T>,
+			recipient: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(KnownRoots::<T>::contains_key(root), Error::<T>::UnknownRoot);
+			ensure!(
+				!Nullifiers::<T>::contains_key(nullifier_hash),
+				Error::<T>::NullifierAlreadyUsed
+			);
+
+			let inputs = [
+				root,
+				nullifier_hash,
+				asset_scalar(&asset),
+				amount_scalar(amount.saturated_into()),
+				account_scalar(&recipient),
+			];
+			ensure!(
+				T::ProofVerifier::verify(T::WithdrawKey::get(), &proof, &inputs)?,
+				Error::<T>::InvalidProof
+			);
+
+			Nullifiers::<T>::insert(nullifier_hash, ());
+			Self::transfer(
+				&asset,
+				&Self::account_id(),
+				&recipient,
+				amount,
+				Preservation::Expendable,
+			)?;
+			Self::deposit_event(Event::Withdrawn { nullifier_hash, asset, amount, recipient });
+			let system = T::ProofVerifier::proving_system(T::WithdrawKey::get());
+			Ok(PostDispatchInfo::from(Some(Self::withdraw_weight(system))))
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Weight of [`Pallet::withdraw`] with a key of `system`, or of any proving system.
+		pub fn withdraw_weight(system: Option<ProvingSystem>) -> Weight {
+			let verify = match system {
+				Some(system) =>
+					T::ProofVerifier::verify_weight(system, WITHDRAW_PUBLIC_INPUTS),
+				None => T::ProofVerifier::max_verify_weight(WITHDRAW_PUBLIC_INPUTS),
+			};
+			T::WeightInfo::withdraw().saturating_add(verify)
+		}
+
+		/// The account holding the deposits.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// The current root of the tree, if any note was deposited.
+		pub fn current_root() -> Option<Scalar> {
+			RootHistory::<T>::get(CurrentRootIndex::<T>::get())
+		}
+
+		/// Whether `root` is among the roots withdrawals may be proven against.
+		pub fn is_known_root(root: &Scalar) -> bool {
+			KnownRoots::<T>::contains_key(root)
+		}
+
+		/// Whether the note with `nullifier_hash` was withdrawn.
+		pub fn is_spent(nullifier_hash: &Scalar) -> bool {
+			Nullifiers::<T>::contains_key(nullifier_hash)
+		}
+
+		/// The leaf of a note, or `None` if `commitment` is not a canonical scalar.
+		pub fn leaf(
+			commitment: &Scalar,
+			asset: &AssetOf<T>,
+			amount: BalanceOf<T>,
+		) -> Option<Scalar> {
+			poseidon::hash_scalars(&[
+				*commitment,
+				asset_scalar(asset),
+				amount_scalar(amount.saturated_into()),
+			])
+		}
+
+		/// Appends `leaf` to the tree, returning its index and the new root.
+		fn insert(leaf: Scalar) -> Result<(u32, Scalar), DispatchError> {
+			let depth = T::TreeDepth::get();
+			let leaf_index = NextLeafIndex::<T>::get();
+			ensure!((leaf_index as u64) < 1u64 << depth, Error::<T>::TreeFull);
+
+			let mut index = leaf_index;
+			let mut node = leaf;
+			for level in 0..depth {
+				let (left, right) = if index.is_multiple_of(2) {
+					FilledSubtrees::<T>::insert(level, node);
+					(node, ZERO_HASHES[level as usize])
+				} else {
+					(FilledSubtrees::<T>::get(level), node)
+				};
+				node = hash_pair(left, right);
+				index /= 2;
+			}
+
+			let root_index = (CurrentRootIndex::<T>::get() + 1) % T::RootHistorySize::get();
+			if let Some(evicted) = RootHistory::<T>::take(root_index) {
+				KnownRoots::<T>::remove(evicted);
+			}
+			RootHistory::<T>::insert(root_index, node);
+			KnownRoots::<T>::insert(node, ());
+			CurrentRootIndex::<T>::put(root_index);
+			NextLeafIndex::<T>::put(leaf_index + 1);
+			Ok((leaf_index, node))
+		}
+
+		fn transfer(
+			asset: &AssetOf<T>,
+			source: &T::AccountId,
+			dest: &T::AccountId,
+			amount: BalanceOf<T>,
+			preservation: Preservation,
+		) -> DispatchResult {
+			match asset {
+				Asset::Native => T::Currency::transfer(source, dest, amount, preservation),
+				Asset::Fungible(id) =>
+					T::Assets::transfer(id.clone(), source, dest, amount, preservation),
+			}
+			.map(|_| ())
+		}
+	}
+}
+
+/// `Poseidon(left, right)`, a node of the tree.
+fn hash_pair(left: Scalar, right: Scalar) -> Scalar {
+	poseidon::hash_scalars(&[left, right]).expect("nodes are canonical scalars; qed")
+}
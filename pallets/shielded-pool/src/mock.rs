@@ -0,0 +1,140 @@
+use crate as pallet_shielded_pool;
+use core::cell::RefCell;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU32},
+	weights::Weight,
+	PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use pallet_zk_verifier::{ProofVerifier, ProvingSystem, ScalarBytes, VkId};
+use sp_runtime::{BuildStorage, DispatchError};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system::Pallet<Test>;
+
+	#[runtime::pallet_index(1)]
+	pub type Balances = pallet_balances::Pallet<Test>;
+
+	#[runtime::pallet_index(2)]
+	pub type Assets = pallet_assets::Pallet<Test>;
+
+	#[runtime::pallet_index(3)]
+	pub type ShieldedPool = pallet_shielded_pool::Pallet<Test>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+	type ForceOrigin = EnsureRoot<u64>;
+}
+
+/// The only key id [`MockVerifier`] knows.
+pub const VK_ID: VkId = 3;
+/// The asset created at genesis.
+pub const ASSET_ID: u32 = 7;
+
+thread_local! {
+	/// Public inputs of the last proof checked by [`MockVerifier`].
+	pub static LAST_INPUTS: RefCell<Vec<ScalarBytes>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Accepts proofs starting with a `1` byte.
+pub struct MockVerifier;
+
+impl ProofVerifier for MockVerifier {
+	fn verify(
+		vk_id: VkId,
+		proof: &[u8],
+		public_inputs: &[ScalarBytes],
+	) -> Result<bool, DispatchError> {
+		if vk_id != VK_ID {
+			return Err(DispatchError::Other("unknown verification key"));
+		}
+		LAST_INPUTS.with(|last| *last.borrow_mut() = public_inputs.to_vec());
+		Ok(proof.first() == Some(&1))
+	}
+
+	fn verify_weight(_system: ProvingSystem, public_inputs: u32) -> Weight {
+		Weight::from_parts(1_000_000 * (public_inputs as u64 + 1), 0)
+	}
+
+	fn max_verify_weight(public_inputs: u32) -> Weight {
+		Weight::from_parts(2_000_000 * (public_inputs as u64 + 1), 0)
+	}
+
+	fn proving_system(vk_id: VkId) -> Option<ProvingSystem> {
+		(vk_id == VK_ID).then_some(ProvingSystem::Groth16Bn254)
+	}
+}
+
+parameter_types! {
+	pub const ShieldedPoolPalletId: PalletId = PalletId(*b"py/shpol");
+	pub const WithdrawKey: VkId = VK_ID;
+}
+
+impl pallet_shielded_pool::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = ShieldedPoolPalletId;
+	type Currency = Balances;
+	type Assets = Assets;
+	type ProofVerifier = MockVerifier;
+	type WithdrawKey = WithdrawKey;
+	type TreeDepth = ConstU32<4>;
+	type RootHistorySize = ConstU32<3>;
+	type MaxProofLen = ConstU32<256>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 1_000), (2, 1_000), (ShieldedPool::account_id(), 1)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	pallet_assets::GenesisConfig::<Test> {
+		assets: vec![(ASSET_ID, 1, true, 1)],
+		accounts: vec![(ASSET_ID, 1, 1_000)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
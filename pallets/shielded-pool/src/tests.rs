@@ -0,0 +1,276 @@
+use crate::{
+	account_scalar, amount_scalar, asset_scalar, mock::*, Asset, CurrentRootIndex, Error, Event,
+	KnownRoots, NextLeafIndex, Nullifiers, RootHistory, ZERO_HASHES,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungible::Inspect as _,
+};
+use kora_zk_hash::{poseidon, Scalar};
+use pallet_zk_verifier::ProvingSystem;
+use sp_runtime::{DispatchError, TokenError};
+
+const NULLIFIER_HASH: Scalar = [9; 32];
+
+fn commitment(value: u8) -> Scalar {
+	let mut commitment = [0u8; 32];
+	commitment[31] = value;
+	commitment
+}
+
+fn leaf(commitment: Scalar, asset: Asset<u32>, amount: u64) -> Scalar {
+	poseidon::hash_scalars(&[commitment, asset_scalar(&asset), amount_scalar(amount.into())])
+		.unwrap()
+}
+
+/// The root of a tree of depth 4 holding `leaves`, computed level by level.
+fn root_of(leaves: &[Scalar]) -> Scalar {
+	let mut level = leaves.to_vec();
+	level.resize(16, [0; 32]);
+	while level.len() > 1 {
+		level = level
+			.chunks(2)
+			.map(|pair| poseidon::hash_scalars(&[pair[0], pair[1]]).unwrap())
+			.collect();
+	}
+	level[0]
+}
+
+fn proof(valid: bool) -> crate::EncodedProofOf<Test> {
+	vec![valid as u8; 8].try_into().unwrap()
+}
+
+fn deposit(value: u8, asset: Asset<u32>, amount: u64) -> Scalar {
+	assert_ok!(ShieldedPool::deposit(RuntimeOrigin::signed(1), asset, amount, commitment(value)));
+	ShieldedPool::current_root().unwrap()
+}
+
+#[test]
+fn deposit_inserts_note() {
+	new_test_ext().execute_with(|| {
+		let pool = ShieldedPool::account_id();
+		assert_eq!(ShieldedPool::current_root(), None);
+
+		assert_ok!(ShieldedPool::deposit(
+			RuntimeOrigin::signed(1),
+			Asset::Native,
+			100,
+			commitment(1)
+		));
+		assert_eq!(Balances::balance(&1), 900);
+		assert_eq!(Balances::balance(&pool), 101);
+		let root = root_of(&[leaf(commitment(1), Asset::Native, 100)]);
+		assert_eq!(ShieldedPool::current_root(), Some(root));
+		assert!(ShieldedPool::is_known_root(&root));
+		System::assert_last_event(
+			Event::Deposited {
+				who: 1,
+				asset: Asset::Native,
+				amount: 100,
+				commitment: commitment(1),
+				leaf_index: 0,
+				root,
+			}
+			.into(),
+		);
+
+		assert_ok!(ShieldedPool::deposit(
+			RuntimeOrigin::signed(1),
+			Asset::Fungible(ASSET_ID),
+			40,
+			commitment(2)
+		));
+		assert_eq!(Assets::balance(ASSET_ID, 1), 960);
+		assert_eq!(Assets::balance(ASSET_ID, pool), 40);
+		assert_eq!(NextLeafIndex::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn tree_matches_reference() {
+	new_test_ext().execute_with(|| {
+		let mut leaves = Vec::new();
+		for value in 1..=7 {
+			let root = deposit(value, Asset::Native, value.into());
+			leaves.push(leaf(commitment(value), Asset::Native, value.into()));
+			assert_eq!(root, root_of(&leaves));
+		}
+	});
+}
+
+#[test]
+fn deposit_rejects_invalid_input() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ShieldedPool::deposit(RuntimeOrigin::signed(1), Asset::Native, 0, commitment(1)),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			ShieldedPool::deposit(RuntimeOrigin::signed(1), Asset::Native, 10, [0xff; 32]),
+			Error::<Test>::InvalidCommitment
+		);
+		assert_noop!(
+			ShieldedPool::deposit(RuntimeOrigin::signed(1), Asset::Native, 1_000, commitment(1)),
+			TokenError::NotExpendable
+		);
+		assert_noop!(
+			ShieldedPool::deposit(
+				RuntimeOrigin::signed(2),
+				Asset::Fungible(ASSET_ID),
+				10,
+				commitment(1)
+			),
+			TokenError::FundsUnavailable
+		);
+
+		NextLeafIndex::<Test>::put(16);
+		assert_noop!(
+			ShieldedPool::deposit(RuntimeOrigin::signed(1), Asset::Native, 10, commitment(1)),
+			Error::<Test>::TreeFull
+		);
+	});
+}
+
+#[test]
+fn only_recent_roots_are_known() {
+	new_test_ext().execute_with(|| {
+		let roots: Vec<_> = (1..=4).map(|value| deposit(value, Asset::Native, 10)).collect();
+
+		assert!(!ShieldedPool::is_known_root(&roots[0]));
+		for root in &roots[1..] {
+			assert!(ShieldedPool::is_known_root(root));
+		}
+		assert_eq!(KnownRoots::<Test>::iter().count(), 3);
+		assert_eq!(RootHistory::<Test>::get(CurrentRootIndex::<Test>::get()), Some(roots[3]));
+	});
+}
+
+#[test]
+fn withdraw_pays_out_note() {
+	new_test_ext().execute_with(|| {
+		let root = deposit(1, Asset::Native, 100);
+
+		assert_ok!(ShieldedPool::withdraw(
+			RuntimeOrigin::signed(2),
+			proof(true),
+			root,
+			NULLIFIER_HASH,
+			Asset::Native,
+			100,
+			3
+		));
+		assert_eq!(Balances::balance(&3), 100);
+		assert!(ShieldedPool::is_spent(&NULLIFIER_HASH));
+		assert_eq!(
+			LAST_INPUTS.with(|inputs| inputs.borrow().clone()),
+			vec![
+				root,
+				NULLIFIER_HASH,
+				asset_scalar(&Asset::<u32>::Native),
+				amount_scalar(100),
+				account_scalar(&3u64),
+			]
+		);
+		System::assert_last_event(
+			Event::Withdrawn {
+				nullifier_hash: NULLIFIER_HASH,
+				asset: Asset::Native,
+				amount: 100,
+				recipient: 3,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn withdraw_pays_out_asset_note() {
+	new_test_ext().execute_with(|| {
+		let asset = Asset::Fungible(ASSET_ID);
+		let root = deposit(1, asset, 50);
+
+		assert_ok!(ShieldedPool::withdraw(
+			RuntimeOrigin::signed(2),
+			proof(true),
+			root,
+			NULLIFIER_HASH,
+			asset,
+			50,
+			3
+		));
+		assert_eq!(Assets::balance(ASSET_ID, 3), 50);
+		assert_eq!(Assets::balance(ASSET_ID, ShieldedPool::account_id()), 0);
+	});
+}
+
+#[test]
+fn withdraw_rejects_invalid_claims() {
+	new_test_ext().execute_with(|| {
+		let root = deposit(1, Asset::Native, 100);
+		let withdraw = |proof, root, nullifier_hash| {
+			ShieldedPool::withdraw(
+				RuntimeOrigin::signed(2),
+				proof,
+				root,
+				nullifier_hash,
+				Asset::Native,
+				100,
+				3,
+			)
+		};
+
+		assert_noop!(withdraw(proof(true), [1; 32], NULLIFIER_HASH), Error::<Test>::UnknownRoot);
+		assert_noop!(withdraw(proof(false), root, NULLIFIER_HASH), Error::<Test>::InvalidProof);
+		assert_noop!(
+			ShieldedPool::withdraw(
+				RuntimeOrigin::none(),
+				proof(true),
+				root,
+				NULLIFIER_HASH,
+				Asset::Native,
+				100,
+				3
+			),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(withdraw(proof(true), root, NULLIFIER_HASH));
+		assert!(Nullifiers::<Test>::contains_key(NULLIFIER_HASH));
+		assert_noop!(
+			withdraw(proof(true), root, NULLIFIER_HASH),
+			Error::<Test>::NullifierAlreadyUsed
+		);
+	});
+}
+
+#[test]
+fn zero_hashes_are_the_empty_subtree_roots() {
+	assert_eq!(ZERO_HASHES[0], [0; 32]);
+	for level in 1..ZERO_HASHES.len() {
+		let below = ZERO_HASHES[level - 1];
+		assert_eq!(ZERO_HASHES[level], poseidon::hash_scalars(&[below, below]).unwrap());
+	}
+	assert_eq!(root_of(&[]), ZERO_HASHES[4]);
+}
+
+#[test]
+fn withdraw_refunds_down_to_the_weight_of_the_key() {
+	new_test_ext().execute_with(|| {
+		let root = deposit(1, Asset::Native, 100);
+		let charged = ShieldedPool::withdraw_weight(None);
+		let actual = ShieldedPool::withdraw_weight(Some(ProvingSystem::Groth16Bn254));
+		assert!(actual.ref_time() < charged.ref_time());
+
+		let post_info = ShieldedPool::withdraw(
+			RuntimeOrigin::signed(2),
+			proof(true),
+			root,
+			NULLIFIER_HASH,
+			Asset::Native,
+			100,
+			3,
+		)
+		.unwrap();
+		assert_eq!(post_info.actual_weight, Some(actual));
+	});
+}
@@ -0,0 +1,61 @@
+//! Roots of the empty subtrees of the commitment tree.
+
+use kora_zk_hash::Scalar;
+
+/// `ZERO_HASHES[level]` is the root of an empty subtree of height `level`: a zero leaf at level
+/// `0`, then `Poseidon(z, z)` of the level below.
+pub const ZERO_HASHES: [Scalar; 32] = [
+	hex_scalar("0000000000000000000000000000000000000000000000000000000000000000"),
+	hex_scalar("2098f5fb9e239eab3ceac3f27b81e481dc3124d55ffed523a839ee8446b64864"),
+	hex_scalar("1069673dcdb12263df301a6ff584a7ec261a44cb9dc68df067a4774460b1f1e1"),
+	hex_scalar("18f43331537ee2af2e3d758d50f72106467c6eea50371dd528d57eb2b856d238"),
+	hex_scalar("07f9d837cb17b0d36320ffe93ba52345f1b728571a568265caac97559dbc952a"),
+	hex_scalar("2b94cf5e8746b3f5c9631f4c5df32907a699c58c94b2ad4d7b5cec1639183f55"),
+	hex_scalar("2dee93c5a666459646ea7d22cca9e1bcfed71e6951b953611d11dda32ea09d78"),
+	hex_scalar("078295e5a22b84e982cf601eb639597b8b0515a88cb5ac7fa8a4aabe3c87349d"),
+	hex_scalar("2fa5e5f18f6027a6501bec864564472a616b2e274a41211a444cbe3a99f3cc61"),
+	hex_scalar("0e884376d0d8fd21ecb780389e941f66e45e7acce3e228ab3e2156a614fcd747"),
+	hex_scalar("1b7201da72494f1e28717ad1a52eb469f95892f957713533de6175e5da190af2"),
+	hex_scalar("1f8d8822725e36385200c0b201249819a6e6e1e4650808b5bebc6bface7d7636"),
+	hex_scalar("2c5d82f66c914bafb9701589ba8cfcfb6162b0a12acf88a8d0879a0471b5f85a"),
+	hex_scalar("14c54148a0940bb820957f5adf3fa1134ef5c4aaa113f4646458f270e0bfbfd0"),
+	hex_scalar("190d33b12f986f961e10c0ee44d8b9af11be25588cad89d416118e4bf4ebe80c"),
+	hex_scalar("22f98aa9ce704152ac17354914ad73ed1167ae6596af510aa5b3649325e06c92"),
+	hex_scalar("2a7c7c9b6ce5880b9f6f228d72bf6a575a526f29c66ecceef8b753d38bba7323"),
+	hex_scalar("2e8186e558698ec1c67af9c14d463ffc470043c9c2988b954d75dd643f36b992"),
+	hex_scalar("0f57c5571e9a4eab49e2c8cf050dae948aef6ead647392273546249d1c1ff10f"),
+	hex_scalar("1830ee67b5fb554ad5f63d4388800e1cfe78e310697d46e43c9ce36134f72cca"),
+	hex_scalar("2134e76ac5d21aab186c2be1dd8f84ee880a1e46eaf712f9d371b6df22191f3e"),
+	hex_scalar("19df90ec844ebc4ffeebd866f33859b0c051d8c958ee3aa88f8f8df3db91a5b1"),
+	hex_scalar("18cca2a66b5c0787981e69aefd84852d74af0e93ef4912b4648c05f722efe52b"),
+	hex_scalar("2388909415230d1b4d1304d2d54f473a628338f2efad83fadf05644549d2538d"),
+	hex_scalar("27171fb4a97b6cc0e9e8f543b5294de866a2af2c9c8d0b1d96e673e4529ed540"),
+	hex_scalar("2ff6650540f629fd5711a0bc74fc0d28dcb230b9392583e5f8d59696dde6ae21"),
+	hex_scalar("120c58f143d491e95902f7f5277778a2e0ad5168f6add75669932630ce611518"),
+	hex_scalar("1f21feb70d3f21b07bf853d5e5db03071ec495a0a565a21da2d665d279483795"),
+	hex_scalar("24be905fa71335e14c638cc0f66a8623a826e768068a9e968bb1a1dde18a72d2"),
+	hex_scalar("0f8666b62ed17491c50ceadead57d4cd597ef3821d65c328744c74e553dac26d"),
+	hex_scalar("0918d46bf52d98b034413f4a1a1c41594e7a7a3f6ae08cb43d1a2a230e1959ef"),
+	hex_scalar("1bbeb01b4c479ecde76917645e404dfa2e26f90d0afc5a65128513ad375c5ff2"),
+];
+
+/// Parses a scalar from 64 big-endian hex digits.
+const fn hex_scalar(hex: &str) -> Scalar {
+	const fn digit(c: u8) -> u8 {
+		match c {
+			b'0'..=b'9' => c - b'0',
+			b'a'..=b'f' => c - b'a' + 10,
+			_ => panic!("not a lowercase hex digit"),
+		}
+	}
+
+	let hex = hex.as_bytes();
+	assert!(hex.len() == 64, "a scalar has 64 hex digits");
+	let mut scalar = [0u8; 32];
+	let mut i = 0;
+	while i < 32 {
+		scalar[i] = digit(hex[2 * i]) << 4 | digit(hex[2 * i + 1]);
+		i += 1;
+	}
+	scalar
+}
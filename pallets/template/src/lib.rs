@@ -38,6 +38,8 @@
 
 // We make sure this pallet uses `no_std` for compiling to Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
+// The FRAME macros clone `Copy` fields.
+#![allow(clippy::clone_on_copy)]
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
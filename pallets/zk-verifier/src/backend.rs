@@ -0,0 +1,142 @@
+//! Plugging proving systems into the pallet.
+//!
+//! Each supported [`ProvingSystem`] is implemented by a [`VerifierBackend`] that understands the
+//! encoding of its keys and proofs. The runtime picks the backends it trusts by listing them as a
+//! tuple in [`Config::Backends`](crate::Config::Backends), which dispatches to the backend a key
+//! was registered for through [`VerifierRegistry`]:
+//!
+//! ```ignore
+//! type Backends = (Groth16Bn254, Groth16Bls12_381, Plonk, Fflonk);
+//! ```
+
+use crate::{ProvingSystem, ScalarBytes, WeightInfo};
+use frame_support::weights::Weight;
+#[cfg(feature = "runtime-benchmarks")]
+use alloc::vec::Vec;
+
+/// Reasons why a backend refused its input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BackendError {
+	/// No backend is registered for the proving system.
+	UnsupportedProvingSystem,
+	/// The key cannot be decoded or contains an invalid point or parameter.
+	InvalidVerificationKey,
+	/// The proof cannot be decoded or contains an invalid point or scalar.
+	MalformedProof,
+	/// A public input is not a canonical scalar field element.
+	InvalidPublicInput,
+	/// The number of public inputs does not match the verification key.
+	PublicInputCountMismatch,
+}
+
+/// A verifier for the proofs of one proving system.
+pub trait VerifierBackend {
+	/// The proving system keys of this backend are registered for.
+	const SYSTEM: ProvingSystem;
+
+	/// Checks an encoded verification key, returning the number of public inputs it takes.
+	fn validate_key(key: &[u8]) -> Result<u32, BackendError>;
+
+	/// Checks `proof` for `inputs` against a key accepted by [`Self::validate_key`].
+	///
+	/// Returns whether the proof is valid, or an error if the proof or an input is malformed.
+	fn verify(key: &[u8], proof: &[u8], inputs: &[ScalarBytes]) -> Result<bool, BackendError>;
+
+	/// Weight of registering a key with `n` public inputs.
+	fn register_weight<W: WeightInfo>(n: u32) -> Weight;
+
+	/// Weight of verifying a proof with `n` public inputs.
+	fn verify_weight<W: WeightInfo>(n: u32) -> Weight;
+
+	/// A well-formed key with `n` public inputs exercising the worst case of [`Self::verify`].
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_key(n: u32) -> Vec<u8>;
+
+	/// A well-formed proof against [`Self::benchmark_key`]. It does not need to be valid.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_proof() -> Vec<u8>;
+}
+
+/// A set of backends, dispatching to the one of the requested proving system.
+///
+/// Implemented for tuples of [`VerifierBackend`]s.
+pub trait VerifierRegistry {
+	/// Whether a backend for `system` is registered.
+	fn supports(system: ProvingSystem) -> bool;
+
+	/// [`VerifierBackend::validate_key`] of the backend for `system`.
+	fn validate_key(system: ProvingSystem, key: &[u8]) -> Result<u32, BackendError>;
+
+	/// [`VerifierBackend::verify`] of the backend for `system`.
+	fn verify(
+		system: ProvingSystem,
+		key: &[u8],
+		proof: &[u8],
+		inputs: &[ScalarBytes],
+	) -> Result<bool, BackendError>;
+
+	/// [`VerifierBackend::register_weight`] of the backend for `system`, zero if there is none.
+	fn register_weight<W: WeightInfo>(system: ProvingSystem, n: u32) -> Weight;
+
+	/// [`VerifierBackend::verify_weight`] of the backend for `system`, zero if there is none.
+	fn verify_weight<W: WeightInfo>(system: ProvingSystem, n: u32) -> Weight;
+
+	/// The largest [`VerifierBackend::verify_weight`] of all backends.
+	fn max_verify_weight<W: WeightInfo>(n: u32) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(8)]
+#[tuple_types_custom_trait_bound(VerifierBackend)]
+impl VerifierRegistry for Tuple {
+	fn supports(system: ProvingSystem) -> bool {
+		for_tuples!( #( if Tuple::SYSTEM == system { return true } )* );
+		false
+	}
+
+	fn validate_key(system: ProvingSystem, key: &[u8]) -> Result<u32, BackendError> {
+		for_tuples!( #(
+			if Tuple::SYSTEM == system {
+				return <Tuple as VerifierBackend>::validate_key(key)
+			}
+		)* );
+		Err(BackendError::UnsupportedProvingSystem)
+	}
+
+	fn verify(
+		system: ProvingSystem,
+		key: &[u8],
+		proof: &[u8],
+		inputs: &[ScalarBytes],
+	) -> Result<bool, BackendError> {
+		for_tuples!( #(
+			if Tuple::SYSTEM == system {
+				return <Tuple as VerifierBackend>::verify(key, proof, inputs)
+			}
+		)* );
+		Err(BackendError::UnsupportedProvingSystem)
+	}
+
+	fn register_weight<W: WeightInfo>(system: ProvingSystem, n: u32) -> Weight {
+		for_tuples!( #(
+			if Tuple::SYSTEM == system {
+				return <Tuple as VerifierBackend>::register_weight::<W>(n)
+			}
+		)* );
+		Weight::zero()
+	}
+
+	fn verify_weight<W: WeightInfo>(system: ProvingSystem, n: u32) -> Weight {
+		for_tuples!( #(
+			if Tuple::SYSTEM == system {
+				return <Tuple as VerifierBackend>::verify_weight::<W>(n)
+			}
+		)* );
+		Weight::zero()
+	}
+
+	fn max_verify_weight<W: WeightInfo>(n: u32) -> Weight {
+		let mut max = Weight::zero();
+		for_tuples!( #( max = max.max(<Tuple as VerifierBackend>::verify_weight::<W>(n)); )* );
+		max
+	}
+}
@@ -0,0 +1,275 @@
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ZkVerifier;
+use crate::{
+	fflonk::Fflonk,
+	groth16::{Groth16Bls12_381, Groth16Bn254},
+	ops::{Host, Native},
+	plonk::Plonk,
+};
+use alloc::vec::Vec;
+use frame_benchmarking::v2::*;
+use frame_support::traits::EnsureOrigin;
+use kora_zk_hash::truncate_to_scalar;
+
+/// Stores the benchmark key of backend `B` with `n` public inputs under `vk_id`.
+fn insert_key<T: Config, B: VerifierBackend>(vk_id: VkId, n: u32) {
+	let key = B::benchmark_key(n).try_into().expect("MaxKeyLen fits the benchmark keys");
+	VerificationKeys::<T>::insert(
+		vk_id,
+		VerificationKey { system: B::SYSTEM, public_input_count: n, key },
+	);
+}
+
+fn ensure_supported<T: Config, B: VerifierBackend>() -> Result<(), BenchmarkError> {
+	if T::Backends::supports(B::SYSTEM) {
+		Ok(())
+	} else {
+		Err(BenchmarkError::Skip)
+	}
+}
+
+fn register<T: Config, B: VerifierBackend>(n: u32) -> Result<(), BenchmarkError> {
+	ensure_supported::<T, B>()?;
+	let origin =
+		T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	let key = B::benchmark_key(n).try_into().map_err(|_| BenchmarkError::Weightless)?;
+
+	Pallet::<T>::register_verification_key(origin, 0, B::SYSTEM, key)
+		.map_err(|e| BenchmarkError::Stop(e.error.into()))?;
+	Ok(())
+}
+
+/// Registers the benchmark key of `B` and returns a proof and `n` inputs to verify against it.
+fn setup_verify<T: Config, B: VerifierBackend>(
+	n: u32,
+) -> Result<(EncodedProofOf<T>, PublicInputsOf<T>), BenchmarkError> {
+	ensure_supported::<T, B>()?;
+	insert_key::<T, B>(0, n);
+	let proof = B::benchmark_proof().try_into().map_err(|_| BenchmarkError::Weightless)?;
+	// Canonical scalars on both curves.
+	let inputs = (0..n)
+		.map(|i| {
+			let mut input = [0u8; 32];
+			input[24..].copy_from_slice(&(u64::MAX - i as u64).to_be_bytes());
+			input
+		})
+		.collect::<Vec<_>>()
+		.try_into()
+		.map_err(|_| BenchmarkError::Weightless)?;
+	Ok((proof, inputs))
+}
+
+/// Verifies `proof` against the key stored under `0` with `B`, as [`Pallet::verify`] would.
+///
+/// The backend is called directly rather than through [`Config::Backends`] so the native and host
+/// variants of a proving system can both be measured in the same runtime.
+fn verify_with<T: Config, B: VerifierBackend>(
+	proof: &[u8],
+	inputs: &[ScalarBytes],
+) -> Result<(), BenchmarkError> {
+	let vk = VerificationKeys::<T>::get(0).ok_or(BenchmarkError::Weightless)?;
+	B::verify(&vk.key, proof, inputs).map_err(|_| BenchmarkError::Weightless)?;
+	Ok(())
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn register_groth16_bn254(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		#[block]
+		{
+			register::<T, Groth16Bn254>(n)?;
+		}
+
+		assert!(VerificationKeys::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn register_groth16_bls12_381(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		#[block]
+		{
+			register::<T, Groth16Bls12_381>(n)?;
+		}
+
+		assert!(VerificationKeys::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn register_plonk_bn254() -> Result<(), BenchmarkError> {
+		#[block]
+		{
+			register::<T, Plonk>(T::MaxPublicInputs::get())?;
+		}
+
+		assert!(VerificationKeys::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn register_fflonk_bn254() -> Result<(), BenchmarkError> {
+		#[block]
+		{
+			register::<T, Fflonk>(T::MaxPublicInputs::get())?;
+		}
+
+		assert!(VerificationKeys::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_verification_key() -> Result<(), BenchmarkError> {
+		let origin =
+			T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		insert_key::<T, Groth16Bn254>(0, T::MaxPublicInputs::get());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0);
+
+		assert!(!VerificationKeys::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_groth16_bn254(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Groth16Bn254<Native>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Groth16Bn254<Native>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_groth16_bn254_host(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Groth16Bn254<Host>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Groth16Bn254<Host>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_groth16_bls12_381(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Groth16Bls12_381<Native>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Groth16Bls12_381<Native>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_groth16_bls12_381_host(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Groth16Bls12_381<Host>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Groth16Bls12_381<Host>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_plonk_bn254(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Plonk<Native>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Plonk<Native>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_plonk_bn254_host(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Plonk<Host>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Plonk<Host>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_fflonk_bn254(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Fflonk<Native>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Fflonk<Native>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn verify_fflonk_bn254_host(
+		n: Linear<0, { T::MaxPublicInputs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let (proof, inputs) = setup_verify::<T, Fflonk<Host>>(n)?;
+
+		#[block]
+		{
+			verify_with::<T, Fflonk<Host>>(&proof, &inputs)?;
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn hash_poseidon(n: Linear<1, { kora_zk_hash::poseidon::MAX_INPUTS as u32 }>) {
+		let inputs = (0..n).map(|i| truncate_to_scalar([i as u8; 32])).collect::<Vec<_>>();
+
+		#[block]
+		{
+			kora_zk_hash::poseidon::hash_scalars(&inputs).expect("canonical inputs");
+		}
+	}
+
+	#[benchmark]
+	fn hash_mimc(n: Linear<1, 64>) {
+		let inputs = (0..n).map(|i| truncate_to_scalar([i as u8; 32])).collect::<Vec<_>>();
+
+		#[block]
+		{
+			kora_zk_hash::mimc::hash_scalars(&inputs, &[0; 32]).expect("canonical inputs");
+		}
+	}
+
+	impl_benchmark_test_suite!(ZkVerifier, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,141 @@
+//! Encoding of BLS12-381 scalars and points.
+//!
+//! Points use the big-endian encoding of the Ethereum BLS12-381 precompiles (EIP-2537):
+//!
+//! - a scalar is 32 bytes, big-endian;
+//! - a base field element is 64 bytes, big-endian, the top 16 bytes being zero;
+//! - a G1 point is `x || y`, the point at infinity being all zeroes;
+//! - a G2 point is `x.c0 || x.c1 || y.c0 || y.c1`, i.e. the real part first.
+
+use crate::{
+	encoding::{read_bigint, write_bigint},
+	groth16,
+	ops::CurveOps,
+	ScalarBytes,
+};
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{PrimeField, Zero};
+
+/// An encoded BLS12-381 G1 point.
+pub type G1Bytes = [u8; 128];
+/// An encoded BLS12-381 G2 point.
+pub type G2Bytes = [u8; 256];
+
+/// A Groth16 verification key over BLS12-381.
+pub type Groth16VerificationKey = groth16::VerificationKey<G1Bytes, G2Bytes>;
+/// A Groth16 proof over BLS12-381.
+pub type Groth16Proof = groth16::Proof<G1Bytes, G2Bytes>;
+
+fn read_fq(bytes: &[u8]) -> Option<Fq> {
+	if bytes[..16].iter().any(|byte| *byte != 0) {
+		return None;
+	}
+	Fq::from_bigint(read_bigint(&bytes[16..]))
+}
+
+fn write_fq(value: &Fq, out: &mut [u8]) {
+	write_bigint(value.into_bigint(), &mut out[16..]);
+}
+
+/// Decodes a scalar, rejecting values that are not reduced modulo the group order.
+pub fn decode_scalar(bytes: &ScalarBytes) -> Option<Fr> {
+	Fr::from_bigint(read_bigint(bytes))
+}
+
+/// Encodes a scalar.
+pub fn encode_scalar(value: &Fr) -> ScalarBytes {
+	let mut out = [0u8; 32];
+	write_bigint(value.into_bigint(), &mut out);
+	out
+}
+
+/// Decodes a G1 point and checks that it is a valid group element.
+pub fn decode_g1(bytes: &G1Bytes) -> Option<G1Affine> {
+	read_g1(bytes, true)
+}
+
+fn read_g1(bytes: &G1Bytes, validate: bool) -> Option<G1Affine> {
+	let x = read_fq(&bytes[..64])?;
+	let y = read_fq(&bytes[64..])?;
+	if x.is_zero() && y.is_zero() {
+		return Some(G1Affine::zero());
+	}
+	let point = G1Affine::new_unchecked(x, y);
+	if validate && (!point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve()) {
+		return None;
+	}
+	Some(point)
+}
+
+/// Encodes a G1 point.
+pub fn encode_g1(point: &G1Affine) -> G1Bytes {
+	let mut out = [0u8; 128];
+	if let Some((x, y)) = point.xy() {
+		write_fq(&x, &mut out[..64]);
+		write_fq(&y, &mut out[64..]);
+	}
+	out
+}
+
+/// Decodes a G2 point and checks that it is a valid group element.
+pub fn decode_g2(bytes: &G2Bytes) -> Option<G2Affine> {
+	read_g2(bytes, true)
+}
+
+fn read_g2(bytes: &G2Bytes, validate: bool) -> Option<G2Affine> {
+	let x = Fq2::new(read_fq(&bytes[..64])?, read_fq(&bytes[64..128])?);
+	let y = Fq2::new(read_fq(&bytes[128..192])?, read_fq(&bytes[192..])?);
+	if x.is_zero() && y.is_zero() {
+		return Some(G2Affine::zero());
+	}
+	let point = G2Affine::new_unchecked(x, y);
+	if validate && (!point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve()) {
+		return None;
+	}
+	Some(point)
+}
+
+/// Encodes a G2 point.
+pub fn encode_g2(point: &G2Affine) -> G2Bytes {
+	let mut out = [0u8; 256];
+	if let Some((x, y)) = point.xy() {
+		write_fq(&x.c0, &mut out[..64]);
+		write_fq(&x.c1, &mut out[64..128]);
+		write_fq(&y.c0, &mut out[128..192]);
+		write_fq(&y.c1, &mut out[192..]);
+	}
+	out
+}
+
+impl groth16::Curve for Bls12_381 {
+	type G1Bytes = G1Bytes;
+	type G2Bytes = G2Bytes;
+
+	fn decode_scalar(bytes: &ScalarBytes) -> Option<Fr> {
+		decode_scalar(bytes)
+	}
+
+	fn decode_g1(bytes: &G1Bytes, validate: bool) -> Option<G1Affine> {
+		read_g1(bytes, validate)
+	}
+
+	fn decode_g2(bytes: &G2Bytes, validate: bool) -> Option<G2Affine> {
+		read_g2(bytes, validate)
+	}
+
+	fn encode_g1(point: &G1Affine) -> G1Bytes {
+		encode_g1(point)
+	}
+
+	fn encode_g2(point: &G2Affine) -> G2Bytes {
+		encode_g2(point)
+	}
+	fn msm<O: CurveOps>(bases: &[G1Affine], scalars: &[Fr]) -> Option<G1Projective> {
+		O::bls12_381_msm(bases, scalars)
+	}
+
+	fn pairing_check<O: CurveOps>(g1: &[G1Affine], g2: &[G2Affine]) -> bool {
+		O::bls12_381_pairing_check(g1, g2)
+	}
+}
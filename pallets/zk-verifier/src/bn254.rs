@@ -0,0 +1,149 @@
+//! Encoding of BN254 scalars and points.
+//!
+//! Points and scalars use the big-endian encoding of the Ethereum `ecAdd`, `ecMul` and
+//! `ecPairing` precompiles (EIP-196 and EIP-197). That is the layout snarkjs emits for its
+//! Solidity verifiers and `generatecall`, so circom artifacts can be submitted without any
+//! re-serialization:
+//!
+//! - a scalar or base field element is 32 bytes, big-endian;
+//! - a G1 point is `x || y`, the point at infinity being all zeroes;
+//! - a G2 point is `x.c1 || x.c0 || y.c1 || y.c0`, i.e. the imaginary part first.
+
+use crate::{
+	encoding::{read_bigint, write_bigint},
+	groth16,
+	ops::CurveOps,
+	ScalarBytes,
+};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{PrimeField, Zero};
+
+/// An encoded BN254 G1 point.
+pub type G1Bytes = [u8; 64];
+/// An encoded BN254 G2 point.
+pub type G2Bytes = [u8; 128];
+
+/// A Groth16 verification key over BN254.
+pub type Groth16VerificationKey = groth16::VerificationKey<G1Bytes, G2Bytes>;
+/// A Groth16 proof over BN254, `(pi_a, pi_b, pi_c)` in snarkjs terms.
+pub type Groth16Proof = groth16::Proof<G1Bytes, G2Bytes>;
+
+fn read_fq(bytes: &[u8]) -> Option<Fq> {
+	Fq::from_bigint(read_bigint(bytes))
+}
+
+/// Decodes a scalar, rejecting values that are not reduced modulo the group order.
+pub fn decode_scalar(bytes: &ScalarBytes) -> Option<Fr> {
+	Fr::from_bigint(read_bigint(bytes))
+}
+
+/// Encodes a scalar.
+pub fn encode_scalar(value: &Fr) -> ScalarBytes {
+	let mut out = [0u8; 32];
+	write_bigint(value.into_bigint(), &mut out);
+	out
+}
+
+/// Decodes a G1 point and checks that it is a valid group element.
+pub fn decode_g1(bytes: &G1Bytes) -> Option<G1Affine> {
+	read_g1(bytes, true)
+}
+
+/// Decodes a G1 point that was already validated with [`decode_g1`].
+pub fn decode_g1_trusted(bytes: &G1Bytes) -> Option<G1Affine> {
+	read_g1(bytes, false)
+}
+
+fn read_g1(bytes: &G1Bytes, validate: bool) -> Option<G1Affine> {
+	let x = read_fq(&bytes[..32])?;
+	let y = read_fq(&bytes[32..])?;
+	if x.is_zero() && y.is_zero() {
+		return Some(G1Affine::zero());
+	}
+	let point = G1Affine::new_unchecked(x, y);
+	if validate && (!point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve()) {
+		return None;
+	}
+	Some(point)
+}
+
+/// Encodes a G1 point.
+pub fn encode_g1(point: &G1Affine) -> G1Bytes {
+	let mut out = [0u8; 64];
+	if let Some((x, y)) = point.xy() {
+		write_bigint(x.into_bigint(), &mut out[..32]);
+		write_bigint(y.into_bigint(), &mut out[32..]);
+	}
+	out
+}
+
+/// Decodes a G2 point and checks that it is a valid group element.
+///
+/// The subgroup check dominates the cost of decoding, so keys that are decoded repeatedly should
+/// be validated once and read back with [`decode_g2_trusted`].
+pub fn decode_g2(bytes: &G2Bytes) -> Option<G2Affine> {
+	read_g2(bytes, true)
+}
+
+/// Decodes a G2 point that was already validated with [`decode_g2`].
+pub fn decode_g2_trusted(bytes: &G2Bytes) -> Option<G2Affine> {
+	read_g2(bytes, false)
+}
+
+fn read_g2(bytes: &G2Bytes, validate: bool) -> Option<G2Affine> {
+	let x = Fq2::new(read_fq(&bytes[32..64])?, read_fq(&bytes[..32])?);
+	let y = Fq2::new(read_fq(&bytes[96..])?, read_fq(&bytes[64..96])?);
+	if x.is_zero() && y.is_zero() {
+		return Some(G2Affine::zero());
+	}
+	let point = G2Affine::new_unchecked(x, y);
+	if validate && (!point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve()) {
+		return None;
+	}
+	Some(point)
+}
+
+/// Encodes a G2 point.
+pub fn encode_g2(point: &G2Affine) -> G2Bytes {
+	let mut out = [0u8; 128];
+	if let Some((x, y)) = point.xy() {
+		write_bigint(x.c1.into_bigint(), &mut out[..32]);
+		write_bigint(x.c0.into_bigint(), &mut out[32..64]);
+		write_bigint(y.c1.into_bigint(), &mut out[64..96]);
+		write_bigint(y.c0.into_bigint(), &mut out[96..]);
+	}
+	out
+}
+
+impl groth16::Curve for Bn254 {
+	type G1Bytes = G1Bytes;
+	type G2Bytes = G2Bytes;
+
+	fn decode_scalar(bytes: &ScalarBytes) -> Option<Fr> {
+		decode_scalar(bytes)
+	}
+
+	fn decode_g1(bytes: &G1Bytes, validate: bool) -> Option<G1Affine> {
+		read_g1(bytes, validate)
+	}
+
+	fn decode_g2(bytes: &G2Bytes, validate: bool) -> Option<G2Affine> {
+		read_g2(bytes, validate)
+	}
+
+	fn encode_g1(point: &G1Affine) -> G1Bytes {
+		encode_g1(point)
+	}
+
+	fn encode_g2(point: &G2Affine) -> G2Bytes {
+		encode_g2(point)
+	}
+	fn msm<O: CurveOps>(bases: &[G1Affine], scalars: &[Fr]) -> Option<G1Projective> {
+		O::bn254_msm(bases, scalars)
+	}
+
+	fn pairing_check<O: CurveOps>(g1: &[G1Affine], g2: &[G2Affine]) -> bool {
+		O::bn254_pairing_check(g1, g2)
+	}
+}
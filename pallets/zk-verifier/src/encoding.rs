@@ -0,0 +1,25 @@
+//! Big-endian integers backing the curve encodings.
+
+use ark_ff::BigInt;
+
+/// Reads a big-endian integer of `8 * N` bytes.
+pub(crate) fn read_bigint<const N: usize>(bytes: &[u8]) -> BigInt<N> {
+	debug_assert_eq!(bytes.len(), 8 * N);
+	let mut limbs = [0u64; N];
+	for (i, limb) in limbs.iter_mut().enumerate() {
+		let end = 8 * (N - i);
+		let mut word = [0u8; 8];
+		word.copy_from_slice(&bytes[end - 8..end]);
+		*limb = u64::from_be_bytes(word);
+	}
+	BigInt::new(limbs)
+}
+
+/// Writes `value` as a big-endian integer of `8 * N` bytes.
+pub(crate) fn write_bigint<const N: usize>(value: BigInt<N>, out: &mut [u8]) {
+	debug_assert_eq!(out.len(), 8 * N);
+	for (i, limb) in value.0.iter().enumerate() {
+		let end = 8 * (N - i);
+		out[end - 8..end].copy_from_slice(&limb.to_be_bytes());
+	}
+}
@@ -0,0 +1,431 @@
+//! fflonk verification over BN254, compatible with snarkjs.
+//!
+//! This follows `fflonk_verify.js` of snarkjs. The prover commits to three polynomials combining
+//! the PLONK polynomials, `C0` (the circuit, part of the key), `C1` (wires and gate quotient) and
+//! `C2` (permutation and its quotients), and opens them at the roots of `xi` with a single
+//! batched KZG proof `(W1, W2)`. A key is the SCALE encoding of a [`VerificationKey`] and a proof
+//! that of a [`Proof`], holding the values of the snarkjs `verification_key.json` and `proof.json`
+//! in the [`crate::bn254`] encoding. An encoded proof is the `uint256[24]` argument of the snarkjs
+//! Solidity verifier.
+
+use crate::{
+	backend::{BackendError, VerifierBackend},
+	ops::{CurveOps, Native},
+	bn254::{self, G1Bytes, G2Bytes},
+	snarkjs::{self, Transcript},
+	ProvingSystem, ScalarBytes, WeightInfo,
+};
+use alloc::vec::Vec;
+use ark_bn254::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{batch_inversion, Field, One, Zero};
+use codec::{Decode, DecodeAll, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::weights::Weight;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// An encoded snarkjs fflonk verification key.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VerificationKey {
+	/// `nPublic`, the number of public inputs.
+	pub n_public: u32,
+	/// `power`, the base-2 logarithm of the domain size.
+	pub power: u32,
+	pub k1: ScalarBytes,
+	pub k2: ScalarBytes,
+	/// `w`, the generator of the evaluation domain.
+	pub w: ScalarBytes,
+	/// `w3`, a primitive cube root of unity.
+	pub w3: ScalarBytes,
+	/// `w4`, a primitive fourth root of unity.
+	pub w4: ScalarBytes,
+	/// `w8`, a primitive eighth root of unity.
+	pub w8: ScalarBytes,
+	/// `wr`, a cube root of `w`.
+	pub wr: ScalarBytes,
+	/// `X_2`, the trusted setup secret in G2.
+	pub x_2: G2Bytes,
+	/// `C0`, the commitment to the selector and permutation polynomials.
+	pub c0: G1Bytes,
+}
+
+/// An encoded snarkjs fflonk proof.
+///
+/// `inv` only lets the Solidity verifier batch its inversions and is not used here.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Proof {
+	pub c1: G1Bytes,
+	pub c2: G1Bytes,
+	pub w1: G1Bytes,
+	pub w2: G1Bytes,
+	pub ql: ScalarBytes,
+	pub qr: ScalarBytes,
+	pub qm: ScalarBytes,
+	pub qo: ScalarBytes,
+	pub qc: ScalarBytes,
+	pub s1: ScalarBytes,
+	pub s2: ScalarBytes,
+	pub s3: ScalarBytes,
+	pub a: ScalarBytes,
+	pub b: ScalarBytes,
+	pub c: ScalarBytes,
+	pub z: ScalarBytes,
+	pub zw: ScalarBytes,
+	pub t1w: ScalarBytes,
+	pub t2w: ScalarBytes,
+	pub inv: ScalarBytes,
+}
+
+struct PreparedKey {
+	n_public: u32,
+	power: u32,
+	k1: Fr,
+	k2: Fr,
+	w: Fr,
+	w3: Fr,
+	w4: Fr,
+	w8: Fr,
+	wr: Fr,
+	x_2: G2Affine,
+	c0: G1Affine,
+}
+
+/// The evaluations of a proof, in transcript order.
+struct Evaluations {
+	ql: Fr,
+	qr: Fr,
+	qm: Fr,
+	qo: Fr,
+	qc: Fr,
+	s1: Fr,
+	s2: Fr,
+	s3: Fr,
+	a: Fr,
+	b: Fr,
+	c: Fr,
+	z: Fr,
+	zw: Fr,
+	t1w: Fr,
+	t2w: Fr,
+}
+
+struct PreparedProof {
+	c1: G1Affine,
+	c2: G1Affine,
+	w1: G1Affine,
+	w2: G1Affine,
+	evals: Evaluations,
+}
+
+/// Decodes a key, checking its points and roots of unity when `validate` is set.
+fn decode_key(key: &[u8], validate: bool) -> Option<PreparedKey> {
+	let key = VerificationKey::decode_all(&mut &key[..]).ok()?;
+	let prepared = PreparedKey {
+		n_public: key.n_public,
+		power: key.power,
+		k1: bn254::decode_scalar(&key.k1)?,
+		k2: bn254::decode_scalar(&key.k2)?,
+		w: bn254::decode_scalar(&key.w)?,
+		w3: bn254::decode_scalar(&key.w3)?,
+		w4: bn254::decode_scalar(&key.w4)?,
+		w8: bn254::decode_scalar(&key.w8)?,
+		wr: bn254::decode_scalar(&key.wr)?,
+		x_2: if validate {
+			bn254::decode_g2(&key.x_2)?
+		} else {
+			bn254::decode_g2_trusted(&key.x_2)?
+		},
+		c0: if validate { bn254::decode_g1(&key.c0)? } else { bn254::decode_g1_trusted(&key.c0)? },
+	};
+	if validate &&
+		!(snarkjs::is_domain_generator(prepared.w, prepared.power) &&
+			prepared.w3.pow([3]).is_one() &&
+			prepared.w4.pow([4]).is_one() &&
+			prepared.w8.pow([8]).is_one() &&
+			prepared.wr.pow([3]) == prepared.w)
+	{
+		return None;
+	}
+	Some(prepared)
+}
+
+fn decode_proof(proof: &[u8]) -> Option<PreparedProof> {
+	let proof = Proof::decode_all(&mut &proof[..]).ok()?;
+	let scalar = bn254::decode_scalar;
+	scalar(&proof.inv)?;
+	Some(PreparedProof {
+		c1: bn254::decode_g1(&proof.c1)?,
+		c2: bn254::decode_g1(&proof.c2)?,
+		w1: bn254::decode_g1(&proof.w1)?,
+		w2: bn254::decode_g1(&proof.w2)?,
+		evals: Evaluations {
+			ql: scalar(&proof.ql)?,
+			qr: scalar(&proof.qr)?,
+			qm: scalar(&proof.qm)?,
+			qo: scalar(&proof.qo)?,
+			qc: scalar(&proof.qc)?,
+			s1: scalar(&proof.s1)?,
+			s2: scalar(&proof.s2)?,
+			s3: scalar(&proof.s3)?,
+			a: scalar(&proof.a)?,
+			b: scalar(&proof.b)?,
+			c: scalar(&proof.c)?,
+			z: scalar(&proof.z)?,
+			zw: scalar(&proof.zw)?,
+			t1w: scalar(&proof.t1w)?,
+			t2w: scalar(&proof.t2w)?,
+		},
+	})
+}
+
+/// `root, root * w, root * w^2, ..` up to `count` values.
+fn coset(root: Fr, w: Fr, count: usize) -> Vec<Fr> {
+	core::iter::successors(Some(root), |h| Some(*h * w)).take(count).collect()
+}
+
+/// `sum(coeffs[i] * x^i)`.
+fn evaluate(coeffs: &[Fr], x: Fr) -> Fr {
+	coeffs.iter().rev().fold(Fr::zero(), |acc, coeff| acc * x + coeff)
+}
+
+/// `prod(y - x)` over `xs`.
+fn vanishing(xs: &[Fr], y: Fr) -> Fr {
+	xs.iter().map(|x| y - x).product()
+}
+
+/// The value at `y` of the polynomial of degree `< xs.len()` taking `values` at `xs`.
+///
+/// Returns `None` if `y` is one of the points.
+fn interpolate(xs: &[Fr], values: &[Fr], y: Fr) -> Option<Fr> {
+	let mut weights: Vec<Fr> = xs
+		.iter()
+		.enumerate()
+		.map(|(i, xi)| {
+			let others: Fr =
+				xs.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, xj)| *xi - xj).product();
+			others * (y - xi)
+		})
+		.collect();
+	if weights.iter().any(Zero::is_zero) {
+		return None;
+	}
+	batch_inversion(&mut weights);
+	let sum: Fr = values.iter().zip(&weights).map(|(value, weight)| *value * weight).sum();
+	Some(vanishing(xs, y) * sum)
+}
+
+/// Runs the snarkjs verifier on decoded values.
+fn check<O: CurveOps>(vk: &PreparedKey, proof: &PreparedProof, inputs: &[Fr]) -> bool {
+	let e = &proof.evals;
+	let mut transcript = Transcript::default();
+	transcript.add_point(&vk.c0);
+	for input in inputs {
+		transcript.add_scalar(input);
+	}
+	transcript.add_point(&proof.c1);
+	let beta = transcript.challenge();
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&proof.c2);
+	let xi_seed = transcript.challenge();
+	transcript.add_scalar(&xi_seed);
+	for eval in [
+		&e.ql, &e.qr, &e.qm, &e.qo, &e.qc, &e.s1, &e.s2, &e.s3, &e.a, &e.b, &e.c, &e.z, &e.zw,
+		&e.t1w, &e.t2w,
+	] {
+		transcript.add_scalar(eval);
+	}
+	let alpha = transcript.challenge();
+	transcript.add_scalar(&alpha);
+	transcript.add_point(&proof.w1);
+	let y = transcript.challenge();
+
+	// `C0`, `C1` and `C2` are opened at the 8th, 4th and 3rd roots of `xi = xi_seed^24`, and `C2`
+	// also at the cube roots of `xi * w`.
+	let h0 = xi_seed.pow([3]);
+	let h1 = h0.square();
+	let h2 = h1 * xi_seed.square();
+	let h3 = h2 * vk.wr;
+	let xi = h2.pow([3]);
+	let roots0 = coset(h0, vk.w8, 8);
+	let roots1 = coset(h1, vk.w4, 4);
+	let mut roots2 = coset(h2, vk.w3, 3);
+	roots2.extend(coset(h3, vk.w3, 3));
+
+	let xin = snarkjs::pow_domain_size(xi, vk.power);
+	let zh = xin - Fr::one();
+	let Some(zh_inv) = zh.inverse() else { return false };
+	let Some(lagrange) =
+		snarkjs::lagrange_evaluations(xi, zh, vk.power, vk.w, inputs.len().max(1))
+	else {
+		return false;
+	};
+	let pi = snarkjs::public_input_evaluation(inputs, &lagrange);
+
+	// The quotients of the gate, the first permutation value and the permutation at `xi`.
+	let t0 = (e.ql * e.a + e.qr * e.b + e.qm * e.a * e.b + e.qo * e.c + e.qc + pi) * zh_inv;
+	let t1 = (e.z - Fr::one()) * lagrange[0] * zh_inv;
+	let beta_xi = beta * xi;
+	let t2 = ((e.a + beta_xi + gamma) *
+		(e.b + beta_xi * vk.k1 + gamma) *
+		(e.c + beta_xi * vk.k2 + gamma) *
+		e.z - (e.a + beta * e.s1 + gamma) *
+		(e.b + beta * e.s2 + gamma) *
+		(e.c + beta * e.s3 + gamma) *
+		e.zw) * zh_inv;
+
+	// The values the commitments take at their opening points, interpolated at `y`.
+	let c0 = [e.ql, e.qr, e.qo, e.qm, e.qc, e.s1, e.s2, e.s3];
+	let c1 = [e.a, e.b, e.c, t0];
+	let c2 = [e.z, t1, t2];
+	let c2w = [e.zw, e.t1w, e.t2w];
+	let values0: Vec<Fr> = roots0.iter().map(|h| evaluate(&c0, *h)).collect();
+	let values1: Vec<Fr> = roots1.iter().map(|h| evaluate(&c1, *h)).collect();
+	let values2: Vec<Fr> = roots2[..3]
+		.iter()
+		.map(|h| evaluate(&c2, *h))
+		.chain(roots2[3..].iter().map(|h| evaluate(&c2w, *h)))
+		.collect();
+	let (Some(r0), Some(r1), Some(r2)) = (
+		interpolate(&roots0, &values0, y),
+		interpolate(&roots1, &values1, y),
+		interpolate(&roots2, &values2, y),
+	) else {
+		return false;
+	};
+
+	let z0 = vanishing(&roots0, y);
+	let (Some(z1_inv), Some(z2_inv)) =
+		(vanishing(&roots1, y).inverse(), vanishing(&roots2, y).inverse())
+	else {
+		return false;
+	};
+	let quotient1 = alpha * z0 * z1_inv;
+	let quotient2 = alpha.square() * z0 * z2_inv;
+
+	// `A1 = [F] - [E] - [J] + y [W2]` with `[F] = [C0] + q1 [C1] + q2 [C2]`,
+	// `[E] = (r0 + q1 r1 + q2 r2) [1]` and `[J] = z0 [W1]`.
+	let Some(a1) = O::bn254_msm(
+		&[vk.c0, proof.c1, proof.c2, G1Affine::generator(), proof.w1, proof.w2],
+		&[Fr::one(), quotient1, quotient2, -(r0 + quotient1 * r1 + quotient2 * r2), -z0, y],
+	) else {
+		return false;
+	};
+
+	O::bn254_pairing_check(&[-a1.into_affine(), proof.w2], &[G2Affine::generator(), vk.x_2])
+}
+
+/// The snarkjs fflonk backend, computing curve operations with `O`.
+pub struct Fflonk<O = Native>(PhantomData<O>);
+
+impl<O: CurveOps> VerifierBackend for Fflonk<O> {
+	const SYSTEM: ProvingSystem = ProvingSystem::FflonkBn254;
+
+	fn validate_key(key: &[u8]) -> Result<u32, BackendError> {
+		decode_key(key, true)
+			.map(|key| key.n_public)
+			.ok_or(BackendError::InvalidVerificationKey)
+	}
+
+	fn verify(key: &[u8], proof: &[u8], inputs: &[ScalarBytes]) -> Result<bool, BackendError> {
+		let vk = decode_key(key, false).ok_or(BackendError::InvalidVerificationKey)?;
+		if inputs.len() != vk.n_public as usize {
+			return Err(BackendError::PublicInputCountMismatch);
+		}
+		let proof = decode_proof(proof).ok_or(BackendError::MalformedProof)?;
+		let inputs = inputs
+			.iter()
+			.map(bn254::decode_scalar)
+			.collect::<Option<Vec<_>>>()
+			.ok_or(BackendError::InvalidPublicInput)?;
+
+		Ok(check::<O>(&vk, &proof, &inputs))
+	}
+
+	fn register_weight<W: WeightInfo>(_n: u32) -> Weight {
+		W::register_fflonk_bn254()
+	}
+
+	fn verify_weight<W: WeightInfo>(n: u32) -> Weight {
+		if O::ON_HOST {
+			W::verify_fflonk_bn254_host(n)
+		} else {
+			W::verify_fflonk_bn254(n)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_key(n: u32) -> Vec<u8> {
+		use ark_ff::FftField;
+
+		// The largest domain, which maximises the cost of evaluating the vanishing polynomial.
+		let power = Fr::TWO_ADICITY;
+		let root = |n: u64| Fr::get_root_of_unity(n).expect("divides 3 * 2^28");
+		let wr = root(3 << power);
+		VerificationKey {
+			n_public: n,
+			power,
+			k1: bn254::encode_scalar(&Fr::from(2u64)),
+			k2: bn254::encode_scalar(&Fr::from(3u64)),
+			w: bn254::encode_scalar(&wr.pow([3])),
+			w3: bn254::encode_scalar(&root(3)),
+			w4: bn254::encode_scalar(&root(4)),
+			w8: bn254::encode_scalar(&root(8)),
+			wr: bn254::encode_scalar(&wr),
+			x_2: bn254::encode_g2(&G2Affine::generator()),
+			c0: bn254::encode_g1(&G1Affine::generator()),
+		}
+		.encode()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_proof() -> Vec<u8> {
+		let g1 = bn254::encode_g1(&G1Affine::generator());
+		let eval = |i: u64| bn254::encode_scalar(&Fr::from(i));
+		Proof {
+			c1: g1,
+			c2: g1,
+			w1: g1,
+			w2: g1,
+			ql: eval(1),
+			qr: eval(2),
+			qm: eval(3),
+			qo: eval(4),
+			qc: eval(5),
+			s1: eval(6),
+			s2: eval(7),
+			s3: eval(8),
+			a: eval(9),
+			b: eval(10),
+			c: eval(11),
+			z: eval(12),
+			zw: eval(13),
+			t1w: eval(14),
+			t2w: eval(15),
+			inv: eval(16),
+		}
+		.encode()
+	}
+}
@@ -0,0 +1,250 @@
+//! Groth16 verification over BN254 and BLS12-381.
+//!
+//! A key is the SCALE encoding of a [`VerificationKey`] and a proof that of a [`Proof`], with
+//! points in the encoding of the curve: [`crate::bn254`] for circom/snarkjs circuits and
+//! [`crate::bls12_381`] for BLS12-381 ones. Points have a fixed size, so an encoded proof is
+//! simply `a || b || c`.
+
+use crate::{
+	backend::{BackendError, VerifierBackend},
+	ops::{CurveOps, Native},
+	ProvingSystem, ScalarBytes, WeightInfo,
+};
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use codec::{Decode, DecodeAll, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::weights::Weight;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// A pairing-friendly curve with an encoding of its points.
+pub trait Curve: Pairing {
+	/// An encoded G1 point.
+	type G1Bytes: Encode + Decode + Copy;
+	/// An encoded G2 point.
+	type G2Bytes: Encode + Decode + Copy;
+
+	/// Decodes a scalar, rejecting values that are not reduced modulo the group order.
+	fn decode_scalar(bytes: &ScalarBytes) -> Option<Self::ScalarField>;
+
+	/// Decodes a G1 point, checking that it is a valid group element when `validate` is set.
+	fn decode_g1(bytes: &Self::G1Bytes, validate: bool) -> Option<Self::G1Affine>;
+
+	/// Decodes a G2 point, checking that it is a valid group element when `validate` is set.
+	fn decode_g2(bytes: &Self::G2Bytes, validate: bool) -> Option<Self::G2Affine>;
+
+	/// Encodes a G1 point.
+	fn encode_g1(point: &Self::G1Affine) -> Self::G1Bytes;
+
+	/// Encodes a G2 point.
+	fn encode_g2(point: &Self::G2Affine) -> Self::G2Bytes;
+
+	/// Multi-scalar multiplication on G1 with `O`.
+	fn msm<O: CurveOps>(bases: &[Self::G1Affine], scalars: &[Self::ScalarField])
+		-> Option<Self::G1>;
+
+	/// Pairing check with `O`, see [`CurveOps`].
+	fn pairing_check<O: CurveOps>(g1: &[Self::G1Affine], g2: &[Self::G2Affine]) -> bool;
+}
+
+/// An encoded Groth16 verification key.
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct VerificationKey<G1, G2> {
+	pub alpha_g1: G1,
+	pub beta_g2: G2,
+	pub gamma_g2: G2,
+	pub delta_g2: G2,
+	/// `IC[0]`, the constant term of the public input commitment.
+	pub ic_base: G1,
+	/// `IC[1..]`, one point per public input.
+	pub ic: Vec<G1>,
+}
+
+/// An encoded Groth16 proof.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Proof<G1, G2> {
+	pub a: G1,
+	pub b: G2,
+	pub c: G1,
+}
+
+/// A decoded verification key.
+struct PreparedKey<C: Curve> {
+	alpha_g1: C::G1Affine,
+	beta_g2: C::G2Affine,
+	gamma_g2: C::G2Affine,
+	delta_g2: C::G2Affine,
+	/// `IC` in snarkjs terms: one point for the constant term followed by one per public input.
+	ic: Vec<C::G1Affine>,
+}
+
+/// Decodes a key, checking every point when `validate` is set.
+///
+/// Keys are validated when they are registered, so stored keys can skip the checks.
+fn decode_key<C: Curve>(key: &[u8], validate: bool) -> Result<PreparedKey<C>, BackendError> {
+	let key = VerificationKey::<C::G1Bytes, C::G2Bytes>::decode_all(&mut &key[..])
+		.map_err(|_| BackendError::InvalidVerificationKey)?;
+	let g1 = |point| C::decode_g1(point, validate).ok_or(BackendError::InvalidVerificationKey);
+	let g2 = |point| C::decode_g2(point, validate).ok_or(BackendError::InvalidVerificationKey);
+	Ok(PreparedKey {
+		alpha_g1: g1(&key.alpha_g1)?,
+		beta_g2: g2(&key.beta_g2)?,
+		gamma_g2: g2(&key.gamma_g2)?,
+		delta_g2: g2(&key.delta_g2)?,
+		ic: core::iter::once(&key.ic_base)
+			.chain(key.ic.iter())
+			.map(g1)
+			.collect::<Result<_, _>>()?,
+	})
+}
+
+fn validate_key<C: Curve>(key: &[u8]) -> Result<u32, BackendError> {
+	decode_key::<C>(key, true).map(|key| key.ic.len() as u32 - 1)
+}
+
+/// Checks the Groth16 pairing equation
+/// `e(A, B) = e(alpha, beta) * e(IC(inputs), gamma) * e(C, delta)`.
+fn verify<C: Curve, O: CurveOps>(
+	key: &[u8],
+	proof: &[u8],
+	inputs: &[ScalarBytes],
+) -> Result<bool, BackendError> {
+	let vk = decode_key::<C>(key, false)?;
+	if inputs.len() + 1 != vk.ic.len() {
+		return Err(BackendError::PublicInputCountMismatch);
+	}
+
+	let proof = Proof::<C::G1Bytes, C::G2Bytes>::decode_all(&mut &proof[..])
+		.map_err(|_| BackendError::MalformedProof)?;
+	let a = C::decode_g1(&proof.a, true).ok_or(BackendError::MalformedProof)?;
+	let b = C::decode_g2(&proof.b, true).ok_or(BackendError::MalformedProof)?;
+	let c = C::decode_g1(&proof.c, true).ok_or(BackendError::MalformedProof)?;
+	let inputs = inputs
+		.iter()
+		.map(C::decode_scalar)
+		.collect::<Option<Vec<_>>>()
+		.ok_or(BackendError::InvalidPublicInput)?;
+
+	let Some(acc) = C::msm::<O>(&vk.ic[1..], &inputs) else { return Ok(false) };
+	let acc = acc + vk.ic[0];
+
+	Ok(C::pairing_check::<O>(
+		&[(-C::G1::from(a)).into_affine(), vk.alpha_g1, acc.into_affine(), c],
+		&[b, vk.beta_g2, vk.gamma_g2, vk.delta_g2],
+	))
+}
+
+/// A key with `n` public inputs made of group generators.
+///
+/// Verification cost does not depend on whether the proof is valid, so generators are good
+/// enough to exercise the worst case.
+#[cfg(feature = "runtime-benchmarks")]
+fn generator_key<C: Curve>(n: u32) -> Vec<u8> {
+	use ark_ec::AffineRepr;
+	let g1 = C::encode_g1(&C::G1Affine::generator());
+	let g2 = C::encode_g2(&C::G2Affine::generator());
+	VerificationKey {
+		alpha_g1: g1,
+		beta_g2: g2,
+		gamma_g2: g2,
+		delta_g2: g2,
+		ic_base: g1,
+		ic: (0..n).map(|_| g1).collect(),
+	}
+	.encode()
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+fn generator_proof<C: Curve>() -> Vec<u8> {
+	use ark_ec::AffineRepr;
+	let g1 = C::encode_g1(&C::G1Affine::generator());
+	let g2 = C::encode_g2(&C::G2Affine::generator());
+	Proof { a: g1, b: g2, c: g1 }.encode()
+}
+
+/// The Groth16 backend over curve `C`, computing curve operations with `O`.
+pub struct Groth16<C, O = Native>(PhantomData<(C, O)>);
+
+/// Groth16 over BN254, see [`crate::bn254`].
+pub type Groth16Bn254<O = Native> = Groth16<ark_bn254::Bn254, O>;
+/// Groth16 over BLS12-381, see [`crate::bls12_381`].
+pub type Groth16Bls12_381<O = Native> = Groth16<ark_bls12_381::Bls12_381, O>;
+
+impl<O: CurveOps> VerifierBackend for Groth16Bn254<O> {
+	const SYSTEM: ProvingSystem = ProvingSystem::Groth16Bn254;
+
+	fn validate_key(key: &[u8]) -> Result<u32, BackendError> {
+		validate_key::<ark_bn254::Bn254>(key)
+	}
+
+	fn verify(key: &[u8], proof: &[u8], inputs: &[ScalarBytes]) -> Result<bool, BackendError> {
+		verify::<ark_bn254::Bn254, O>(key, proof, inputs)
+	}
+
+	fn register_weight<W: WeightInfo>(n: u32) -> Weight {
+		W::register_groth16_bn254(n)
+	}
+
+	fn verify_weight<W: WeightInfo>(n: u32) -> Weight {
+		if O::ON_HOST {
+			W::verify_groth16_bn254_host(n)
+		} else {
+			W::verify_groth16_bn254(n)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_key(n: u32) -> Vec<u8> {
+		generator_key::<ark_bn254::Bn254>(n)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_proof() -> Vec<u8> {
+		generator_proof::<ark_bn254::Bn254>()
+	}
+}
+
+impl<O: CurveOps> VerifierBackend for Groth16Bls12_381<O> {
+	const SYSTEM: ProvingSystem = ProvingSystem::Groth16Bls12_381;
+
+	fn validate_key(key: &[u8]) -> Result<u32, BackendError> {
+		validate_key::<ark_bls12_381::Bls12_381>(key)
+	}
+
+	fn verify(key: &[u8], proof: &[u8], inputs: &[ScalarBytes]) -> Result<bool, BackendError> {
+		verify::<ark_bls12_381::Bls12_381, O>(key, proof, inputs)
+	}
+
+	fn register_weight<W: WeightInfo>(n: u32) -> Weight {
+		W::register_groth16_bls12_381(n)
+	}
+
+	fn verify_weight<W: WeightInfo>(n: u32) -> Weight {
+		if O::ON_HOST {
+			W::verify_groth16_bls12_381_host(n)
+		} else {
+			W::verify_groth16_bls12_381(n)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_key(n: u32) -> Vec<u8> {
+		generator_key::<ark_bls12_381::Bls12_381>(n)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_proof() -> Vec<u8> {
+		generator_proof::<ark_bls12_381::Bls12_381>()
+	}
+}
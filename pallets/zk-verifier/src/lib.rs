@@ -0,0 +1,373 @@
+//! # ZK Verifier Pallet
+//!
+//! Verifies zk-SNARK proofs against verification keys registered on-chain.
+//!
+//! ## Overview
+//!
+//! Applications prove statements off-chain with circom/snarkjs or arkworks and submit the proof
+//! here instead of re-implementing a verifier in every contract or pallet:
+//!
+//! - [`Config::ManagerOrigin`] registers a verification key of any [`ProvingSystem`] the runtime
+//!   supports under a [`VkId`] with [`Pallet::register_verification_key`] and can retire it with
+//!   [`Pallet::remove_verification_key`]. Keys are validated once at registration.
+//! - Anyone can call [`Pallet::verify`] with a proof and its public inputs. The call succeeds
+//!   whenever the inputs are well-formed and reports the outcome through
+//!   [`Event::ProofVerified`] or [`Event::ProofRejected`].
+//! - Other pallets can use [`Pallet::verify_proof`] directly.
+//!
+//! Callers only name the key: the proof is checked by the backend of the key's proving system.
+//! The supported systems are the [`VerifierBackend`]s listed in [`Config::Backends`]:
+//!
+//! - [`groth16::Groth16Bn254`] and [`groth16::Groth16Bls12_381`];
+//! - [`plonk::Plonk`] and [`fflonk::Fflonk`], the snarkjs PLONK and fflonk verifiers over BN254.
+//!
+//! Keys and proofs are passed as bytes in the encoding documented by each backend. Public inputs
+//! are 32-byte big-endian scalars for every backend, see [`bn254`] and [`bls12_381`].
+//!
+//! Each backend computes its pairings and multi-scalar multiplications through an
+//! [`ops::CurveOps`]: in Wasm with [`ops::Native`], or on the node through host functions with
+//! [`ops::Host`], which is several times cheaper and charged accordingly.
+//!
+//! The [`WeightInfo`] also prices the `kora_zk_hash` functions, for callers such as the
+//! contracts chain extension that offer them outside of a proof.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The FRAME macros clone `Copy` fields and convert dispatch results into their own type.
+#![allow(clippy::clone_on_copy, clippy::useless_conversion)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod backend;
+pub mod bls12_381;
+pub mod bn254;
+mod encoding;
+pub mod fflonk;
+pub mod groth16;
+pub mod ops;
+pub mod plonk;
+mod snarkjs;
+pub mod weights;
+pub use backend::{BackendError, VerifierBackend, VerifierRegistry};
+pub use weights::*;
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::Get, weights::Weight, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound,
+	RuntimeDebugNoBound,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{DispatchError, RuntimeDebug};
+
+/// Identifier under which a verification key is registered.
+pub type VkId = u32;
+
+/// An encoded scalar field element, 32 bytes big-endian.
+pub type ScalarBytes = [u8; 32];
+
+/// A proving system together with the curve its proofs are over.
+#[derive(
+	Clone,
+	Copy,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum ProvingSystem {
+	/// Groth16 over BN254, as produced by circom and snarkjs.
+	Groth16Bn254,
+	/// Groth16 over BLS12-381.
+	Groth16Bls12_381,
+	/// PLONK over BN254, as produced by snarkjs.
+	PlonkBn254,
+	/// fflonk over BN254, as produced by snarkjs.
+	FflonkBn254,
+}
+
+/// A registered verification key.
+#[derive(
+	CloneNoBound,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxKeyLen))]
+pub struct VerificationKey<MaxKeyLen: Get<u32>> {
+	/// The proving system the key belongs to.
+	pub system: ProvingSystem,
+	/// Number of public inputs a proof against the key must provide.
+	pub public_input_count: u32,
+	/// The key, in the encoding of its proving system.
+	pub key: BoundedVec<u8, MaxKeyLen>,
+}
+
+/// Public metadata of a registered verification key.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct VerificationKeyInfo {
+	/// The proving system the key belongs to.
+	pub system: ProvingSystem,
+	/// Number of public inputs a proof against the key must provide.
+	pub public_input_count: u32,
+}
+
+/// Verification of proofs against registered keys, for use by other pallets.
+pub trait ProofVerifier {
+	/// Check the encoded `proof` for `public_inputs` against the key registered under `vk_id`.
+	fn verify(vk_id: VkId, proof: &[u8], public_inputs: &[ScalarBytes])
+		-> Result<bool, DispatchError>;
+
+	/// Weight of [`Self::verify`] with `public_inputs` inputs against a key of `system`.
+	fn verify_weight(system: ProvingSystem, public_inputs: u32) -> Weight;
+
+	/// Weight of [`Self::verify`] with `public_inputs` inputs against any key.
+	fn max_verify_weight(public_inputs: u32) -> Weight;
+
+	/// The proving system of the key registered under `vk_id`, if any.
+	fn proving_system(vk_id: VkId) -> Option<ProvingSystem>;
+}
+
+pub type VerificationKeyOf<T> = VerificationKey<<T as Config>::MaxKeyLen>;
+pub type EncodedKeyOf<T> = BoundedVec<u8, <T as Config>::MaxKeyLen>;
+pub type EncodedProofOf<T> = BoundedVec<u8, <T as Config>::MaxProofLen>;
+pub type PublicInputsOf<T> = BoundedVec<ScalarBytes, <T as Config>::MaxPublicInputs>;
+
+impl<T> From<BackendError> for Error<T> {
+	fn from(error: BackendError) -> Self {
+		match error {
+			BackendError::UnsupportedProvingSystem => Error::UnsupportedProvingSystem,
+			BackendError::InvalidVerificationKey => Error::InvalidVerificationKey,
+			BackendError::MalformedProof => Error::MalformedProof,
+			BackendError::InvalidPublicInput => Error::InvalidPublicInput,
+			BackendError::PublicInputCountMismatch => Error::PublicInputCountMismatch,
+		}
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Origin allowed to register and remove verification keys.
+		type ManagerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The proving systems keys can be registered for, as a tuple of [`VerifierBackend`]s.
+		type Backends: VerifierRegistry;
+		/// Maximum number of public inputs of a registered circuit.
+		#[pallet::constant]
+		type MaxPublicInputs: Get<u32>;
+		/// Maximum length of an encoded verification key.
+		#[pallet::constant]
+		type MaxKeyLen: Get<u32>;
+		/// Maximum length of an encoded proof.
+		#[pallet::constant]
+		type MaxProofLen: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Registered verification keys.
+	#[pallet::storage]
+	pub type VerificationKeys<T: Config> =
+		StorageMap<_, Blake2_128Concat, VkId, VerificationKeyOf<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A verification key was registered.
+		VerificationKeyRegistered { vk_id: VkId, system: ProvingSystem },
+		/// A verification key was removed.
+		VerificationKeyRemoved { vk_id: VkId },
+		/// A proof was checked and is valid.
+		ProofVerified { vk_id: VkId, who: T::AccountId },
+		/// A proof was checked and is invalid.
+		ProofRejected { vk_id: VkId, who: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A verification key is already registered under this id.
+		VerificationKeyExists,
+		/// No verification key is registered under this id.
+		UnknownVerificationKey,
+		/// The runtime has no backend for the proving system.
+		UnsupportedProvingSystem,
+		/// The verification key cannot be decoded or contains an invalid point or parameter.
+		InvalidVerificationKey,
+		/// The verification key takes more than `MaxPublicInputs` public inputs.
+		TooManyPublicInputs,
+		/// The proof cannot be decoded or contains an invalid point or scalar.
+		MalformedProof,
+		/// A public input is not a canonical scalar field element.
+		InvalidPublicInput,
+		/// The number of public inputs does not match the verification key.
+		PublicInputCountMismatch,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register `key`, a verification key of `system`, under `vk_id`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(
+			T::Backends::register_weight::<T::WeightInfo>(*system, T::MaxPublicInputs::get())
+		)]
+		pub fn register_verification_key(
+			origin: OriginFor<T>,
+			vk_id: VkId,
+			system: ProvingSystem,
+			key: EncodedKeyOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(
+				!VerificationKeys::<T>::contains_key(vk_id),
+				Error::<T>::VerificationKeyExists
+			);
+			let public_input_count =
+				T::Backends::validate_key(system, &key).map_err(Error::<T>::from)?;
+			ensure!(
+				public_input_count <= T::MaxPublicInputs::get(),
+				Error::<T>::TooManyPublicInputs
+			);
+
+			VerificationKeys::<T>::insert(
+				vk_id,
+				VerificationKey { system, public_input_count, key },
+			);
+			Self::deposit_event(Event::VerificationKeyRegistered { vk_id, system });
+			Ok(Some(T::Backends::register_weight::<T::WeightInfo>(system, public_input_count))
+				.into())
+		}
+
+		/// Remove the verification key registered under `vk_id`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_verification_key())]
+		pub fn remove_verification_key(origin: OriginFor<T>, vk_id: VkId) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(
+				VerificationKeys::<T>::contains_key(vk_id),
+				Error::<T>::UnknownVerificationKey
+			);
+
+			VerificationKeys::<T>::remove(vk_id);
+			Self::deposit_event(Event::VerificationKeyRemoved { vk_id });
+			Ok(())
+		}
+
+		/// Verify `proof` for `public_inputs` against the key registered under `vk_id`.
+		///
+		/// The outcome is reported as an event; the call only fails on malformed input. It is
+		/// charged for the most expensive backend and refunded down to the one of the key.
+		#[pallet::call_index(2)]
+		#[pallet::weight(Pallet::<T>::max_verify_weight(public_inputs.len() as u32))]
+		pub fn verify(
+			origin: OriginFor<T>,
+			vk_id: VkId,
+			proof: EncodedProofOf<T>,
+			public_inputs: PublicInputsOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let (system, valid) = Self::check_proof(vk_id, &proof, &public_inputs)?;
+			if valid {
+				Self::deposit_event(Event::ProofVerified { vk_id, who });
+			} else {
+				Self::deposit_event(Event::ProofRejected { vk_id, who });
+			}
+			Ok(Some(Self::verify_weight(system, public_inputs.len() as u32)).into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Check `proof` for `public_inputs` against the key registered under `vk_id`.
+		///
+		/// Returns whether the proof is valid, or an error if any of the inputs is malformed.
+		pub fn verify_proof(
+			vk_id: VkId,
+			proof: &[u8],
+			public_inputs: &[ScalarBytes],
+		) -> Result<bool, Error<T>> {
+			Self::check_proof(vk_id, proof, public_inputs).map(|(_, valid)| valid)
+		}
+
+		/// Metadata of the key registered under `vk_id`, if any.
+		pub fn verification_key_info(vk_id: VkId) -> Option<VerificationKeyInfo> {
+			VerificationKeys::<T>::get(vk_id).map(|vk| VerificationKeyInfo {
+				system: vk.system,
+				public_input_count: vk.public_input_count,
+			})
+		}
+
+		/// Weight of verifying a proof with `public_inputs` inputs against a key of `system`.
+		pub fn verify_weight(system: ProvingSystem, public_inputs: u32) -> Weight {
+			T::Backends::verify_weight::<T::WeightInfo>(system, public_inputs)
+		}
+
+		/// Weight of verifying a proof with `public_inputs` inputs against any key.
+		pub fn max_verify_weight(public_inputs: u32) -> Weight {
+			T::Backends::max_verify_weight::<T::WeightInfo>(public_inputs)
+		}
+
+		fn check_proof(
+			vk_id: VkId,
+			proof: &[u8],
+			public_inputs: &[ScalarBytes],
+		) -> Result<(ProvingSystem, bool), Error<T>> {
+			let vk = VerificationKeys::<T>::get(vk_id).ok_or(Error::<T>::UnknownVerificationKey)?;
+			ensure!(
+				public_inputs.len() == vk.public_input_count as usize,
+				Error::<T>::PublicInputCountMismatch
+			);
+
+			let valid = T::Backends::verify(vk.system, &vk.key, proof, public_inputs)?;
+			Ok((vk.system, valid))
+		}
+	}
+
+	impl<T: Config> ProofVerifier for Pallet<T> {
+		fn verify(
+			vk_id: VkId,
+			proof: &[u8],
+			public_inputs: &[ScalarBytes],
+		) -> Result<bool, DispatchError> {
+			Self::verify_proof(vk_id, proof, public_inputs).map_err(Into::into)
+		}
+
+		fn verify_weight(system: ProvingSystem, public_inputs: u32) -> Weight {
+			Pallet::<T>::verify_weight(system, public_inputs)
+		}
+
+		fn max_verify_weight(public_inputs: u32) -> Weight {
+			Pallet::<T>::max_verify_weight(public_inputs)
+		}
+
+		fn proving_system(vk_id: VkId) -> Option<ProvingSystem> {
+			VerificationKeys::<T>::get(vk_id).map(|vk| vk.system)
+		}
+	}
+}
@@ -0,0 +1,110 @@
+//! Where the elliptic curve arithmetic of the backends runs.
+//!
+//! Every backend spends most of its time in one multi-scalar multiplication and one product of
+//! pairings. The backends are generic over [`CurveOps`], which computes those two operations
+//! either inside the runtime with [`Native`] or on the node through the `kora-zk-crypto` host
+//! functions with [`Host`]:
+//!
+//! ```ignore
+//! type Backends = (Groth16Bn254<Host>, Groth16Bls12_381<Host>, Plonk<Host>, Fflonk<Host>);
+//! ```
+//!
+//! [`Native`] works on any node and is the fallback while nodes are being upgraded: a runtime
+//! using [`Host`] cannot be instantiated by a node that does not provide
+//! `kora_zk_crypto::HostFunctions`. Backends charge the `*_host` weights when they use [`Host`].
+
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::Zero;
+
+/// Computes the expensive curve operations of the verifiers.
+///
+/// Callers pass points that are valid group elements and slices of the same length.
+pub trait CurveOps {
+	/// Whether the operations run on the host, which selects the `*_host` weights.
+	const ON_HOST: bool;
+
+	/// `sum(scalars[i] * bases[i])` on BN254 G1, `None` if it could not be computed.
+	fn bn254_msm(
+		bases: &[ark_bn254::G1Affine],
+		scalars: &[ark_bn254::Fr],
+	) -> Option<ark_bn254::G1Projective>;
+
+	/// Whether `e(g1[0], g2[0]) * e(g1[1], g2[1]) * ...` is the identity on BN254.
+	fn bn254_pairing_check(g1: &[ark_bn254::G1Affine], g2: &[ark_bn254::G2Affine]) -> bool;
+
+	/// `sum(scalars[i] * bases[i])` on BLS12-381 G1, `None` if it could not be computed.
+	fn bls12_381_msm(
+		bases: &[ark_bls12_381::G1Affine],
+		scalars: &[ark_bls12_381::Fr],
+	) -> Option<ark_bls12_381::G1Projective>;
+
+	/// Whether `e(g1[0], g2[0]) * e(g1[1], g2[1]) * ...` is the identity on BLS12-381.
+	fn bls12_381_pairing_check(
+		g1: &[ark_bls12_381::G1Affine],
+		g2: &[ark_bls12_381::G2Affine],
+	) -> bool;
+}
+
+/// Curve operations computed by arkworks inside the runtime.
+pub struct Native;
+
+impl CurveOps for Native {
+	const ON_HOST: bool = false;
+
+	fn bn254_msm(
+		bases: &[ark_bn254::G1Affine],
+		scalars: &[ark_bn254::Fr],
+	) -> Option<ark_bn254::G1Projective> {
+		ark_bn254::G1Projective::msm(bases, scalars).ok()
+	}
+
+	fn bn254_pairing_check(g1: &[ark_bn254::G1Affine], g2: &[ark_bn254::G2Affine]) -> bool {
+		g1.len() == g2.len() && ark_bn254::Bn254::multi_pairing(g1, g2).is_zero()
+	}
+
+	fn bls12_381_msm(
+		bases: &[ark_bls12_381::G1Affine],
+		scalars: &[ark_bls12_381::Fr],
+	) -> Option<ark_bls12_381::G1Projective> {
+		ark_bls12_381::G1Projective::msm(bases, scalars).ok()
+	}
+
+	fn bls12_381_pairing_check(
+		g1: &[ark_bls12_381::G1Affine],
+		g2: &[ark_bls12_381::G2Affine],
+	) -> bool {
+		g1.len() == g2.len() && ark_bls12_381::Bls12_381::multi_pairing(g1, g2).is_zero()
+	}
+}
+
+/// Curve operations computed by the node through the `kora-zk-crypto` host functions.
+pub struct Host;
+
+impl CurveOps for Host {
+	const ON_HOST: bool = true;
+
+	fn bn254_msm(
+		bases: &[ark_bn254::G1Affine],
+		scalars: &[ark_bn254::Fr],
+	) -> Option<ark_bn254::G1Projective> {
+		kora_zk_crypto::bn254::msm_g1(bases, scalars)
+	}
+
+	fn bn254_pairing_check(g1: &[ark_bn254::G1Affine], g2: &[ark_bn254::G2Affine]) -> bool {
+		kora_zk_crypto::bn254::pairing_check(g1, g2).unwrap_or(false)
+	}
+
+	fn bls12_381_msm(
+		bases: &[ark_bls12_381::G1Affine],
+		scalars: &[ark_bls12_381::Fr],
+	) -> Option<ark_bls12_381::G1Projective> {
+		kora_zk_crypto::bls12_381::msm_g1(bases, scalars)
+	}
+
+	fn bls12_381_pairing_check(
+		g1: &[ark_bls12_381::G1Affine],
+		g2: &[ark_bls12_381::G2Affine],
+	) -> bool {
+		kora_zk_crypto::bls12_381::pairing_check(g1, g2).unwrap_or(false)
+	}
+}
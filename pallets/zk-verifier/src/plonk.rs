@@ -0,0 +1,394 @@
+//! PLONK verification over BN254, compatible with snarkjs.
+//!
+//! This follows `plonk_verify.js` of snarkjs: the challenges are derived with its Keccak-256
+//! transcript and the proof is checked with a single batched KZG opening at `xi` and `xi * w`.
+//! A key is the SCALE encoding of a [`VerificationKey`] and a proof that of a [`Proof`], holding
+//! the values of the snarkjs `verification_key.json` and `proof.json` in the [`crate::bn254`]
+//! encoding. An encoded proof is the `uint256[24]` argument of the snarkjs Solidity verifier.
+
+use crate::{
+	backend::{BackendError, VerifierBackend},
+	ops::{CurveOps, Native},
+	bn254::{self, G1Bytes, G2Bytes},
+	snarkjs::{self, Transcript},
+	ProvingSystem, ScalarBytes, WeightInfo,
+};
+use alloc::vec::Vec;
+use ark_bn254::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One};
+use codec::{Decode, DecodeAll, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::weights::Weight;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// An encoded snarkjs PLONK verification key.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VerificationKey {
+	/// `nPublic`, the number of public inputs.
+	pub n_public: u32,
+	/// `power`, the base-2 logarithm of the domain size.
+	pub power: u32,
+	pub k1: ScalarBytes,
+	pub k2: ScalarBytes,
+	pub qm: G1Bytes,
+	pub ql: G1Bytes,
+	pub qr: G1Bytes,
+	pub qo: G1Bytes,
+	pub qc: G1Bytes,
+	pub s1: G1Bytes,
+	pub s2: G1Bytes,
+	pub s3: G1Bytes,
+	/// `X_2`, the trusted setup secret in G2.
+	pub x_2: G2Bytes,
+	/// `w`, the generator of the evaluation domain.
+	pub w: ScalarBytes,
+}
+
+/// An encoded snarkjs PLONK proof.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Proof {
+	pub a: G1Bytes,
+	pub b: G1Bytes,
+	pub c: G1Bytes,
+	pub z: G1Bytes,
+	pub t1: G1Bytes,
+	pub t2: G1Bytes,
+	pub t3: G1Bytes,
+	pub wxi: G1Bytes,
+	pub wxiw: G1Bytes,
+	pub eval_a: ScalarBytes,
+	pub eval_b: ScalarBytes,
+	pub eval_c: ScalarBytes,
+	pub eval_s1: ScalarBytes,
+	pub eval_s2: ScalarBytes,
+	pub eval_zw: ScalarBytes,
+}
+
+struct PreparedKey {
+	n_public: u32,
+	power: u32,
+	k1: Fr,
+	k2: Fr,
+	qm: G1Affine,
+	ql: G1Affine,
+	qr: G1Affine,
+	qo: G1Affine,
+	qc: G1Affine,
+	s1: G1Affine,
+	s2: G1Affine,
+	s3: G1Affine,
+	x_2: G2Affine,
+	w: Fr,
+}
+
+struct PreparedProof {
+	a: G1Affine,
+	b: G1Affine,
+	c: G1Affine,
+	z: G1Affine,
+	t1: G1Affine,
+	t2: G1Affine,
+	t3: G1Affine,
+	wxi: G1Affine,
+	wxiw: G1Affine,
+	eval_a: Fr,
+	eval_b: Fr,
+	eval_c: Fr,
+	eval_s1: Fr,
+	eval_s2: Fr,
+	eval_zw: Fr,
+}
+
+/// Decodes a key, checking its points and domain when `validate` is set.
+fn decode_key(key: &[u8], validate: bool) -> Option<PreparedKey> {
+	let key = VerificationKey::decode_all(&mut &key[..]).ok()?;
+	let g1 = |point| {
+		if validate {
+			bn254::decode_g1(point)
+		} else {
+			bn254::decode_g1_trusted(point)
+		}
+	};
+	let g2 = |point| {
+		if validate {
+			bn254::decode_g2(point)
+		} else {
+			bn254::decode_g2_trusted(point)
+		}
+	};
+	let prepared = PreparedKey {
+		n_public: key.n_public,
+		power: key.power,
+		k1: bn254::decode_scalar(&key.k1)?,
+		k2: bn254::decode_scalar(&key.k2)?,
+		qm: g1(&key.qm)?,
+		ql: g1(&key.ql)?,
+		qr: g1(&key.qr)?,
+		qo: g1(&key.qo)?,
+		qc: g1(&key.qc)?,
+		s1: g1(&key.s1)?,
+		s2: g1(&key.s2)?,
+		s3: g1(&key.s3)?,
+		x_2: g2(&key.x_2)?,
+		w: bn254::decode_scalar(&key.w)?,
+	};
+	if validate && !snarkjs::is_domain_generator(prepared.w, prepared.power) {
+		return None;
+	}
+	Some(prepared)
+}
+
+fn decode_proof(proof: &[u8]) -> Option<PreparedProof> {
+	let proof = Proof::decode_all(&mut &proof[..]).ok()?;
+	Some(PreparedProof {
+		a: bn254::decode_g1(&proof.a)?,
+		b: bn254::decode_g1(&proof.b)?,
+		c: bn254::decode_g1(&proof.c)?,
+		z: bn254::decode_g1(&proof.z)?,
+		t1: bn254::decode_g1(&proof.t1)?,
+		t2: bn254::decode_g1(&proof.t2)?,
+		t3: bn254::decode_g1(&proof.t3)?,
+		wxi: bn254::decode_g1(&proof.wxi)?,
+		wxiw: bn254::decode_g1(&proof.wxiw)?,
+		eval_a: bn254::decode_scalar(&proof.eval_a)?,
+		eval_b: bn254::decode_scalar(&proof.eval_b)?,
+		eval_c: bn254::decode_scalar(&proof.eval_c)?,
+		eval_s1: bn254::decode_scalar(&proof.eval_s1)?,
+		eval_s2: bn254::decode_scalar(&proof.eval_s2)?,
+		eval_zw: bn254::decode_scalar(&proof.eval_zw)?,
+	})
+}
+
+/// Runs the snarkjs verifier on decoded values.
+fn check<O: CurveOps>(vk: &PreparedKey, proof: &PreparedProof, inputs: &[Fr]) -> bool {
+	let mut transcript = Transcript::default();
+	for point in [&vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3] {
+		transcript.add_point(point);
+	}
+	for input in inputs {
+		transcript.add_scalar(input);
+	}
+	for point in [&proof.a, &proof.b, &proof.c] {
+		transcript.add_point(point);
+	}
+	let beta = transcript.challenge();
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+	transcript.add_scalar(&beta);
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&proof.z);
+	let alpha = transcript.challenge();
+	transcript.add_scalar(&alpha);
+	for point in [&proof.t1, &proof.t2, &proof.t3] {
+		transcript.add_point(point);
+	}
+	let xi = transcript.challenge();
+	transcript.add_scalar(&xi);
+	for eval in [
+		&proof.eval_a,
+		&proof.eval_b,
+		&proof.eval_c,
+		&proof.eval_s1,
+		&proof.eval_s2,
+		&proof.eval_zw,
+	] {
+		transcript.add_scalar(eval);
+	}
+	let v1 = transcript.challenge();
+	transcript.add_point(&proof.wxi);
+	transcript.add_point(&proof.wxiw);
+	let u = transcript.challenge();
+	let [v2, v3, v4, v5] = [v1.pow([2]), v1.pow([3]), v1.pow([4]), v1.pow([5])];
+
+	let xin = snarkjs::pow_domain_size(xi, vk.power);
+	let zh = xin - Fr::one();
+	let Some(lagrange) =
+		snarkjs::lagrange_evaluations(xi, zh, vk.power, vk.w, inputs.len().max(1))
+	else {
+		return false;
+	};
+	let pi = snarkjs::public_input_evaluation(inputs, &lagrange);
+	let l1 = lagrange[0];
+	let alpha2 = alpha.square();
+
+	// The constant term of the linearisation polynomial.
+	let perm_a = proof.eval_a + beta * proof.eval_s1 + gamma;
+	let perm_b = proof.eval_b + beta * proof.eval_s2 + gamma;
+	let r0 = pi - l1 * alpha2 - alpha * perm_a * perm_b * (proof.eval_c + gamma) * proof.eval_zw;
+
+	// `[D]`, the commitment to the rest of the linearisation polynomial, batched with the other
+	// openings at `xi` into `[F]`, minus their claimed evaluation `[E]`.
+	let beta_xi = beta * xi;
+	let z_coeff = alpha *
+		(proof.eval_a + beta_xi + gamma) *
+		(proof.eval_b + beta_xi * vk.k1 + gamma) *
+		(proof.eval_c + beta_xi * vk.k2 + gamma) +
+		l1 * alpha2 + u;
+	let s3_coeff = alpha * beta * proof.eval_zw * perm_a * perm_b;
+	let e = -r0 +
+		v1 * proof.eval_a +
+		v2 * proof.eval_b +
+		v3 * proof.eval_c +
+		v4 * proof.eval_s1 +
+		v5 * proof.eval_s2 +
+		u * proof.eval_zw;
+
+	// `B1 = xi [Wxi] + u xi w [Wxiw] + [F] - [E]` and `A1 = [Wxi] + u [Wxiw]`.
+	let bases = [
+		vk.qm,
+		vk.ql,
+		vk.qr,
+		vk.qo,
+		vk.qc,
+		proof.z,
+		vk.s3,
+		proof.t1,
+		proof.t2,
+		proof.t3,
+		proof.a,
+		proof.b,
+		proof.c,
+		vk.s1,
+		vk.s2,
+		proof.wxi,
+		proof.wxiw,
+		G1Affine::generator(),
+	];
+	let scalars = [
+		proof.eval_a * proof.eval_b,
+		proof.eval_a,
+		proof.eval_b,
+		proof.eval_c,
+		Fr::one(),
+		z_coeff,
+		-s3_coeff,
+		-zh,
+		-zh * xin,
+		-zh * xin.square(),
+		v1,
+		v2,
+		v3,
+		v4,
+		v5,
+		xi,
+		u * xi * vk.w,
+		-e,
+	];
+	let Some(b1) = O::bn254_msm(&bases, &scalars) else { return false };
+	let a1 = proof.wxiw * u + proof.wxi;
+
+	O::bn254_pairing_check(&[-a1.into_affine(), b1.into_affine()], &[vk.x_2, G2Affine::generator()])
+}
+
+/// The snarkjs PLONK backend, computing curve operations with `O`.
+pub struct Plonk<O = Native>(PhantomData<O>);
+
+impl<O: CurveOps> VerifierBackend for Plonk<O> {
+	const SYSTEM: ProvingSystem = ProvingSystem::PlonkBn254;
+
+	fn validate_key(key: &[u8]) -> Result<u32, BackendError> {
+		decode_key(key, true)
+			.map(|key| key.n_public)
+			.ok_or(BackendError::InvalidVerificationKey)
+	}
+
+	fn verify(key: &[u8], proof: &[u8], inputs: &[ScalarBytes]) -> Result<bool, BackendError> {
+		let vk = decode_key(key, false).ok_or(BackendError::InvalidVerificationKey)?;
+		if inputs.len() != vk.n_public as usize {
+			return Err(BackendError::PublicInputCountMismatch);
+		}
+		let proof = decode_proof(proof).ok_or(BackendError::MalformedProof)?;
+		let inputs = inputs
+			.iter()
+			.map(bn254::decode_scalar)
+			.collect::<Option<Vec<_>>>()
+			.ok_or(BackendError::InvalidPublicInput)?;
+
+		Ok(check::<O>(&vk, &proof, &inputs))
+	}
+
+	fn register_weight<W: WeightInfo>(_n: u32) -> Weight {
+		W::register_plonk_bn254()
+	}
+
+	fn verify_weight<W: WeightInfo>(n: u32) -> Weight {
+		if O::ON_HOST {
+			W::verify_plonk_bn254_host(n)
+		} else {
+			W::verify_plonk_bn254(n)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_key(n: u32) -> Vec<u8> {
+		use ark_ff::FftField;
+
+		let g1 = bn254::encode_g1(&G1Affine::generator());
+		// The largest domain, which maximises the cost of evaluating the vanishing polynomial.
+		let power = Fr::TWO_ADICITY;
+		let w = Fr::get_root_of_unity(1 << power).expect("within two-adicity");
+		VerificationKey {
+			n_public: n,
+			power,
+			k1: bn254::encode_scalar(&Fr::from(2u64)),
+			k2: bn254::encode_scalar(&Fr::from(3u64)),
+			qm: g1,
+			ql: g1,
+			qr: g1,
+			qo: g1,
+			qc: g1,
+			s1: g1,
+			s2: g1,
+			s3: g1,
+			x_2: bn254::encode_g2(&G2Affine::generator()),
+			w: bn254::encode_scalar(&w),
+		}
+		.encode()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_proof() -> Vec<u8> {
+		let g1 = bn254::encode_g1(&G1Affine::generator());
+		let eval = |i: u64| bn254::encode_scalar(&Fr::from(i));
+		Proof {
+			a: g1,
+			b: g1,
+			c: g1,
+			z: g1,
+			t1: g1,
+			t2: g1,
+			t3: g1,
+			wxi: g1,
+			wxiw: g1,
+			eval_a: eval(1),
+			eval_b: eval(2),
+			eval_c: eval(3),
+			eval_s1: eval(4),
+			eval_s2: eval(5),
+			eval_zw: eval(6),
+		}
+		.encode()
+	}
+}
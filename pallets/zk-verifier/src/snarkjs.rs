@@ -0,0 +1,73 @@
+//! Parts shared by the snarkjs PLONK and fflonk verifiers.
+
+use crate::bn254;
+use alloc::vec::Vec;
+use ark_bn254::{Fr, G1Affine};
+use ark_ff::{batch_inversion, Field, One, PrimeField, Zero};
+use sp_io::hashing::keccak_256;
+
+/// The Fiat-Shamir transcript of snarkjs (`Keccak256Transcript`).
+///
+/// Points and scalars are appended in their [`bn254`] encoding and each challenge is the
+/// Keccak-256 hash of everything appended since the previous one, reduced modulo the group order.
+#[derive(Default)]
+pub(crate) struct Transcript(Vec<u8>);
+
+impl Transcript {
+	pub fn add_point(&mut self, point: &G1Affine) {
+		self.0.extend_from_slice(&bn254::encode_g1(point));
+	}
+
+	pub fn add_scalar(&mut self, scalar: &Fr) {
+		self.0.extend_from_slice(&bn254::encode_scalar(scalar));
+	}
+
+	pub fn challenge(&mut self) -> Fr {
+		let hash = keccak_256(&self.0);
+		self.0.clear();
+		Fr::from_be_bytes_mod_order(&hash)
+	}
+}
+
+/// `xi^n` for a domain of size `n = 2^power`.
+pub(crate) fn pow_domain_size(xi: Fr, power: u32) -> Fr {
+	(0..power).fold(xi, |acc, _| acc.square())
+}
+
+/// Whether `w` is an `n`-th root of unity for `n = 2^power`, `power` being within the two-adicity
+/// of the scalar field.
+pub(crate) fn is_domain_generator(w: Fr, power: u32) -> bool {
+	power <= <Fr as ark_ff::FftField>::TWO_ADICITY && pow_domain_size(w, power).is_one()
+}
+
+/// The Lagrange polynomials `L_1(xi), .., L_count(xi)` of a domain of size `2^power` generated by
+/// `w`, given `zh = xi^n - 1`.
+///
+/// Returns `None` if `xi` lies in the domain.
+pub(crate) fn lagrange_evaluations(
+	xi: Fr,
+	zh: Fr,
+	power: u32,
+	w: Fr,
+	count: usize,
+) -> Option<Vec<Fr>> {
+	let n = Fr::from(1u64 << power);
+	let mut numerators = Vec::with_capacity(count);
+	let mut denominators = Vec::with_capacity(count);
+	let mut root = Fr::one();
+	for _ in 0..count {
+		numerators.push(root * zh);
+		denominators.push(n * (xi - root));
+		root *= w;
+	}
+	if denominators.iter().any(Zero::is_zero) {
+		return None;
+	}
+	batch_inversion(&mut denominators);
+	Some(numerators.into_iter().zip(denominators).map(|(num, den)| num * den).collect())
+}
+
+/// The evaluation `PI(xi) = -sum(input_i * L_i(xi))` of the public input polynomial.
+pub(crate) fn public_input_evaluation(inputs: &[Fr], lagrange: &[Fr]) -> Fr {
+	inputs.iter().zip(lagrange).fold(Fr::zero(), |pi, (input, l)| pi - *input * l)
+}
@@ -0,0 +1,872 @@
+use crate::{
+	bls12_381, bn254,
+	fflonk::{self, Fflonk},
+	groth16::{self, Curve, Groth16Bls12_381, Groth16Bn254},
+	mock::*,
+	ops::Host,
+	plonk::{self, Plonk},
+	snarkjs::Transcript,
+	BackendError, EncodedKeyOf, EncodedProofOf, Error, Event, ProvingSystem, PublicInputsOf,
+	ScalarBytes, VerificationKey, VerificationKeyInfo, VerificationKeys, VerifierRegistry,
+};
+use ark_bn254::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{FftField, Field, One, PrimeField, Zero};
+use ark_poly::{
+	univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial,
+	Radix2EvaluationDomain,
+};
+use ark_relations::{
+	lc,
+	r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_snark::SNARK;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+/// A key, a proof against it and the public inputs it was made for.
+struct Fixture {
+	system: ProvingSystem,
+	key: EncodedKeyOf<Test>,
+	proof: EncodedProofOf<Test>,
+	inputs: PublicInputsOf<Test>,
+}
+
+fn public_inputs(values: &[u64]) -> PublicInputsOf<Test> {
+	values
+		.iter()
+		.map(|v| {
+			let mut input = [0u8; 32];
+			input[24..].copy_from_slice(&v.to_be_bytes());
+			input
+		})
+		.collect::<Vec<ScalarBytes>>()
+		.try_into()
+		.unwrap()
+}
+
+fn bounded<T: TryFrom<Vec<u8>>>(value: impl Encode) -> T {
+	value.encode().try_into().ok().unwrap()
+}
+
+/// Proves knowledge of `a` and `b` with public `a * b` and `a + b`.
+#[derive(Clone)]
+struct MulAddCircuit<F> {
+	a: Option<F>,
+	b: Option<F>,
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for MulAddCircuit<F> {
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		let a_value = self.a.ok_or(SynthesisError::AssignmentMissing);
+		let b_value = self.b.ok_or(SynthesisError::AssignmentMissing);
+		let a = cs.new_witness_variable(|| a_value)?;
+		let b = cs.new_witness_variable(|| b_value)?;
+		let product = cs.new_input_variable(|| Ok(a_value? * b_value?))?;
+		let sum = cs.new_input_variable(|| Ok(a_value? + b_value?))?;
+		cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+		cs.enforce_constraint(lc!() + a + b, lc!() + Variable::One, lc!() + sum)?;
+		Ok(())
+	}
+}
+
+/// Runs a Groth16 trusted setup over `C` and proves `3 * 11 = 33`, `3 + 11 = 14`.
+fn groth16_fixture<C: Curve>(system: ProvingSystem) -> Fixture {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (pk, vk) = ark_groth16::Groth16::<C>::circuit_specific_setup(
+		MulAddCircuit { a: None, b: None },
+		&mut rng,
+	)
+	.unwrap();
+	let circuit = MulAddCircuit { a: Some(3u64.into()), b: Some(11u64.into()) };
+	let proof = ark_groth16::Groth16::<C>::prove(&pk, circuit, &mut rng).unwrap();
+
+	Fixture {
+		system,
+		key: bounded(groth16::VerificationKey {
+			alpha_g1: C::encode_g1(&vk.alpha_g1),
+			beta_g2: C::encode_g2(&vk.beta_g2),
+			gamma_g2: C::encode_g2(&vk.gamma_g2),
+			delta_g2: C::encode_g2(&vk.delta_g2),
+			ic_base: C::encode_g1(&vk.gamma_abc_g1[0]),
+			ic: vk.gamma_abc_g1[1..].iter().map(C::encode_g1).collect(),
+		}),
+		proof: bounded(groth16::Proof {
+			a: C::encode_g1(&proof.a),
+			b: C::encode_g2(&proof.b),
+			c: C::encode_g1(&proof.c),
+		}),
+		inputs: public_inputs(&[33, 14]),
+	}
+}
+
+fn groth16_bn254() -> Fixture {
+	groth16_fixture::<ark_bn254::Bn254>(ProvingSystem::Groth16Bn254)
+}
+
+fn groth16_bls12_381() -> Fixture {
+	groth16_fixture::<ark_bls12_381::Bls12_381>(ProvingSystem::Groth16Bls12_381)
+}
+
+/// The secret of the test setup. Commitments are `p(TAU) * G1` and KZG openings are computed
+/// from it directly.
+const TAU: u64 = 0x5eed;
+
+fn commit(value: Fr) -> G1Affine {
+	(G1Affine::generator() * value).into_affine()
+}
+
+fn x_2() -> G2Affine {
+	(G2Affine::generator() * Fr::from(TAU)).into_affine()
+}
+
+/// The value at `x` of the polynomial taking `values` at `xs`.
+fn interpolate(xs: &[Fr], values: &[Fr], x: Fr) -> Fr {
+	xs.iter()
+		.zip(values)
+		.map(|(xi, value)| {
+			xs.iter()
+				.filter(|xj| *xj != xi)
+				.fold(*value, |acc, xj| acc * (x - xj) / (*xi - xj))
+		})
+		.sum()
+}
+
+fn vanishing(xs: &[Fr], x: Fr) -> Fr {
+	xs.iter().map(|xi| x - xi).product()
+}
+
+/// `sum(x^i * polys[i](x^m))`, the value at `x` of the fflonk combination of `polys`.
+fn combine(polys: &[&DensePolynomial<Fr>], m: u64, x: Fr) -> Fr {
+	let xm = x.pow([m]);
+	polys.iter().rev().fold(Fr::zero(), |acc, p| acc * x + p.evaluate(&xm))
+}
+
+/// A PLONK circuit over eight rows proving knowledge of `x` with public `x` and `x^2`.
+///
+/// Rows 0 and 1 take the public inputs through `a`, row 2 multiplies `x` by itself and copy
+/// constraints tie its wires to the inputs.
+struct Circuit {
+	domain: Radix2EvaluationDomain<Fr>,
+	inputs: Vec<Fr>,
+	/// `k1` and `k2`, labelling the `b` and `c` columns of the permutation.
+	k: [Fr; 2],
+	ql: DensePolynomial<Fr>,
+	qr: DensePolynomial<Fr>,
+	qm: DensePolynomial<Fr>,
+	qo: DensePolynomial<Fr>,
+	qc: DensePolynomial<Fr>,
+	/// `S1`, `S2` and `S3`, with their values on the domain.
+	sigma: [(Vec<Fr>, DensePolynomial<Fr>); 3],
+	/// `a`, `b` and `c`, with their values on the domain.
+	wires: [(Vec<Fr>, DensePolynomial<Fr>); 3],
+}
+
+impl Circuit {
+	const N: usize = 8;
+
+	fn new(x: u64) -> Self {
+		let domain = Radix2EvaluationDomain::<Fr>::new(Self::N).unwrap();
+		let interpolate =
+			|values: Vec<Fr>| Evaluations::from_vec_and_domain(values, domain).interpolate();
+		let column = |values: &[i64]| {
+			let mut column: Vec<Fr> = values.iter().map(|v| Fr::from(*v)).collect();
+			column.resize(Self::N, Fr::zero());
+			column
+		};
+		let (x, y) = (Fr::from(x), Fr::from(x * x));
+		let k = [Fr::from(2u64), Fr::from(3u64)];
+
+		// Positions are numbered column by column; `a0 -> a2 -> b2 -> a0` and `a1 <-> c2`.
+		let n = Self::N;
+		let mut sigma: Vec<usize> = (0..3 * n).collect();
+		sigma[0] = 2;
+		sigma[2] = n + 2;
+		sigma[n + 2] = 0;
+		sigma[1] = 2 * n + 2;
+		sigma[2 * n + 2] = 1;
+		let label = |position: usize| {
+			let column = [Fr::one(), k[0], k[1]][position / n];
+			column * domain.element(position % n)
+		};
+		let sigma = [0, 1, 2].map(|column| {
+			let values: Vec<Fr> = (0..n).map(|row| label(sigma[column * n + row])).collect();
+			(values.clone(), interpolate(values))
+		});
+		let zero = Fr::zero();
+		let wires = [vec![x, y, x], vec![zero, zero, x], vec![zero, zero, y]].map(|mut values| {
+			values.resize(n, zero);
+			(values.clone(), interpolate(values))
+		});
+
+		Self {
+			domain,
+			inputs: vec![x, y],
+			k,
+			ql: interpolate(column(&[1, 1])),
+			qr: interpolate(column(&[])),
+			qm: interpolate(column(&[0, 0, 1])),
+			qo: interpolate(column(&[0, 0, -1])),
+			qc: interpolate(column(&[])),
+			sigma,
+			wires,
+		}
+	}
+
+	fn interpolate(&self, values: Vec<Fr>) -> DensePolynomial<Fr> {
+		Evaluations::from_vec_and_domain(values, self.domain).interpolate()
+	}
+
+	fn constant(value: Fr) -> DensePolynomial<Fr> {
+		DensePolynomial::from_coefficients_vec(vec![value])
+	}
+
+	fn w(&self) -> Fr {
+		self.domain.group_gen
+	}
+
+	fn power(&self) -> u32 {
+		self.domain.log_size_of_group
+	}
+
+	/// `PI(X) = -sum(input_i * L_i(X))`.
+	fn pi(&self) -> DensePolynomial<Fr> {
+		let mut values: Vec<Fr> = self.inputs.iter().map(|input| -*input).collect();
+		values.resize(Self::N, Fr::zero());
+		self.interpolate(values)
+	}
+
+	fn l1(&self) -> DensePolynomial<Fr> {
+		let mut values = vec![Fr::zero(); Self::N];
+		values[0] = Fr::one();
+		self.interpolate(values)
+	}
+
+	/// `p(X * w)`.
+	fn shift(&self, p: &DensePolynomial<Fr>) -> DensePolynomial<Fr> {
+		let coeffs = p.coeffs.iter().zip(self.domain.elements()).map(|(c, w)| *c * w).collect();
+		DensePolynomial::from_coefficients_vec(coeffs)
+	}
+
+	/// The quotient of `p` by the vanishing polynomial of the domain, which must divide it.
+	fn quotient(&self, p: &DensePolynomial<Fr>) -> DensePolynomial<Fr> {
+		let (quotient, remainder) = p.divide_by_vanishing_poly(self.domain);
+		assert!(remainder.is_zero(), "the circuit is not satisfied");
+		quotient
+	}
+
+	/// The permutation accumulator `Z`.
+	fn z(&self, beta: Fr, gamma: Fr) -> DensePolynomial<Fr> {
+		let mut values = vec![Fr::one()];
+		for row in 0..Self::N {
+			let w = self.domain.element(row);
+			let mut factor = Fr::one();
+			for (column, k) in [Fr::one(), self.k[0], self.k[1]].into_iter().enumerate() {
+				let wire = self.wires[column].0[row];
+				factor *= (wire + beta * k * w + gamma) /
+					(wire + beta * self.sigma[column].0[row] + gamma);
+			}
+			values.push(*values.last().unwrap() * factor);
+		}
+		assert_eq!(values.pop(), Some(Fr::one()), "the copy constraints do not hold");
+		self.interpolate(values)
+	}
+
+	/// The gate constraint including the public inputs.
+	fn gate(&self) -> DensePolynomial<Fr> {
+		let [(_, a), (_, b), (_, c)] = &self.wires;
+		[&(&(&self.qm * a) * b), &(&self.ql * a), &(&self.qr * b), &(&self.qo * c), &self.pi()]
+			.into_iter()
+			.fold(self.qc.clone(), |acc, term| &acc + term)
+	}
+
+	/// The permutation constraint `Z(X) * prod(w + beta k X + gamma) -
+	/// Z(X w) * prod(w + beta S + gamma)`.
+	fn permutation(&self, z: &DensePolynomial<Fr>, beta: Fr, gamma: Fr) -> DensePolynomial<Fr> {
+		let mut identity = z.clone();
+		let mut permuted = self.shift(z);
+		for (column, k) in [Fr::one(), self.k[0], self.k[1]].into_iter().enumerate() {
+			let wire = &self.wires[column].1;
+			let label = DensePolynomial::from_coefficients_vec(vec![gamma, beta * k]);
+			identity = &identity * &(wire + &label);
+			let sigma = &(&self.sigma[column].1 * beta) + &Self::constant(gamma);
+			permuted = &permuted * &(wire + &sigma);
+		}
+		&identity - &permuted
+	}
+}
+
+/// Proves `3 * 3 = 9` with the snarkjs PLONK protocol.
+fn plonk_fixture() -> Fixture {
+	let circuit = Circuit::new(3);
+	let tau = Fr::from(TAU);
+	let [(_, a), (_, b), (_, c)] = &circuit.wires;
+	let [(_, s1), (_, s2), (_, s3)] = &circuit.sigma;
+	let (n, w) = (Circuit::N as u64, circuit.w());
+	let at_tau = |p: &DensePolynomial<Fr>| commit(p.evaluate(&tau));
+
+	let selectors = [&circuit.qm, &circuit.ql, &circuit.qr, &circuit.qo, &circuit.qc, s1, s2, s3];
+	let mut transcript = Transcript::default();
+	for p in selectors {
+		transcript.add_point(&at_tau(p));
+	}
+	for input in &circuit.inputs {
+		transcript.add_scalar(input);
+	}
+	let wires = [a, b, c].map(at_tau);
+	for point in &wires {
+		transcript.add_point(point);
+	}
+	let beta = transcript.challenge();
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+
+	let z = circuit.z(beta, gamma);
+	transcript.add_scalar(&beta);
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&at_tau(&z));
+	let alpha = transcript.challenge();
+
+	let z_minus_one = &z - &Circuit::constant(Fr::one());
+	let permutation = &circuit.permutation(&z, beta, gamma) * alpha;
+	let first = &(&z_minus_one * &circuit.l1()) * alpha.square();
+	let t = circuit.quotient(&(&(&circuit.gate() + &permutation) + &first));
+	assert!(t.coeffs.len() <= 3 * n as usize);
+	let split = |i: usize| {
+		let coeffs = t.coeffs.iter().skip(i * n as usize).take(n as usize).copied().collect();
+		DensePolynomial::from_coefficients_vec(coeffs)
+	};
+	let [t1, t2, t3] = [0, 1, 2].map(split);
+	transcript.add_scalar(&alpha);
+	for p in [&t1, &t2, &t3] {
+		transcript.add_point(&at_tau(p));
+	}
+	let xi = transcript.challenge();
+
+	let [eval_a, eval_b, eval_c, eval_s1, eval_s2] = [a, b, c, s1, s2].map(|p| p.evaluate(&xi));
+	let eval_zw = z.evaluate(&(xi * w));
+	transcript.add_scalar(&xi);
+	for eval in [&eval_a, &eval_b, &eval_c, &eval_s1, &eval_s2, &eval_zw] {
+		transcript.add_scalar(eval);
+	}
+	let v = transcript.challenge();
+
+	// The linearisation polynomial, which vanishes at `xi`.
+	let xin = xi.pow([n]);
+	let [k1, k2] = circuit.k;
+	let linearisation = |x: Fr| {
+		let gate = eval_a * eval_b * circuit.qm.evaluate(&x) +
+			eval_a * circuit.ql.evaluate(&x) +
+			eval_b * circuit.qr.evaluate(&x) +
+			eval_c * circuit.qo.evaluate(&x) +
+			circuit.qc.evaluate(&x) +
+			circuit.pi().evaluate(&xi);
+		let permutation = (eval_a + beta * xi + gamma) *
+			(eval_b + beta * k1 * xi + gamma) *
+			(eval_c + beta * k2 * xi + gamma) *
+			z.evaluate(&x) -
+			(eval_a + beta * eval_s1 + gamma) *
+				(eval_b + beta * eval_s2 + gamma) *
+				(eval_c + beta * s3.evaluate(&x) + gamma) *
+				eval_zw;
+		let first = (z.evaluate(&x) - Fr::one()) * circuit.l1().evaluate(&xi);
+		let quotient = (xin - Fr::one()) *
+			(t1.evaluate(&x) + xin * t2.evaluate(&x) + xin.square() * t3.evaluate(&x));
+		gate + alpha * permutation + alpha.square() * first - quotient
+	};
+	assert!(linearisation(xi).is_zero());
+	let opened = [(a, eval_a), (b, eval_b), (c, eval_c), (s1, eval_s1), (s2, eval_s2)]
+		.iter()
+		.zip(core::iter::successors(Some(v), |power| Some(*power * v)))
+		.map(|((p, eval), power)| power * (p.evaluate(&tau) - eval))
+		.sum::<Fr>();
+	let wxi = (linearisation(tau) + opened) / (tau - xi);
+	let wxiw = (z.evaluate(&tau) - eval_zw) / (tau - xi * w);
+
+	let g1 = |p: &DensePolynomial<Fr>| bn254::encode_g1(&at_tau(p));
+	let scalar = bn254::encode_scalar;
+	Fixture {
+		system: ProvingSystem::PlonkBn254,
+		key: bounded(plonk::VerificationKey {
+			n_public: 2,
+			power: circuit.power(),
+			k1: scalar(&k1),
+			k2: scalar(&k2),
+			qm: g1(&circuit.qm),
+			ql: g1(&circuit.ql),
+			qr: g1(&circuit.qr),
+			qo: g1(&circuit.qo),
+			qc: g1(&circuit.qc),
+			s1: g1(s1),
+			s2: g1(s2),
+			s3: g1(s3),
+			x_2: bn254::encode_g2(&x_2()),
+			w: scalar(&w),
+		}),
+		proof: bounded(plonk::Proof {
+			a: bn254::encode_g1(&wires[0]),
+			b: bn254::encode_g1(&wires[1]),
+			c: bn254::encode_g1(&wires[2]),
+			z: g1(&z),
+			t1: g1(&t1),
+			t2: g1(&t2),
+			t3: g1(&t3),
+			wxi: bn254::encode_g1(&commit(wxi)),
+			wxiw: bn254::encode_g1(&commit(wxiw)),
+			eval_a: scalar(&eval_a),
+			eval_b: scalar(&eval_b),
+			eval_c: scalar(&eval_c),
+			eval_s1: scalar(&eval_s1),
+			eval_s2: scalar(&eval_s2),
+			eval_zw: scalar(&eval_zw),
+		}),
+		inputs: public_inputs(&[3, 9]),
+	}
+}
+
+/// Proves `3 * 3 = 9` with the snarkjs fflonk protocol.
+fn fflonk_fixture() -> Fixture {
+	let circuit = Circuit::new(3);
+	let tau = Fr::from(TAU);
+	let [(_, a), (_, b), (_, c)] = &circuit.wires;
+	let [(_, s1), (_, s2), (_, s3)] = &circuit.sigma;
+	let (n, w) = (Circuit::N as u64, circuit.w());
+	let root = |order: u64| Fr::get_root_of_unity(order).unwrap();
+	let (w3, w4, w8) = (root(3), root(4), root(8));
+	// A cube root of `w`.
+	let wr = (0..3 * n)
+		.map(|i| root(3 * n).pow([i]))
+		.find(|wr| wr.pow([3]) == w)
+		.unwrap();
+
+	let c0 = [&circuit.ql, &circuit.qr, &circuit.qo, &circuit.qm, &circuit.qc, s1, s2, s3];
+	let c0_commitment = commit(combine(&c0, 8, tau));
+	let mut transcript = Transcript::default();
+	transcript.add_point(&c0_commitment);
+	for input in &circuit.inputs {
+		transcript.add_scalar(input);
+	}
+	let t0 = circuit.quotient(&circuit.gate());
+	let c1 = [a, b, c, &t0];
+	let c1_commitment = commit(combine(&c1, 4, tau));
+	transcript.add_point(&c1_commitment);
+	let beta = transcript.challenge();
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+
+	let z = circuit.z(beta, gamma);
+	let t1 = circuit.quotient(&(&(&z - &Circuit::constant(Fr::one())) * &circuit.l1()));
+	let t2 = circuit.quotient(&circuit.permutation(&z, beta, gamma));
+	let c2 = [&z, &t1, &t2];
+	let c2_commitment = commit(combine(&c2, 3, tau));
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&c2_commitment);
+	let xi_seed = transcript.challenge();
+
+	let coset = |root: Fr, w: Fr, count: usize| -> Vec<Fr> {
+		core::iter::successors(Some(root), |h| Some(*h * w)).take(count).collect()
+	};
+	let h0 = xi_seed.pow([3]);
+	let h2 = xi_seed.pow([8]);
+	let xi = xi_seed.pow([24]);
+	let roots0 = coset(h0, w8, 8);
+	let roots1 = coset(xi_seed.pow([6]), w4, 4);
+	let mut roots2 = coset(h2, w3, 3);
+	roots2.extend(coset(h2 * wr, w3, 3));
+
+	let evals = [&circuit.ql, &circuit.qr, &circuit.qm, &circuit.qo, &circuit.qc, s1, s2, s3]
+		.into_iter()
+		.chain([a, b, c, &z])
+		.map(|p| p.evaluate(&xi))
+		.collect::<Vec<_>>();
+	let evals: [Fr; 12] = evals.try_into().unwrap();
+	let shifted = [&z, &t1, &t2].map(|p| p.evaluate(&(xi * w)));
+	transcript.add_scalar(&xi_seed);
+	for eval in evals.iter().chain(&shifted) {
+		transcript.add_scalar(eval);
+	}
+	let alpha = transcript.challenge();
+
+	// Each commitment opened at its roots, as `(value at tau, roots, values at the roots)`.
+	let opening = |polys: &[&DensePolynomial<Fr>], m, roots: &Vec<Fr>| {
+		let values: Vec<Fr> = roots.iter().map(|h| combine(polys, m, *h)).collect();
+		(combine(polys, m, tau), roots.clone(), values)
+	};
+	let openings = [opening(&c0, 8, &roots0), opening(&c1, 4, &roots1), opening(&c2, 3, &roots2)];
+	let w1: Fr = openings
+		.iter()
+		.zip([Fr::one(), alpha, alpha.square()])
+		.map(|((at_tau, roots, values), power)| {
+			power * (*at_tau - interpolate(roots, values, tau)) / vanishing(roots, tau)
+		})
+		.sum();
+	let w1_commitment = commit(w1);
+	transcript.add_scalar(&alpha);
+	transcript.add_point(&w1_commitment);
+	let y = transcript.challenge();
+
+	let z0 = vanishing(&roots0, y);
+	let w2 = openings
+		.iter()
+		.zip([
+			Fr::one(),
+			alpha * z0 / vanishing(&roots1, y),
+			alpha.square() * z0 / vanishing(&roots2, y),
+		])
+		.map(|((at_tau, roots, values), quotient)| {
+			quotient * (*at_tau - interpolate(roots, values, y))
+		})
+		.sum::<Fr>() -
+		z0 * w1;
+	let w2 = w2 / (tau - y);
+
+	let scalar = bn254::encode_scalar;
+	let [ql, qr, qm, qo, qc, eval_s1, eval_s2, eval_s3, eval_a, eval_b, eval_c, eval_z] =
+		evals.map(|eval| scalar(&eval));
+	let [zw, t1w, t2w] = shifted.map(|eval| scalar(&eval));
+	Fixture {
+		system: ProvingSystem::FflonkBn254,
+		key: bounded(fflonk::VerificationKey {
+			n_public: 2,
+			power: circuit.power(),
+			k1: scalar(&circuit.k[0]),
+			k2: scalar(&circuit.k[1]),
+			w: scalar(&w),
+			w3: scalar(&w3),
+			w4: scalar(&w4),
+			w8: scalar(&w8),
+			wr: scalar(&wr),
+			x_2: bn254::encode_g2(&x_2()),
+			c0: bn254::encode_g1(&c0_commitment),
+		}),
+		proof: bounded(fflonk::Proof {
+			c1: bn254::encode_g1(&c1_commitment),
+			c2: bn254::encode_g1(&c2_commitment),
+			w1: bn254::encode_g1(&w1_commitment),
+			w2: bn254::encode_g1(&commit(w2)),
+			ql,
+			qr,
+			qm,
+			qo,
+			qc,
+			s1: eval_s1,
+			s2: eval_s2,
+			s3: eval_s3,
+			a: eval_a,
+			b: eval_b,
+			c: eval_c,
+			z: eval_z,
+			zw,
+			t1w,
+			t2w,
+			inv: [0; 32],
+		}),
+		inputs: public_inputs(&[3, 9]),
+	}
+}
+
+fn all_fixtures() -> [Fixture; 4] {
+	[groth16_bn254(), groth16_bls12_381(), plonk_fixture(), fflonk_fixture()]
+}
+
+fn register(fixture: &Fixture) {
+	assert_ok!(ZkVerifier::register_verification_key(
+		RuntimeOrigin::root(),
+		7,
+		fixture.system,
+		fixture.key.clone()
+	));
+}
+
+#[test]
+fn register_verification_key_works() {
+	new_test_ext().execute_with(|| {
+		for (vk_id, fixture) in (0..).zip(all_fixtures()) {
+			let Fixture { system, key, .. } = fixture;
+			assert_ok!(ZkVerifier::register_verification_key(
+				RuntimeOrigin::root(),
+				vk_id,
+				system,
+				key.clone()
+			));
+
+			assert_eq!(
+				VerificationKeys::<Test>::get(vk_id),
+				Some(VerificationKey { system, public_input_count: 2, key: key.clone() })
+			);
+			System::assert_last_event(Event::VerificationKeyRegistered { vk_id, system }.into());
+			assert_noop!(
+				ZkVerifier::register_verification_key(RuntimeOrigin::root(), vk_id, system, key),
+				Error::<Test>::VerificationKeyExists
+			);
+		}
+	});
+}
+
+#[test]
+fn register_verification_key_requires_manager_origin() {
+	new_test_ext().execute_with(|| {
+		let Fixture { system, key, .. } = groth16_bn254();
+		assert_noop!(
+			ZkVerifier::register_verification_key(RuntimeOrigin::signed(1), 7, system, key),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn register_verification_key_rejects_invalid_points() {
+	new_test_ext().execute_with(|| {
+		let Fixture { system, key, .. } = groth16_bn254();
+		let mut vk = bn254::Groth16VerificationKey::decode(&mut &key[..]).unwrap();
+		vk.alpha_g1[63] ^= 1;
+		assert_noop!(
+			ZkVerifier::register_verification_key(RuntimeOrigin::root(), 7, system, bounded(vk)),
+			Error::<Test>::InvalidVerificationKey
+		);
+
+		let Fixture { system, key, .. } = groth16_bls12_381();
+		let mut vk = bls12_381::Groth16VerificationKey::decode(&mut &key[..]).unwrap();
+		vk.beta_g2[255] ^= 1;
+		assert_noop!(
+			ZkVerifier::register_verification_key(RuntimeOrigin::root(), 7, system, bounded(vk)),
+			Error::<Test>::InvalidVerificationKey
+		);
+	});
+}
+
+#[test]
+fn register_verification_key_rejects_invalid_domain() {
+	new_test_ext().execute_with(|| {
+		let Fixture { system, key, .. } = plonk_fixture();
+		let mut vk = plonk::VerificationKey::decode(&mut &key[..]).unwrap();
+		vk.w = bn254::encode_scalar(&Fr::from(5u64));
+		assert_noop!(
+			ZkVerifier::register_verification_key(RuntimeOrigin::root(), 7, system, bounded(vk)),
+			Error::<Test>::InvalidVerificationKey
+		);
+
+		let Fixture { system, key, .. } = fflonk_fixture();
+		let mut vk = fflonk::VerificationKey::decode(&mut &key[..]).unwrap();
+		vk.power = u32::MAX;
+		assert_noop!(
+			ZkVerifier::register_verification_key(RuntimeOrigin::root(), 7, system, bounded(vk)),
+			Error::<Test>::InvalidVerificationKey
+		);
+	});
+}
+
+#[test]
+fn register_verification_key_rejects_key_of_other_system() {
+	new_test_ext().execute_with(|| {
+		let Fixture { key, .. } = groth16_bn254();
+		for system in [ProvingSystem::Groth16Bls12_381, ProvingSystem::PlonkBn254] {
+			assert_noop!(
+				ZkVerifier::register_verification_key(
+					RuntimeOrigin::root(),
+					7,
+					system,
+					key.clone()
+				),
+				Error::<Test>::InvalidVerificationKey
+			);
+		}
+	});
+}
+
+#[test]
+fn register_verification_key_rejects_too_many_public_inputs() {
+	new_test_ext().execute_with(|| {
+		let g1 = bn254::encode_g1(&G1Affine::generator());
+		let g2 = bn254::encode_g2(&G2Affine::generator());
+		let vk = bn254::Groth16VerificationKey {
+			alpha_g1: g1,
+			beta_g2: g2,
+			gamma_g2: g2,
+			delta_g2: g2,
+			ic_base: g1,
+			ic: vec![g1; 17],
+		};
+		assert_noop!(
+			ZkVerifier::register_verification_key(
+				RuntimeOrigin::root(),
+				7,
+				ProvingSystem::Groth16Bn254,
+				bounded(vk)
+			),
+			Error::<Test>::TooManyPublicInputs
+		);
+	});
+}
+
+#[test]
+fn registry_rejects_unsupported_system() {
+	type Registry = (Groth16Bn254,);
+
+	let Fixture { key, proof, inputs, .. } = groth16_bn254();
+	assert!(Registry::supports(ProvingSystem::Groth16Bn254));
+	assert!(!Registry::supports(ProvingSystem::PlonkBn254));
+	assert_eq!(Registry::validate_key(ProvingSystem::Groth16Bn254, &key), Ok(2));
+	assert_eq!(
+		Registry::validate_key(ProvingSystem::PlonkBn254, &key),
+		Err(BackendError::UnsupportedProvingSystem)
+	);
+	assert_eq!(
+		Registry::verify(ProvingSystem::FflonkBn254, &key, &proof, &inputs),
+		Err(BackendError::UnsupportedProvingSystem)
+	);
+	assert!(Registry::verify_weight::<()>(ProvingSystem::PlonkBn254, 2).is_zero());
+}
+
+#[test]
+fn host_backends_match_native_ones() {
+	type HostBackends = (Groth16Bn254<Host>, Groth16Bls12_381<Host>, Plonk<Host>, Fflonk<Host>);
+
+	for Fixture { system, key, proof, inputs } in all_fixtures() {
+		assert_eq!(HostBackends::verify(system, &key, &proof, &inputs), Ok(true));
+
+		let mut wrong_inputs = inputs.clone();
+		wrong_inputs[1][31] += 1;
+		assert_eq!(HostBackends::verify(system, &key, &proof, &wrong_inputs), Ok(false));
+
+		// Only the host variants are charged the cheaper weights.
+		let native = <<Test as crate::Config>::Backends>::verify_weight::<()>(system, 2);
+		assert!(HostBackends::verify_weight::<()>(system, 2).ref_time() < native.ref_time());
+	}
+}
+
+#[test]
+fn verification_key_info_works() {
+	new_test_ext().execute_with(|| {
+		let fixture = plonk_fixture();
+		assert_eq!(ZkVerifier::verification_key_info(7), None);
+
+		register(&fixture);
+		assert_eq!(
+			ZkVerifier::verification_key_info(7),
+			Some(VerificationKeyInfo { system: ProvingSystem::PlonkBn254, public_input_count: 2 })
+		);
+	});
+}
+
+#[test]
+fn remove_verification_key_works() {
+	new_test_ext().execute_with(|| {
+		register(&groth16_bn254());
+
+		assert_noop!(
+			ZkVerifier::remove_verification_key(RuntimeOrigin::signed(1), 7),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ZkVerifier::remove_verification_key(RuntimeOrigin::root(), 7));
+		assert!(!VerificationKeys::<Test>::contains_key(7));
+		System::assert_last_event(Event::VerificationKeyRemoved { vk_id: 7 }.into());
+		assert_noop!(
+			ZkVerifier::remove_verification_key(RuntimeOrigin::root(), 7),
+			Error::<Test>::UnknownVerificationKey
+		);
+	});
+}
+
+#[test]
+fn verify_accepts_valid_proofs() {
+	for fixture in all_fixtures() {
+		new_test_ext().execute_with(|| {
+			register(&fixture);
+
+			let Fixture { system, proof, inputs, .. } = fixture;
+			let info = ZkVerifier::verify(RuntimeOrigin::signed(1), 7, proof, inputs).unwrap();
+			System::assert_last_event(Event::ProofVerified { vk_id: 7, who: 1 }.into());
+			// Charged for the backend of the key only.
+			assert_eq!(info.actual_weight, Some(ZkVerifier::verify_weight(system, 2)));
+		});
+	}
+}
+
+#[test]
+fn verify_rejects_wrong_public_inputs() {
+	for fixture in all_fixtures() {
+		new_test_ext().execute_with(|| {
+			register(&fixture);
+
+			let mut inputs = fixture.inputs.clone();
+			inputs[1][31] += 1;
+			assert_ok!(ZkVerifier::verify(RuntimeOrigin::signed(1), 7, fixture.proof, inputs));
+			System::assert_last_event(Event::ProofRejected { vk_id: 7, who: 1 }.into());
+		});
+	}
+}
+
+#[test]
+fn verify_rejects_tampered_proof() {
+	new_test_ext().execute_with(|| {
+		let fixture = groth16_bn254();
+		register(&fixture);
+
+		let mut proof = bn254::Groth16Proof::decode(&mut &fixture.proof[..]).unwrap();
+		proof.c = bn254::encode_g1(&G1Affine::zero());
+		assert_ok!(ZkVerifier::verify(RuntimeOrigin::signed(1), 7, bounded(proof), fixture.inputs));
+		System::assert_last_event(Event::ProofRejected { vk_id: 7, who: 1 }.into());
+	});
+
+	new_test_ext().execute_with(|| {
+		let fixture = fflonk_fixture();
+		register(&fixture);
+
+		let mut proof = fflonk::Proof::decode(&mut &fixture.proof[..]).unwrap();
+		proof.t2w = bn254::encode_scalar(&Fr::one());
+		assert_ok!(ZkVerifier::verify(RuntimeOrigin::signed(1), 7, bounded(proof), fixture.inputs));
+		System::assert_last_event(Event::ProofRejected { vk_id: 7, who: 1 }.into());
+	});
+}
+
+#[test]
+fn verify_fails_on_malformed_input() {
+	new_test_ext().execute_with(|| {
+		let Fixture { proof, inputs, .. } = groth16_bn254();
+		let verify = |proof: &EncodedProofOf<Test>, inputs: PublicInputsOf<Test>| {
+			ZkVerifier::verify(RuntimeOrigin::signed(1), 7, proof.clone(), inputs)
+		};
+
+		assert_noop!(verify(&proof, inputs.clone()), Error::<Test>::UnknownVerificationKey);
+
+		register(&groth16_bn254());
+		assert_noop!(verify(&proof, public_inputs(&[33])), Error::<Test>::PublicInputCountMismatch);
+
+		let mut out_of_range = inputs.clone();
+		out_of_range[0] = [0xff; 32];
+		assert_noop!(verify(&proof, out_of_range), Error::<Test>::InvalidPublicInput);
+
+		let mut off_curve = bn254::Groth16Proof::decode(&mut &proof[..]).unwrap();
+		off_curve.a[63] ^= 1;
+		assert_noop!(verify(&bounded(off_curve), inputs.clone()), Error::<Test>::MalformedProof);
+
+		let truncated = bounded(&proof[..proof.len() - 1]);
+		assert_noop!(verify(&truncated, inputs), Error::<Test>::MalformedProof);
+	});
+}
+
+#[test]
+fn point_encoding_roundtrips() {
+	let g1 = G1Affine::generator();
+	let g2 = G2Affine::generator();
+	assert_eq!(bn254::decode_g1(&bn254::encode_g1(&g1)), Some(g1));
+	assert_eq!(bn254::decode_g2(&bn254::encode_g2(&g2)), Some(g2));
+	assert_eq!(bn254::decode_g1(&[0; 64]), Some(G1Affine::zero()));
+	// The G1 generator is (1, 2) in the EIP-196 encoding.
+	let encoded = bn254::encode_g1(&g1);
+	assert_eq!(encoded[31], 1);
+	assert_eq!(encoded[63], 2);
+
+	let g1 = ark_bls12_381::G1Affine::generator();
+	let g2 = ark_bls12_381::G2Affine::generator();
+	assert_eq!(bls12_381::decode_g1(&bls12_381::encode_g1(&g1)), Some(g1));
+	assert_eq!(bls12_381::decode_g2(&bls12_381::encode_g2(&g2)), Some(g2));
+	assert_eq!(bls12_381::decode_g1(&[0; 128]), Some(ark_bls12_381::G1Affine::zero()));
+	// Field elements are padded to 64 bytes in the EIP-2537 encoding.
+	let encoded = bls12_381::encode_g1(&g1);
+	assert_eq!(encoded[..16], [0; 16]);
+	assert_eq!(encoded[64..80], [0; 16]);
+}
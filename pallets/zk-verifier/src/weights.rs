@@ -0,0 +1,226 @@
+//! Weights for pallet_zk_verifier
+//!
+//! These are hand-written, conservative estimates derived from the cost of BN254 and BLS12-381
+//! arithmetic in the Wasm executor, not the output of the benchmarks in `benchmarking.rs`. The
+//! `*_host` variants are for backends computing pairings and multi-scalar multiplications through
+//! host functions, which leaves only decoding, point validation and field arithmetic in Wasm.
+//! Only the Groth16 multi-scalar multiplication grows with the public inputs; PLONK and fflonk
+//! multiply a fixed number of points and spend their per-input cost on the transcript and the
+//! Lagrange evaluations, which stay in Wasm, so their host slopes are the native ones. The
+//! `hash_*` weights are for Poseidon and MiMC-7 from `kora_zk_hash`. Replace this file with the
+//! benchmarked weights, generated on reference hardware with:
+//!
+//! ```text
+//! kora-chain-node benchmark pallet --chain dev --pallet pallet_zk_verifier --extrinsic '*' \
+//!     --steps 50 --repeat 20 --output pallets/zk-verifier/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_zk_verifier.
+pub trait WeightInfo {
+	fn register_groth16_bn254(n: u32, ) -> Weight;
+	fn register_groth16_bls12_381(n: u32, ) -> Weight;
+	fn register_plonk_bn254() -> Weight;
+	fn register_fflonk_bn254() -> Weight;
+	fn remove_verification_key() -> Weight;
+	fn verify_groth16_bn254(n: u32, ) -> Weight;
+	fn verify_groth16_bls12_381(n: u32, ) -> Weight;
+	fn verify_plonk_bn254(n: u32, ) -> Weight;
+	fn verify_fflonk_bn254(n: u32, ) -> Weight;
+	fn verify_groth16_bn254_host(n: u32, ) -> Weight;
+	fn verify_groth16_bls12_381_host(n: u32, ) -> Weight;
+	fn verify_plonk_bn254_host(n: u32, ) -> Weight;
+	fn verify_fflonk_bn254_host(n: u32, ) -> Weight;
+	fn hash_poseidon(n: u32, ) -> Weight;
+	fn hash_mimc(n: u32, ) -> Weight;
+}
+
+/// Weights for pallet_zk_verifier using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:1)
+	/// The range of component `n` is `[0, 64]`.
+	fn register_groth16_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(4_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:1)
+	/// The range of component `n` is `[0, 64]`.
+	fn register_groth16_bls12_381(n: u32, ) -> Weight {
+		Weight::from_parts(6_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(110_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:1)
+	fn register_plonk_bn254() -> Weight {
+		Weight::from_parts(2_000_000_000, 16_500)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:1)
+	fn register_fflonk_bn254() -> Weight {
+		Weight::from_parts(1_600_000_000, 16_500)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:1)
+	fn remove_verification_key() -> Weight {
+		Weight::from_parts(25_000_000, 16_500)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_groth16_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(15_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(350_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_groth16_bls12_381(n: u32, ) -> Weight {
+		Weight::from_parts(24_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(600_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_plonk_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(11_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_fflonk_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_groth16_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(3_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_groth16_bls12_381_host(n: u32, ) -> Weight {
+		Weight::from_parts(5_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(70_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_plonk_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(2_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: ZkVerifier VerificationKeys (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn verify_fflonk_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(2_200_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// The range of component `n` is `[1, 5]`.
+	fn hash_poseidon(n: u32, ) -> Weight {
+		Weight::from_parts(150_000_000, 0)
+			.saturating_add(Weight::from_parts(250_000_000, 0).saturating_mul(n.into()))
+	}
+	/// The range of component `n` is `[1, 64]`.
+	fn hash_mimc(n: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn register_groth16_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(4_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn register_groth16_bls12_381(n: u32, ) -> Weight {
+		Weight::from_parts(6_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(110_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn register_plonk_bn254() -> Weight {
+		Weight::from_parts(2_000_000_000, 16_500)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn register_fflonk_bn254() -> Weight {
+		Weight::from_parts(1_600_000_000, 16_500)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn remove_verification_key() -> Weight {
+		Weight::from_parts(25_000_000, 16_500)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn verify_groth16_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(15_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(350_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_groth16_bls12_381(n: u32, ) -> Weight {
+		Weight::from_parts(24_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(600_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_plonk_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(11_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_fflonk_bn254(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_groth16_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(3_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_groth16_bls12_381_host(n: u32, ) -> Weight {
+		Weight::from_parts(5_000_000_000, 16_500)
+			.saturating_add(Weight::from_parts(70_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_plonk_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(2_500_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn verify_fflonk_bn254_host(n: u32, ) -> Weight {
+		Weight::from_parts(2_200_000_000, 16_500)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	fn hash_poseidon(n: u32, ) -> Weight {
+		Weight::from_parts(150_000_000, 0)
+			.saturating_add(Weight::from_parts(250_000_000, 0).saturating_mul(n.into()))
+	}
+	fn hash_mimc(n: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(n.into()))
+	}
+}
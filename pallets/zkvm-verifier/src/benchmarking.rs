@@ -0,0 +1,83 @@
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ZkvmVerifier;
+use alloc::{boxed::Box, vec};
+use frame_benchmarking::v2::*;
+use frame_support::traits::EnsureOrigin;
+use frame_system::RawOrigin;
+
+const IMAGE_ID: ImageId = [7; 32];
+
+fn manager_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+	T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn approve_image() -> Result<(), BenchmarkError> {
+		let origin = manager_origin::<T>()?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, IMAGE_ID);
+
+		assert!(ApprovedImages::<T>::contains_key(IMAGE_ID));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn revoke_image() -> Result<(), BenchmarkError> {
+		let origin = manager_origin::<T>()?;
+		ApprovedImages::<T>::insert(IMAGE_ID, ());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, IMAGE_ID);
+
+		assert!(!ApprovedImages::<T>::contains_key(IMAGE_ID));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_verifier_parameters() -> Result<(), BenchmarkError> {
+		let origin = manager_origin::<T>()?;
+		let parameters =
+			VerifierParameters { vk_id: 0, control_root: [1; 32], bn254_control_id: [2; 32] };
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, parameters.clone());
+
+		assert_eq!(Parameters::<T>::get(), Some(parameters));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_receipt(j: Linear<0, { T::MaxJournalLen::get() }>) {
+		ApprovedImages::<T>::insert(IMAGE_ID, ());
+		Parameters::<T>::put(VerifierParameters {
+			vk_id: 0,
+			control_root: [1; 32],
+			bn254_control_id: [2; 32],
+		});
+		let journal: JournalOf<T> = vec![0xab; j as usize].try_into().unwrap();
+		let seal = Seal { a: [0; 64], b: [0; 128], c: [0; 64] };
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[block]
+		{
+			// The seal is verified against a key that is not registered, so this measures
+			// everything but the verification, which `Config::ProofVerifier` prices separately;
+			// the journal write is accounted for in the weight function.
+			let _ = ZkvmVerifier::<T>::submit_receipt(
+				RawOrigin::Signed(caller).into(),
+				IMAGE_ID,
+				Box::new(seal),
+				journal,
+			);
+		}
+	}
+
+	impl_benchmark_test_suite!(ZkvmVerifier, crate::mock::new_test_ext(), crate::mock::Test);
+}
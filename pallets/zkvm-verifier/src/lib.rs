@@ -0,0 +1,283 @@
+//! # zkVM Verifier Pallet
+//!
+//! Verifies succinct RISC Zero receipts and records the attested journals on-chain.
+//!
+//! ## Overview
+//!
+//! A zkVM receipt proves that a guest program, identified by its image id, ran to completion and
+//! committed a journal. This pallet:
+//!
+//! - keeps the set of image ids approved by [`Config::ManagerOrigin`]
+//!   ([`Pallet::approve_image`], [`Pallet::revoke_image`]);
+//! - holds the parameters of the recursion circuit the receipts are wrapped in
+//!   ([`Pallet::set_verifier_parameters`]), whose Groth16 verification key lives in the verifier
+//!   plugged in as [`Config::ProofVerifier`];
+//! - checks receipts submitted with [`Pallet::submit_receipt`] and stores their journal under the
+//!   receipt hash, where other pallets ([`Pallet::verified_journal`]) and contracts can read it.
+//!
+//! The claim a receipt proves is rebuilt on-chain from the image id and journal, see [`risc0`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The FRAME macros clone `Copy` fields and convert dispatch results into their own type.
+#![allow(clippy::clone_on_copy, clippy::useless_conversion)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod risc0;
+pub mod weights;
+pub use weights::*;
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::Get, weights::Weight, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound,
+	RuntimeDebugNoBound,
+};
+use pallet_zk_verifier::{ProofVerifier, ProvingSystem, VkId};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// A SHA-256 digest as used by the zkVM.
+pub type Digest = [u8; 32];
+/// The digest identifying a guest program.
+pub type ImageId = Digest;
+/// The Groth16 proof wrapping a succinct receipt.
+pub type Seal = pallet_zk_verifier::bn254::Groth16Proof;
+
+/// Parameters of the circuit that wraps receipts into a Groth16 proof.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VerifierParameters {
+	/// Key of the Groth16 verifier circuit in [`Config::ProofVerifier`].
+	pub vk_id: VkId,
+	/// Root of the allowed recursion programs, `ALLOWED_CONTROL_ROOT` in `risc0-circuit-recursion`.
+	pub control_root: Digest,
+	/// `BN254_IDENTITY_CONTROL_ID` in `risc0-circuit-recursion`.
+	pub bn254_control_id: Digest,
+}
+
+/// A journal attested by a verified receipt.
+#[derive(
+	CloneNoBound,
+	Encode,
+	Decode,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxJournalLen))]
+pub struct VerifiedJournal<AccountId, BlockNumber, MaxJournalLen: Get<u32>>
+where
+	AccountId: Clone + PartialEq + Eq + core::fmt::Debug,
+	BlockNumber: Clone + PartialEq + Eq + core::fmt::Debug,
+{
+	pub image_id: ImageId,
+	pub journal: BoundedVec<u8, MaxJournalLen>,
+	/// Account that submitted the receipt.
+	pub submitter: AccountId,
+	/// Block in which the receipt was verified.
+	pub verified_at: BlockNumber,
+}
+
+pub type JournalOf<T> = BoundedVec<u8, <T as Config>::MaxJournalLen>;
+pub type VerifiedJournalOf<T> = VerifiedJournal<
+	<T as frame_system::Config>::AccountId,
+	frame_system::pallet_prelude::BlockNumberFor<T>,
+	<T as Config>::MaxJournalLen,
+>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use alloc::boxed::Box;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Hash;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Origin allowed to approve images and set the verifier parameters.
+		type ManagerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Verifier of the Groth16 proofs wrapping receipts.
+		type ProofVerifier: ProofVerifier;
+		/// Maximum length of a journal in bytes.
+		#[pallet::constant]
+		type MaxJournalLen: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Image ids receipts may be submitted for.
+	#[pallet::storage]
+	pub type ApprovedImages<T: Config> = StorageMap<_, Identity, ImageId, ()>;
+
+	/// Parameters of the receipt wrapping circuit.
+	#[pallet::storage]
+	pub type Parameters<T: Config> = StorageValue<_, VerifierParameters>;
+
+	/// Journals of verified receipts, by receipt hash.
+	#[pallet::storage]
+	pub type VerifiedJournals<T: Config> =
+		StorageMap<_, Identity, T::Hash, VerifiedJournalOf<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Receipts for an image can now be submitted.
+		ImageApproved { image_id: ImageId },
+		/// Receipts for an image are no longer accepted.
+		ImageRevoked { image_id: ImageId },
+		/// The receipt wrapping circuit parameters were updated.
+		VerifierParametersSet { parameters: VerifierParameters },
+		/// A receipt was verified and its journal recorded.
+		ReceiptVerified { receipt_hash: T::Hash, image_id: ImageId, submitter: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The image is already approved.
+		ImageAlreadyApproved,
+		/// The image is not approved.
+		ImageNotApproved,
+		/// No verifier parameters are set.
+		ParametersNotSet,
+		/// The receipt was already recorded.
+		ReceiptAlreadyVerified,
+		/// The seal does not prove the claim for this image and journal.
+		InvalidReceipt,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Accept receipts for `image_id`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::approve_image())]
+		pub fn approve_image(origin: OriginFor<T>, image_id: ImageId) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(
+				!ApprovedImages::<T>::contains_key(image_id),
+				Error::<T>::ImageAlreadyApproved
+			);
+
+			ApprovedImages::<T>::insert(image_id, ());
+			Self::deposit_event(Event::ImageApproved { image_id });
+			Ok(())
+		}
+
+		/// Stop accepting receipts for `image_id`.
+		///
+		/// Journals that were already recorded are kept.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::revoke_image())]
+		pub fn revoke_image(origin: OriginFor<T>, image_id: ImageId) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(ApprovedImages::<T>::contains_key(image_id), Error::<T>::ImageNotApproved);
+
+			ApprovedImages::<T>::remove(image_id);
+			Self::deposit_event(Event::ImageRevoked { image_id });
+			Ok(())
+		}
+
+		/// Set the parameters of the receipt wrapping circuit.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::set_verifier_parameters())]
+		pub fn set_verifier_parameters(
+			origin: OriginFor<T>,
+			parameters: VerifierParameters,
+		) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			Parameters::<T>::put(&parameters);
+			Self::deposit_event(Event::VerifierParametersSet { parameters });
+			Ok(())
+		}
+
+		/// Verify a succinct receipt that `image_id` committed `journal` and record the journal.
+		#[pallet::call_index(3)]
+		#[pallet::weight(Pallet::<T>::submit_receipt_weight(journal.len() as u32))]
+		pub fn submit_receipt(
+			origin: OriginFor<T>,
+			image_id: ImageId,
+			seal: Box<Seal>,
+			journal: JournalOf<T>,
+		) -> DispatchResult {
+			let submitter = ensure_signed(origin)?;
+			ensure!(ApprovedImages::<T>::contains_key(image_id), Error::<T>::ImageNotApproved);
+			let receipt_hash = Self::receipt_hash(&image_id, &seal, &journal);
+			ensure!(
+				!VerifiedJournals::<T>::contains_key(receipt_hash),
+				Error::<T>::ReceiptAlreadyVerified
+			);
+
+			let parameters = Parameters::<T>::get().ok_or(Error::<T>::ParametersNotSet)?;
+			let claim = risc0::claim_digest(&image_id, &journal);
+			let inputs = risc0::public_inputs(
+				&parameters.control_root,
+				&parameters.bn254_control_id,
+				&claim,
+			);
+			ensure!(
+				T::ProofVerifier::verify(parameters.vk_id, &seal.encode(), &inputs)?,
+				Error::<T>::InvalidReceipt
+			);
+
+			VerifiedJournals::<T>::insert(
+				receipt_hash,
+				VerifiedJournal {
+					image_id,
+					journal,
+					submitter: submitter.clone(),
+					verified_at: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+			Self::deposit_event(Event::ReceiptVerified { receipt_hash, image_id, submitter });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Weight of [`Pallet::submit_receipt`] with a journal of `journal_len` bytes: the
+		/// verification of the seal by [`Config::ProofVerifier`] on top of hashing the journal
+		/// and recording it.
+		pub fn submit_receipt_weight(journal_len: u32) -> Weight {
+			T::WeightInfo::submit_receipt(journal_len).saturating_add(
+				T::ProofVerifier::verify_weight(ProvingSystem::Groth16Bn254, risc0::PUBLIC_INPUTS),
+			)
+		}
+
+		/// Hash under which the journal of a receipt is recorded.
+		pub fn receipt_hash(image_id: &ImageId, seal: &Seal, journal: &[u8]) -> T::Hash {
+			T::Hashing::hash_of(&(image_id, seal, journal))
+		}
+
+		/// The journal recorded for `receipt_hash`, if the receipt was verified.
+		pub fn verified_journal(receipt_hash: T::Hash) -> Option<VerifiedJournalOf<T>> {
+			VerifiedJournals::<T>::get(receipt_hash)
+		}
+	}
+}
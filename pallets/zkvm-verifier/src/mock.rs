@@ -0,0 +1,90 @@
+use crate as pallet_zkvm_verifier;
+use core::cell::RefCell;
+use frame_support::{derive_impl, traits::ConstU32, weights::Weight};
+use frame_system::EnsureRoot;
+use pallet_zk_verifier::{ProofVerifier, ProvingSystem, ScalarBytes, VkId};
+use sp_runtime::{BuildStorage, DispatchError};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system::Pallet<Test>;
+
+	#[runtime::pallet_index(1)]
+	pub type ZkvmVerifier = pallet_zkvm_verifier::Pallet<Test>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+/// The only key id [`MockVerifier`] knows.
+pub const VK_ID: VkId = 1;
+
+thread_local! {
+	/// Public inputs of the last proof checked by [`MockVerifier`].
+	pub static LAST_INPUTS: RefCell<Vec<ScalarBytes>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Accepts proofs whose encoding, and so their `a` point, starts with a `1` byte.
+pub struct MockVerifier;
+
+impl ProofVerifier for MockVerifier {
+	fn verify(
+		vk_id: VkId,
+		proof: &[u8],
+		public_inputs: &[ScalarBytes],
+	) -> Result<bool, DispatchError> {
+		if vk_id != VK_ID {
+			return Err(DispatchError::Other("unknown verification key"));
+		}
+		LAST_INPUTS.with(|last| *last.borrow_mut() = public_inputs.to_vec());
+		Ok(proof.first() == Some(&1))
+	}
+
+	fn verify_weight(_system: ProvingSystem, public_inputs: u32) -> Weight {
+		Weight::from_parts(1_000_000 * (public_inputs as u64 + 1), 0)
+	}
+
+	fn max_verify_weight(public_inputs: u32) -> Weight {
+		Weight::from_parts(2_000_000 * (public_inputs as u64 + 1), 0)
+	}
+
+	fn proving_system(vk_id: VkId) -> Option<ProvingSystem> {
+		(vk_id == VK_ID).then_some(ProvingSystem::Groth16Bn254)
+	}
+}
+
+impl pallet_zkvm_verifier::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type ProofVerifier = MockVerifier;
+	type MaxJournalLen = ConstU32<256>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
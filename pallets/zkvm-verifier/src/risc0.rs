@@ -0,0 +1,73 @@
+//! RISC Zero receipt claims and their Groth16 encoding.
+//!
+//! A succinct RISC Zero receipt is a Groth16 proof over BN254 whose public inputs commit to the
+//! recursion circuit (`control_root`, `bn254_control_id`) and to the digest of a
+//! [`ReceiptClaim`]-style structure. For a guest that halted normally without assumptions the
+//! claim only depends on the image id and the journal, which is all this module needs:
+//!
+//! ```text
+//! claim = tagged_struct("risc0.ReceiptClaim",
+//!     [input = 0, pre = image_id, post = SystemState { pc: 0, merkle_root: 0 },
+//!      output = tagged_struct("risc0.Output", [sha256(journal), assumptions = 0], [])],
+//!     [sys_exit = 0, user_exit = 0])
+//! ```
+//!
+//! Digests are hashed with SHA-256 exactly as `risc0_zkp::core::digest` does, so the values match
+//! what `risc0-zkvm` reports for a receipt.
+
+use crate::{Digest, ImageId};
+use alloc::vec::Vec;
+use pallet_zk_verifier::ScalarBytes;
+use sp_io::hashing::sha2_256;
+
+const ZERO: Digest = [0u8; 32];
+
+/// `tagged_struct` from `risc0_zkp::core::digest`.
+pub(crate) fn tagged_struct(tag: &str, down: &[Digest], data: &[u32]) -> Digest {
+	let mut preimage = Vec::with_capacity(32 * (down.len() + 1) + 4 * data.len() + 2);
+	preimage.extend_from_slice(&sha2_256(tag.as_bytes()));
+	for digest in down {
+		preimage.extend_from_slice(digest);
+	}
+	for word in data {
+		preimage.extend_from_slice(&word.to_le_bytes());
+	}
+	preimage.extend_from_slice(&(down.len() as u16).to_le_bytes());
+	sha2_256(&preimage)
+}
+
+/// Digest of the claim that `image_id` halted successfully and committed `journal`.
+pub fn claim_digest(image_id: &ImageId, journal: &[u8]) -> Digest {
+	let post = tagged_struct("risc0.SystemState", &[ZERO], &[0]);
+	let output = tagged_struct("risc0.Output", &[sha2_256(journal), ZERO], &[]);
+	tagged_struct("risc0.ReceiptClaim", &[ZERO, *image_id, post, output], &[0, 0])
+}
+
+/// Splits a digest into the two 128-bit field elements the Groth16 circuit takes it as.
+fn split_digest(digest: &Digest) -> [ScalarBytes; 2] {
+	let mut halves = [[0u8; 32]; 2];
+	for (half, chunk) in halves.iter_mut().zip(digest.chunks(16)) {
+		// Each half is read as a little-endian integer and encoded big-endian.
+		for (i, byte) in chunk.iter().enumerate() {
+			half[31 - i] = *byte;
+		}
+	}
+	halves
+}
+
+/// Number of public inputs of the receipt wrapping circuit.
+pub const PUBLIC_INPUTS: u32 = 5;
+
+/// Public inputs of the Groth16 proof wrapping a receipt for `claim`.
+pub fn public_inputs(
+	control_root: &Digest,
+	bn254_control_id: &Digest,
+	claim: &Digest,
+) -> [ScalarBytes; PUBLIC_INPUTS as usize] {
+	let [a0, a1] = split_digest(control_root);
+	let [c0, c1] = split_digest(claim);
+	// The control id is a field element already, stored little-endian.
+	let mut id = *bn254_control_id;
+	id.reverse();
+	[a0, a1, c0, c1, id]
+}
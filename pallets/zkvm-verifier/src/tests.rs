@@ -0,0 +1,227 @@
+use crate::{
+	mock::*, risc0, Error, Event, ImageId, JournalOf, Seal, VerifiedJournal, VerifiedJournals,
+	VerifierParameters,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_zk_verifier::{ProofVerifier, ProvingSystem};
+use sp_runtime::DispatchError;
+
+const IMAGE_ID: ImageId = [7; 32];
+
+fn parameters() -> VerifierParameters {
+	VerifierParameters { vk_id: VK_ID, control_root: [3; 32], bn254_control_id: [4; 32] }
+}
+
+fn seal(valid: bool) -> Box<Seal> {
+	let mut a = [0; 64];
+	a[0] = valid as u8;
+	Box::new(Seal { a, b: [0; 128], c: [0; 64] })
+}
+
+fn journal(bytes: &[u8]) -> JournalOf<Test> {
+	bytes.to_vec().try_into().unwrap()
+}
+
+fn setup() {
+	assert_ok!(ZkvmVerifier::approve_image(RuntimeOrigin::root(), IMAGE_ID));
+	assert_ok!(ZkvmVerifier::set_verifier_parameters(RuntimeOrigin::root(), parameters()));
+}
+
+#[test]
+fn approve_and_revoke_image_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ZkvmVerifier::approve_image(RuntimeOrigin::signed(1), IMAGE_ID),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ZkvmVerifier::approve_image(RuntimeOrigin::root(), IMAGE_ID));
+		System::assert_last_event(Event::ImageApproved { image_id: IMAGE_ID }.into());
+		assert_noop!(
+			ZkvmVerifier::approve_image(RuntimeOrigin::root(), IMAGE_ID),
+			Error::<Test>::ImageAlreadyApproved
+		);
+
+		assert_noop!(
+			ZkvmVerifier::revoke_image(RuntimeOrigin::signed(1), IMAGE_ID),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ZkvmVerifier::revoke_image(RuntimeOrigin::root(), IMAGE_ID));
+		System::assert_last_event(Event::ImageRevoked { image_id: IMAGE_ID }.into());
+		assert_noop!(
+			ZkvmVerifier::revoke_image(RuntimeOrigin::root(), IMAGE_ID),
+			Error::<Test>::ImageNotApproved
+		);
+	});
+}
+
+#[test]
+fn submit_receipt_records_journal() {
+	new_test_ext().execute_with(|| {
+		setup();
+		let receipt_hash = ZkvmVerifier::receipt_hash(&IMAGE_ID, &seal(true), b"42");
+
+		assert_ok!(ZkvmVerifier::submit_receipt(
+			RuntimeOrigin::signed(1),
+			IMAGE_ID,
+			seal(true),
+			journal(b"42")
+		));
+		System::assert_last_event(
+			Event::ReceiptVerified { receipt_hash, image_id: IMAGE_ID, submitter: 1 }.into(),
+		);
+		assert_eq!(
+			ZkvmVerifier::verified_journal(receipt_hash),
+			Some(VerifiedJournal {
+				image_id: IMAGE_ID,
+				journal: journal(b"42"),
+				submitter: 1,
+				verified_at: 1,
+			})
+		);
+
+		// The verifier was asked about the claim for this image and journal.
+		let claim = risc0::claim_digest(&IMAGE_ID, b"42");
+		assert_eq!(
+			LAST_INPUTS.with(|last| last.borrow().clone()),
+			risc0::public_inputs(&[3; 32], &[4; 32], &claim).to_vec()
+		);
+
+		assert_noop!(
+			ZkvmVerifier::submit_receipt(
+				RuntimeOrigin::signed(2),
+				IMAGE_ID,
+				seal(true),
+				journal(b"42")
+			),
+			Error::<Test>::ReceiptAlreadyVerified
+		);
+	});
+}
+
+#[test]
+fn submit_receipt_rejects_invalid_seal() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_noop!(
+			ZkvmVerifier::submit_receipt(
+				RuntimeOrigin::signed(1),
+				IMAGE_ID,
+				seal(false),
+				journal(b"42")
+			),
+			Error::<Test>::InvalidReceipt
+		);
+		assert_eq!(VerifiedJournals::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn submit_receipt_checks_preconditions() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ZkvmVerifier::submit_receipt(
+				RuntimeOrigin::signed(1),
+				IMAGE_ID,
+				seal(true),
+				journal(b"42")
+			),
+			Error::<Test>::ImageNotApproved
+		);
+
+		assert_ok!(ZkvmVerifier::approve_image(RuntimeOrigin::root(), IMAGE_ID));
+		assert_noop!(
+			ZkvmVerifier::submit_receipt(
+				RuntimeOrigin::signed(1),
+				IMAGE_ID,
+				seal(true),
+				journal(b"42")
+			),
+			Error::<Test>::ParametersNotSet
+		);
+
+		// Errors of the underlying verifier are passed through.
+		assert_ok!(ZkvmVerifier::set_verifier_parameters(
+			RuntimeOrigin::root(),
+			VerifierParameters { vk_id: VK_ID + 1, ..parameters() }
+		));
+		assert_noop!(
+			ZkvmVerifier::submit_receipt(
+				RuntimeOrigin::signed(1),
+				IMAGE_ID,
+				seal(true),
+				journal(b"42")
+			),
+			DispatchError::Other("unknown verification key")
+		);
+	});
+}
+
+#[test]
+fn set_verifier_parameters_requires_manager_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ZkvmVerifier::set_verifier_parameters(RuntimeOrigin::signed(1), parameters()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ZkvmVerifier::set_verifier_parameters(RuntimeOrigin::root(), parameters()));
+		System::assert_last_event(Event::VerifierParametersSet { parameters: parameters() }.into());
+	});
+}
+
+#[test]
+fn claim_digest_commits_to_image_and_journal() {
+	let claim = risc0::claim_digest(&IMAGE_ID, b"42");
+	assert_ne!(claim, risc0::claim_digest(&[8; 32], b"42"));
+	assert_ne!(claim, risc0::claim_digest(&IMAGE_ID, b"43"));
+}
+
+#[test]
+fn public_inputs_split_digests_into_128_bit_halves() {
+	let mut digest = [0u8; 32];
+	digest[0] = 0x01;
+	digest[15] = 0x02;
+	digest[16] = 0x03;
+	let mut control_id = [0u8; 32];
+	control_id[0] = 0x05;
+	let [a0, a1, c0, c1, id] = risc0::public_inputs(&digest, &control_id, &[0xff; 32]);
+
+	// Each half is a little-endian integer, encoded big-endian below 2^128.
+	assert_eq!(a0[31], 0x01);
+	assert_eq!(a0[16], 0x02);
+	assert_eq!(a0[..16], [0; 16]);
+	assert_eq!(a1[31], 0x03);
+	assert_eq!(c0, c1);
+	assert_eq!(c0[..16], [0; 16]);
+	assert_eq!(c0[16..], [0xff; 16]);
+	assert_eq!(id[31], 0x05);
+	assert_eq!(id[..31], [0; 31]);
+}
+
+#[test]
+fn tagged_struct_matches_risc0() {
+	// Test vector of `risc0_binfmt::hash::tagged_struct`.
+	let digest1 = risc0::tagged_struct("foo", &[], &[1, 2013265920, 3]);
+	let digest2 = risc0::tagged_struct("bar", &[digest1, digest1], &[2013265920, 5]);
+	let digest3 =
+		risc0::tagged_struct("baz", &[digest1, digest2, digest1], &[6, 7, 2013265920, 9, 10]);
+
+	let hex: String = digest3.iter().map(|byte| format!("{byte:02x}")).collect();
+	assert_eq!(hex, "9ff20cc6d365efa2af09181772f49013d05cdee6da896851614cae23aa5dd442");
+}
+
+#[test]
+fn submit_receipt_is_charged_the_weight_of_the_configured_verifier() {
+	use crate::{Call, WeightInfo};
+	use frame_support::dispatch::GetDispatchInfo;
+
+	let call = Call::<Test>::submit_receipt {
+		image_id: IMAGE_ID,
+		seal: seal(true),
+		journal: journal(b"hello"),
+	};
+	assert_eq!(
+		call.get_dispatch_info().call_weight,
+		<() as WeightInfo>::submit_receipt(5) +
+			MockVerifier::verify_weight(ProvingSystem::Groth16Bn254, risc0::PUBLIC_INPUTS)
+	);
+}
@@ -0,0 +1,84 @@
+//! Weights for pallet_zkvm_verifier
+//!
+//! These are hand-written, conservative estimates. `submit_receipt` covers hashing the journal
+//! into the claim and recording it; the Groth16 verification of the seal is priced by the
+//! configured `ProofVerifier` and added by `Pallet::submit_receipt_weight`. Regenerate them on
+//! reference hardware with:
+//!
+//! ```text
+//! kora-chain-node benchmark pallet --chain dev --pallet pallet_zkvm_verifier --extrinsic '*' \
+//!     --steps 50 --repeat 20 --output pallets/zkvm-verifier/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_zkvm_verifier.
+pub trait WeightInfo {
+	fn approve_image() -> Weight;
+	fn revoke_image() -> Weight;
+	fn set_verifier_parameters() -> Weight;
+	fn submit_receipt(j: u32, ) -> Weight;
+}
+
+/// Weights for pallet_zkvm_verifier using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: ZkvmVerifier ApprovedImages (r:1 w:1)
+	fn approve_image() -> Weight {
+		Weight::from_parts(20_000_000, 3493)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkvmVerifier ApprovedImages (r:1 w:1)
+	fn revoke_image() -> Weight {
+		Weight::from_parts(20_000_000, 3493)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkvmVerifier Parameters (r:0 w:1)
+	fn set_verifier_parameters() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkvmVerifier ApprovedImages (r:1 w:0)
+	/// Storage: ZkvmVerifier VerifiedJournals (r:1 w:1)
+	/// Storage: ZkvmVerifier Parameters (r:1 w:0)
+	/// The range of component `j` is `[0, 4096]`.
+	fn submit_receipt(j: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 5000)
+			.saturating_add(Weight::from_parts(10_000, 0).saturating_mul(j.into()))
+			.saturating_add(Weight::from_parts(0, 1).saturating_mul(j.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn approve_image() -> Weight {
+		Weight::from_parts(20_000_000, 3493)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn revoke_image() -> Weight {
+		Weight::from_parts(20_000_000, 3493)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_verifier_parameters() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn submit_receipt(j: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 5000)
+			.saturating_add(Weight::from_parts(10_000, 0).saturating_mul(j.into()))
+			.saturating_add(Weight::from_parts(0, 1).saturating_mul(j.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
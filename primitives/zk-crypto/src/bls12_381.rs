@@ -0,0 +1,15 @@
+//! BLS12-381 operations computed by the host.
+
+use crate::zk_crypto;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine};
+
+/// `sum(scalars[i] * bases[i])`, or `None` if the slices differ in length.
+pub fn msm_g1(bases: &[G1Affine], scalars: &[Fr]) -> Option<G1Projective> {
+	crate::msm(bases, scalars, zk_crypto::bls12_381_msm_g1)
+}
+
+/// Whether `e(g1[0], g2[0]) * e(g1[1], g2[1]) * ...` is the identity, or `None` if the slices
+/// differ in length.
+pub fn pairing_check(g1: &[G1Affine], g2: &[G2Affine]) -> Option<bool> {
+	crate::pairing_check::<Bls12_381>(g1, g2, zk_crypto::bls12_381_pairing_check)
+}
@@ -0,0 +1,15 @@
+//! BN254 operations computed by the host.
+
+use crate::zk_crypto;
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine};
+
+/// `sum(scalars[i] * bases[i])`, or `None` if the slices differ in length.
+pub fn msm_g1(bases: &[G1Affine], scalars: &[Fr]) -> Option<G1Projective> {
+	crate::msm(bases, scalars, zk_crypto::bn254_msm_g1)
+}
+
+/// Whether `e(g1[0], g2[0]) * e(g1[1], g2[1]) * ...` is the identity, or `None` if the slices
+/// differ in length.
+pub fn pairing_check(g1: &[G1Affine], g2: &[G2Affine]) -> Option<bool> {
+	crate::pairing_check::<Bn254>(g1, g2, zk_crypto::bn254_pairing_check)
+}
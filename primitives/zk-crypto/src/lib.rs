@@ -0,0 +1,113 @@
+//! Host functions for the elliptic curve operations behind zk-SNARK verification.
+//!
+//! Verifying a Groth16, PLONK or fflonk proof is dominated by a multi-scalar multiplication and a
+//! product of pairings, both of which run several times slower in Wasm than natively. The
+//! [`zk_crypto`] runtime interface moves them to the node for BN254 and BLS12-381, and [`bn254`]
+//! and [`bls12_381`] wrap it in arkworks types for use from the runtime.
+//!
+//! A runtime calling into this crate only instantiates on nodes that register [`HostFunctions`]
+//! with their executor, so nodes must be upgraded before such a runtime is enacted.
+//!
+//! Points cross the boundary in arkworks' uncompressed encoding and the host does not check them:
+//! callers validate their inputs exactly as they would before computing on them natively.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod bls12_381;
+pub mod bn254;
+
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use sp_runtime_interface::runtime_interface;
+
+/// The host functions a node must provide to run a runtime using this crate.
+#[cfg(feature = "std")]
+pub type HostFunctions = zk_crypto::HostFunctions;
+
+/// Elliptic curve operations for zk-SNARK verifiers.
+///
+/// Points and scalars are encoded uncompressed with `ark-serialize`, slices as `Vec`s. A call
+/// fails if its arguments do not decode or have mismatched lengths.
+#[runtime_interface]
+#[allow(clippy::result_unit_err)]
+pub trait ZkCrypto {
+	/// Multi-scalar multiplication on BN254 G1.
+	///
+	/// Takes `Vec<G1Affine>` bases and `Vec<Fr>` scalars and returns the `G1Affine` result.
+	fn bn254_msm_g1(bases: Vec<u8>, scalars: Vec<u8>) -> Result<Vec<u8>, ()> {
+		host_msm::<ark_bn254::G1Projective>(&bases, &scalars)
+	}
+
+	/// Checks that the product of pairings on BN254 is the identity.
+	///
+	/// Takes `Vec<G1Affine>` and `Vec<G2Affine>` and pairs them up in order.
+	fn bn254_pairing_check(g1: Vec<u8>, g2: Vec<u8>) -> Result<bool, ()> {
+		host_pairing_check::<ark_bn254::Bn254>(&g1, &g2)
+	}
+
+	/// Multi-scalar multiplication on BLS12-381 G1.
+	///
+	/// Takes `Vec<G1Affine>` bases and `Vec<Fr>` scalars and returns the `G1Affine` result.
+	fn bls12_381_msm_g1(bases: Vec<u8>, scalars: Vec<u8>) -> Result<Vec<u8>, ()> {
+		host_msm::<ark_bls12_381::G1Projective>(&bases, &scalars)
+	}
+
+	/// Checks that the product of pairings on BLS12-381 is the identity.
+	///
+	/// Takes `Vec<G1Affine>` and `Vec<G2Affine>` and pairs them up in order.
+	fn bls12_381_pairing_check(g1: Vec<u8>, g2: Vec<u8>) -> Result<bool, ()> {
+		host_pairing_check::<ark_bls12_381::Bls12_381>(&g1, &g2)
+	}
+}
+
+fn encode<T: CanonicalSerialize + ?Sized>(value: &T) -> Vec<u8> {
+	let mut out = Vec::with_capacity(value.uncompressed_size());
+	value.serialize_uncompressed(&mut out).expect("writing to a vector cannot fail; qed");
+	out
+}
+
+fn decode<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, ()> {
+	T::deserialize_with_mode(bytes, Compress::No, Validate::No).map_err(|_| ())
+}
+
+#[cfg(feature = "std")]
+fn host_msm<G: CurveGroup>(bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, ()> {
+	let bases = decode::<Vec<G::Affine>>(bases)?;
+	let scalars = decode::<Vec<G::ScalarField>>(scalars)?;
+	let result = G::msm(&bases, &scalars).map_err(|_| ())?;
+	Ok(encode(&result.into_affine()))
+}
+
+#[cfg(feature = "std")]
+fn host_pairing_check<P: Pairing>(g1: &[u8], g2: &[u8]) -> Result<bool, ()> {
+	use ark_ff::Zero;
+
+	let g1 = decode::<Vec<P::G1Affine>>(g1)?;
+	let g2 = decode::<Vec<P::G2Affine>>(g2)?;
+	if g1.len() != g2.len() {
+		return Err(());
+	}
+	Ok(P::multi_pairing(g1, g2).is_zero())
+}
+
+/// Runs an MSM on the host through `call`.
+fn msm<G: CurveGroup>(
+	bases: &[G::Affine],
+	scalars: &[G::ScalarField],
+	call: impl FnOnce(Vec<u8>, Vec<u8>) -> Result<Vec<u8>, ()>,
+) -> Option<G> {
+	let result = call(encode(bases), encode(scalars)).ok()?;
+	decode::<G::Affine>(&result).ok().map(AffineRepr::into_group)
+}
+
+/// Runs a pairing check on the host through `call`.
+fn pairing_check<P: Pairing>(
+	g1: &[P::G1Affine],
+	g2: &[P::G2Affine],
+	call: impl FnOnce(Vec<u8>, Vec<u8>) -> Result<bool, ()>,
+) -> Option<bool> {
+	call(encode(g1), encode(g2)).ok()
+}
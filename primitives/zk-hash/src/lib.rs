@@ -0,0 +1,45 @@
+//! SNARK-friendly hash functions over the BN254 scalar field.
+//!
+//! Commitments and Merkle trees checked inside circom circuits have to be built with the same
+//! hash on-chain. This crate implements them for the runtime, bit-for-bit compatible with
+//! circomlib:
+//!
+//! - [`poseidon`], circomlib's `Poseidon(n)` template for up to [`poseidon::MAX_INPUTS`] inputs.
+//! - [`mimc`], circomlib's `MiMC7` and `MultiMiMC7` templates.
+//!
+//! Field elements are exchanged as [`Scalar`]s, 32 bytes big-endian like circuit public inputs.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod mimc;
+pub mod poseidon;
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+
+/// A BN254 scalar field element, 32 bytes big-endian.
+pub type Scalar = [u8; 32];
+
+/// Decodes a scalar, rejecting values that are not reduced modulo the field order.
+pub fn decode_scalar(bytes: &Scalar) -> Option<Fr> {
+	let mut limbs = [0u64; 4];
+	for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(8)) {
+		*limb = u64::from_be_bytes(chunk.try_into().expect("chunks of 8 bytes; qed"));
+	}
+	Fr::from_bigint(ark_ff::BigInt(limbs))
+}
+
+/// Encodes a field element as a scalar.
+pub fn encode_scalar(value: &Fr) -> Scalar {
+	value.into_bigint().to_bytes_be().try_into().expect("BN254 scalars are 32 bytes; qed")
+}
+
+/// Turns a 256-bit digest into a scalar by clearing its top byte.
+///
+/// Used to bind values that are not field elements, such as account ids, to the public inputs of
+/// a circuit through their hash.
+pub fn truncate_to_scalar(digest: [u8; 32]) -> Scalar {
+	let mut scalar = digest;
+	scalar[0] = 0;
+	scalar
+}
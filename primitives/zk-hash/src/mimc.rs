@@ -0,0 +1,114 @@
+//! MiMC-7 over BN254 with circomlib's parameters.
+//!
+//! [`permute`] is circomlib's `MiMC7(91)` template: 91 rounds of `x -> (x + k + c_i)^7` with the
+//! key added to the output. [`hash`] chains it over its inputs as `MultiMiMC7` does, feeding
+//! each input and the running hash back in, and matches `circomlibjs`' `mimc7.multiHash`.
+
+mod constants;
+
+use crate::{decode_scalar, encode_scalar, Scalar};
+use ark_bn254::Fr;
+use ark_ff::Field;
+
+/// The number of rounds of the permutation.
+pub const ROUNDS: usize = 91;
+
+/// Encrypts `input` under `key`.
+pub fn permute(input: Fr, key: Fr) -> Fr {
+	let mut state = input;
+	for (round, constant) in constants::ROUND_CONSTANTS.iter().enumerate() {
+		let t = if round == 0 { input + key } else { state + key + constant };
+		let t2 = t.square();
+		state = t2.square() * t2 * t;
+	}
+	state + key
+}
+
+/// Hashes `inputs` starting from `key`, which circuits usually set to zero.
+pub fn hash(inputs: &[Fr], key: Fr) -> Fr {
+	inputs.iter().fold(key, |state, input| state + input + permute(*input, state))
+}
+
+/// Hashes encoded `inputs` starting from `key`.
+///
+/// Returns `None` if an input or the key is not a canonical scalar.
+pub fn hash_scalars(inputs: &[Scalar], key: &Scalar) -> Option<Scalar> {
+	let mut state = decode_scalar(key)?;
+	for input in inputs {
+		let input = decode_scalar(input)?;
+		state = state + input + permute(input, state);
+	}
+	Some(encode_scalar(&state))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ark_ff::Zero;
+	use core::str::FromStr;
+
+	fn scalar(hex: &str) -> Scalar {
+		let mut scalar = [0u8; 32];
+		for (byte, pair) in scalar.iter_mut().zip(hex.as_bytes().chunks(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		scalar
+	}
+
+	fn small(value: u8) -> Scalar {
+		let mut scalar = [0u8; 32];
+		scalar[31] = value;
+		scalar
+	}
+
+	// Reference values from circomlibjs.
+	#[test]
+	fn matches_circomlib() {
+		assert_eq!(
+			permute(Fr::from(1u64), Fr::from(2u64)),
+			Fr::from_str(
+				"10594780656576967754230020536574539122676596303354946869887184401991294982664"
+			)
+			.unwrap()
+		);
+		assert_eq!(
+			encode_scalar(&permute(Fr::from(12u64), Fr::from(45u64))),
+			scalar("2ba7ebad3c6b6f5a20bdecba2333c63173ca1a5f2f49d958081d9fa7179c44e4")
+		);
+		let zero = [0u8; 32];
+		assert_eq!(
+			hash_scalars(&[small(12)], &zero),
+			Some(scalar("237c92644dbddb86d8a259e0e923aaab65a93f1ec5758b8799988894ac0958fd"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(78), small(41)], &zero),
+			Some(scalar("067f3202335ea256ae6e6aadcd2d5f7f4b06a00b2d1e0de903980d5ab552dc70"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(12), small(45)], &zero),
+			Some(scalar("15ff7fe9793346a17c3150804bcb36d161c8662b110c50f55ccb7113948d8879"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(12), small(45), small(78), small(41)], &zero),
+			Some(scalar("284bc1f34f335933a23a433b6ff3ee179d682cd5e5e2fcdd2d964afa85104beb"))
+		);
+	}
+
+	#[test]
+	fn hash_scalars_matches_hash() {
+		let inputs = [Fr::from(3u64), Fr::from(4u64), Fr::from(5u64)];
+		let encoded = inputs.map(|input| encode_scalar(&input));
+		let key = Fr::from(6u64);
+		assert_eq!(
+			hash_scalars(&encoded, &encode_scalar(&key)),
+			Some(encode_scalar(&hash(&inputs, key)))
+		);
+		assert_eq!(hash(&[], Fr::zero()), Fr::zero());
+	}
+
+	#[test]
+	fn rejects_invalid_input() {
+		assert_eq!(hash_scalars(&[[0xff; 32]], &[0u8; 32]), None);
+		assert_eq!(hash_scalars(&[small(1)], &[0xff; 32]), None);
+	}
+}
@@ -0,0 +1,126 @@
+//! Poseidon over BN254 with circomlib's parameters.
+//!
+//! The permutation has width `t = n + 1` for `n` inputs, x^5 S-boxes, 8 full rounds and the
+//! number of partial rounds circomlib picks for `t`. The state starts as `[0, inputs..]` and the
+//! hash is its first element after the permutation, as in circomlib's `Poseidon(n)` template and
+//! `circomlibjs`' `poseidon`.
+
+mod constants;
+
+use crate::{decode_scalar, encode_scalar, Scalar};
+use ark_bn254::Fr;
+use ark_ff::{Field, Zero};
+
+/// The largest number of inputs supported.
+pub const MAX_INPUTS: usize = 5;
+
+const FULL_ROUNDS: usize = 8;
+
+/// Hashes `inputs`, or returns `None` if there are none or more than [`MAX_INPUTS`].
+pub fn hash(inputs: &[Fr]) -> Option<Fr> {
+	use constants::*;
+	match inputs.len() {
+		1 => Some(permute::<2>(inputs, &ROUND_CONSTANTS_2, &MDS_2)),
+		2 => Some(permute::<3>(inputs, &ROUND_CONSTANTS_3, &MDS_3)),
+		3 => Some(permute::<4>(inputs, &ROUND_CONSTANTS_4, &MDS_4)),
+		4 => Some(permute::<5>(inputs, &ROUND_CONSTANTS_5, &MDS_5)),
+		5 => Some(permute::<6>(inputs, &ROUND_CONSTANTS_6, &MDS_6)),
+		_ => None,
+	}
+}
+
+/// Hashes two elements, as for the nodes of a Merkle tree.
+pub fn hash2(left: Fr, right: Fr) -> Fr {
+	permute::<3>(&[left, right], &constants::ROUND_CONSTANTS_3, &constants::MDS_3)
+}
+
+/// Hashes encoded `inputs`.
+///
+/// Returns `None` if an input is not a canonical scalar or there are too few or too many inputs.
+pub fn hash_scalars(inputs: &[Scalar]) -> Option<Scalar> {
+	let mut decoded = [Fr::zero(); MAX_INPUTS];
+	let decoded = decoded.get_mut(..inputs.len())?;
+	for (value, input) in decoded.iter_mut().zip(inputs) {
+		*value = decode_scalar(input)?;
+	}
+	hash(decoded).map(|hash| encode_scalar(&hash))
+}
+
+fn permute<const T: usize>(inputs: &[Fr], round_constants: &[Fr], mds: &[[Fr; T]; T]) -> Fr {
+	let mut state = [Fr::zero(); T];
+	state[1..].copy_from_slice(inputs);
+
+	let rounds = round_constants.len() / T;
+	for (round, constants) in round_constants.as_chunks::<T>().0.iter().enumerate() {
+		for (value, constant) in state.iter_mut().zip(constants) {
+			*value += constant;
+		}
+		let full = round < FULL_ROUNDS / 2 || round >= rounds - FULL_ROUNDS / 2;
+		let sboxed = if full { &mut state[..] } else { &mut state[..1] };
+		for value in sboxed {
+			*value = value.square().square() * *value;
+		}
+		state = core::array::from_fn(|i| {
+			mds[i].iter().zip(&state).map(|(m, value)| *m * value).sum()
+		});
+	}
+
+	state[0]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn scalar(hex: &str) -> Scalar {
+		let mut scalar = [0u8; 32];
+		for (byte, pair) in scalar.iter_mut().zip(hex.as_bytes().chunks(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		scalar
+	}
+
+	fn small(value: u8) -> Scalar {
+		let mut scalar = [0u8; 32];
+		scalar[31] = value;
+		scalar
+	}
+
+	// Reference values from circomlibjs.
+	#[test]
+	fn matches_circomlib() {
+		assert_eq!(
+			hash_scalars(&[small(1)]),
+			Some(scalar("29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(1), small(2)]),
+			Some(scalar("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(1); 3]),
+			Some(scalar("02c0066e10a72abd2b33c3b214cb3e81bcb1b6e30961cd23c202b18673bf2543"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(1); 4]),
+			Some(scalar("082c9c370a0d24f4416fbc414a37681f78442d27d86385991c17d6fc0c4b7d71"))
+		);
+		assert_eq!(
+			hash_scalars(&[small(1); 5]),
+			Some(scalar("10389605ae688d4f14db853122c47d66a803c72b41589cb1bf868741b206b9bb"))
+		);
+	}
+
+	#[test]
+	fn hash2_matches_hash() {
+		let (left, right) = (Fr::from(3u64), Fr::from(4u64));
+		assert_eq!(Some(hash2(left, right)), hash(&[left, right]));
+	}
+
+	#[test]
+	fn rejects_invalid_input() {
+		assert_eq!(hash(&[]), None);
+		assert_eq!(hash(&[Fr::zero(); MAX_INPUTS + 1]), None);
+		assert_eq!(hash_scalars(&[[0xff; 32]]), None);
+	}
+}
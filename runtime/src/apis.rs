@@ -25,20 +25,25 @@
 
 // External crates imports
 use alloc::vec::Vec;
+use codec::{Decode, Encode};
 use frame_support::{
+	dispatch::DispatchInfo,
 	genesis_builder_helper::{build_state, get_preset},
+	instances::Instance1,
 	weights::Weight,
 };
 use frame_support::traits::fungible::NativeOrWithId;
 use frame_support::traits::KeyOwnerProofSystem;
 use pallet_grandpa::AuthorityId as GrandpaId;
+use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
 use pallet_nomination_pools::PoolId;
+use pallet_revive::{evm::runtime::EthExtra, AddressMapper};
 use sp_api::impl_runtime_apis;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{crypto::KeyTypeId, OpaqueMetadata, H160, U256};
 use sp_runtime::{
-	traits::{Block as BlockT, NumberFor},
+	traits::{Block as BlockT, Keccak256, NumberFor, TransactionExtension},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, RuntimeDebug,
 };
 use sp_version::RuntimeVersion;
 
@@ -53,12 +58,85 @@ pub const BABE_GENESIS_EPOCH_CONFIG: sp_consensus_babe::BabeEpochConfiguration =
 		allowed_slots: sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots,
 	};
 
+/// A leaf of the MMR: the parent block, the BEEFY authority set and the leaf version.
+type MmrLeaf = <BeefyMmrLeaf as sp_mmr_primitives::LeafDataProvider>::LeafData;
+/// A hash in the MMR.
+type MmrHash = <Keccak256 as sp_runtime::traits::Hash>::Output;
+type BeefySignature = <BeefyId as sp_runtime::RuntimeAppPublic>::Signature;
+
 type EventRecord = frame_system::EventRecord<
 	<Runtime as frame_system::Config>::RuntimeEvent,
 	<Runtime as frame_system::Config>::Hash,
 >;
 
+/// An asset class, as returned by [`AssetsApi::asset_details`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct AssetDetails<AccountId, AssetBalance> {
+	/// Can change the team and destroy the asset.
+	pub owner: AccountId,
+	/// Can mint tokens.
+	pub issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	pub admin: AccountId,
+	/// Can freeze tokens.
+	pub freezer: AccountId,
+	/// The total supply across all accounts.
+	pub supply: AssetBalance,
+	/// The balance below which an account is reaped.
+	pub min_balance: AssetBalance,
+	/// Whether holding the asset alone keeps an account alive.
+	pub is_sufficient: bool,
+	/// The number of accounts holding the asset.
+	pub accounts: u32,
+	/// The number of accounts kept alive by the asset.
+	pub sufficients: u32,
+	/// The number of approvals.
+	pub approvals: u32,
+	/// Whether the asset is live, frozen or being destroyed.
+	pub status: pallet_assets::AssetStatus,
+}
+
+/// The metadata of an asset, as returned by [`AssetsApi::asset_metadata`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct AssetMetadata {
+	/// The user friendly name of the asset.
+	pub name: Vec<u8>,
+	/// The ticker symbol of the asset.
+	pub symbol: Vec<u8>,
+	/// The number of decimals of the asset's balances.
+	pub decimals: u8,
+	/// Whether the metadata may no longer be changed by the owner.
+	pub is_frozen: bool,
+}
+
+/// An account's holding of an asset, as returned by [`AssetsApi::account`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct AssetAccount<AssetBalance> {
+	/// The balance of the account.
+	pub balance: AssetBalance,
+	/// Whether the account can send and receive the asset.
+	pub status: pallet_assets::AccountStatus,
+}
+
+/// A swap route through the `AssetConversion` pools, as returned by
+/// [`AssetConversionRouteApi::best_route`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct SwapRoute<Balance, AssetKind> {
+	/// The assets to swap through, as passed to `swap_exact_tokens_for_tokens` and
+	/// `swap_tokens_for_exact_tokens`.
+	pub path: Vec<AssetKind>,
+	/// The amount of the first asset swapped.
+	pub amount_in: Balance,
+	/// The amount of the last asset received.
+	pub amount_out: Balance,
+	/// The liquidity provider fees of all pools along the path, in the first asset.
+	pub fee: Balance,
+	/// How much worse than the pools' current prices the swap is, fees excluded.
+	pub price_impact: sp_runtime::Permill,
+}
+
 sp_api::decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait AssetsApi<AccountId, AssetBalance, AssetId>
 	where
 		AccountId: codec::Codec,
@@ -67,6 +145,65 @@ sp_api::decl_runtime_apis! {
 	{
 		/// Returns the list of `AssetId`s and corresponding balance that an `AccountId` has.
 		fn account_balances(account: AccountId) -> Vec<(AssetId, AssetBalance)>;
+		/// Returns the liquidity pool tokens and corresponding balance that an `AccountId` has.
+		#[api_version(2)]
+		fn pool_account_balances(account: AccountId) -> Vec<(AssetId, AssetBalance)>;
+		/// Returns the details of an asset, or `None` if it does not exist.
+		#[api_version(2)]
+		fn asset_details(asset: AssetId) -> Option<AssetDetails<AccountId, AssetBalance>>;
+		/// Returns the metadata of an asset, or `None` if it does not exist.
+		#[api_version(2)]
+		fn asset_metadata(asset: AssetId) -> Option<AssetMetadata>;
+		/// Returns the delegates `owner` approved to transfer an asset, with the approved amounts.
+		#[api_version(2)]
+		fn approvals(asset: AssetId, owner: AccountId) -> Vec<(AccountId, AssetBalance)>;
+		/// Returns the holding of an asset by `who`, or `None` if it holds none.
+		#[api_version(2)]
+		fn account(asset: AssetId, who: AccountId) -> Option<AssetAccount<AssetBalance>>;
+	}
+
+	/// Multi-hop swap quotes over the `AssetConversion` pools.
+	pub trait AssetConversionRouteApi<Balance, AssetKind>
+	where
+		Balance: codec::Codec,
+		AssetKind: codec::Codec,
+	{
+		/// Returns the best route through at most `MaxSwapPathLength` assets to swap `asset_in`
+		/// for `asset_out`, where `amount` is the exact amount in if `exact_in`, and the exact
+		/// amount out otherwise, or `None` if no pools with liquidity connect the assets.
+		fn best_route(
+			asset_in: AssetKind,
+			asset_out: AssetKind,
+			amount: Balance,
+			exact_in: bool,
+		) -> Option<SwapRoute<Balance, AssetKind>>;
+	}
+
+	/// Transaction fees in the assets `ChargeAssetTxPayment` accepts.
+	pub trait AssetTxPaymentApi<Balance, AssetKind>
+	where
+		Balance: codec::Codec,
+		AssetKind: codec::Codec,
+	{
+		/// Returns the inclusion fee of `uxt`, of encoded length `len`, when paid in `asset`, or
+		/// `None` if no `AssetConversion` pool with enough liquidity prices `asset` in the native
+		/// token.
+		fn query_fee_in_asset(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32,
+			asset: AssetKind,
+		) -> Option<Balance>;
+	}
+
+	/// The SNARK-friendly hashes of the runtime, for off-chain tooling to compute commitments
+	/// exactly as the chain and its contracts do.
+	pub trait ZkHashApi {
+		/// Poseidon of `inputs` as circomlib's `Poseidon(n)`, or `None` if there are none or more
+		/// than five, or an input is not a canonical BN254 scalar.
+		fn poseidon(inputs: Vec<[u8; 32]>) -> Option<[u8; 32]>;
+		/// MiMC-7 of `inputs` starting from `key` as circomlib's `MultiMiMC7`, or `None` if a
+		/// value is not a canonical BN254 scalar.
+		fn mimc(inputs: Vec<[u8; 32]>, key: [u8; 32]) -> Option<[u8; 32]>;
 	}
 }
 
@@ -233,6 +370,140 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_consensus_beefy::BeefyApi<Block, BeefyId> for Runtime {
+		fn beefy_genesis() -> Option<BlockNumber> {
+			pallet_beefy::GenesisBlock::<Runtime>::get()
+		}
+
+		fn validator_set() -> Option<sp_consensus_beefy::ValidatorSet<BeefyId>> {
+			Beefy::validator_set()
+		}
+
+		fn submit_report_double_voting_unsigned_extrinsic(
+			equivocation_proof: sp_consensus_beefy::DoubleVotingProof<
+				BlockNumber,
+				BeefyId,
+				BeefySignature,
+			>,
+			key_owner_proof: sp_consensus_beefy::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Beefy::submit_unsigned_double_voting_report(equivocation_proof, key_owner_proof)
+		}
+
+		fn submit_report_fork_voting_unsigned_extrinsic(
+			equivocation_proof: sp_consensus_beefy::ForkVotingProof<
+				<Block as BlockT>::Header,
+				BeefyId,
+				sp_runtime::OpaqueValue,
+			>,
+			key_owner_proof: sp_consensus_beefy::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			Beefy::submit_unsigned_fork_voting_report(
+				equivocation_proof.try_into()?,
+				key_owner_proof.decode()?,
+			)
+		}
+
+		fn submit_report_future_block_voting_unsigned_extrinsic(
+			equivocation_proof: sp_consensus_beefy::FutureBlockVotingProof<BlockNumber, BeefyId>,
+			key_owner_proof: sp_consensus_beefy::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			Beefy::submit_unsigned_future_block_voting_report(
+				equivocation_proof,
+				key_owner_proof.decode()?,
+			)
+		}
+
+		fn generate_key_ownership_proof(
+			_set_id: sp_consensus_beefy::ValidatorSetId,
+			authority_id: BeefyId,
+		) -> Option<sp_consensus_beefy::OpaqueKeyOwnershipProof> {
+			Historical::prove((sp_consensus_beefy::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(sp_consensus_beefy::OpaqueKeyOwnershipProof::new)
+		}
+
+		fn generate_ancestry_proof(
+			prev_block_number: BlockNumber,
+			best_known_block_number: Option<BlockNumber>,
+		) -> Option<sp_runtime::OpaqueValue> {
+			use sp_consensus_beefy::AncestryHelper;
+
+			BeefyMmrLeaf::generate_proof(prev_block_number, best_known_block_number)
+				.map(|p| p.encode())
+				.map(sp_runtime::OpaqueValue::new)
+		}
+	}
+
+	impl sp_mmr_primitives::MmrApi<Block, MmrHash, BlockNumber> for Runtime {
+		fn mmr_root() -> Result<MmrHash, sp_mmr_primitives::Error> {
+			Ok(pallet_mmr::RootHash::<Runtime>::get())
+		}
+
+		fn mmr_leaf_count() -> Result<sp_mmr_primitives::LeafIndex, sp_mmr_primitives::Error> {
+			Ok(pallet_mmr::NumberOfLeaves::<Runtime>::get())
+		}
+
+		fn generate_proof(
+			block_numbers: Vec<BlockNumber>,
+			best_known_block_number: Option<BlockNumber>,
+		) -> Result<
+			(Vec<sp_mmr_primitives::EncodableOpaqueLeaf>, sp_mmr_primitives::LeafProof<MmrHash>),
+			sp_mmr_primitives::Error,
+		> {
+			Mmr::generate_proof(block_numbers, best_known_block_number).map(|(leaves, proof)| {
+				let leaves = leaves
+					.into_iter()
+					.map(|leaf| sp_mmr_primitives::EncodableOpaqueLeaf::from_leaf(&leaf))
+					.collect();
+				(leaves, proof)
+			})
+		}
+
+		fn verify_proof(
+			leaves: Vec<sp_mmr_primitives::EncodableOpaqueLeaf>,
+			proof: sp_mmr_primitives::LeafProof<MmrHash>,
+		) -> Result<(), sp_mmr_primitives::Error> {
+			let leaves = leaves
+				.into_iter()
+				.map(|leaf| {
+					leaf.into_opaque_leaf().try_decode().ok_or(sp_mmr_primitives::Error::Verify)
+				})
+				.collect::<Result<Vec<MmrLeaf>, _>>()?;
+			Mmr::verify_leaves(leaves, proof)
+		}
+
+		fn verify_proof_stateless(
+			root: MmrHash,
+			leaves: Vec<sp_mmr_primitives::EncodableOpaqueLeaf>,
+			proof: sp_mmr_primitives::LeafProof<MmrHash>,
+		) -> Result<(), sp_mmr_primitives::Error> {
+			let nodes = leaves
+				.into_iter()
+				.map(|leaf| sp_mmr_primitives::DataOrHash::Data(leaf.into_opaque_leaf()))
+				.collect();
+			pallet_mmr::verify_leaves_proof::<Keccak256, _>(root, nodes, proof)
+		}
+	}
+
+	impl pallet_beefy_mmr::BeefyMmrApi<Block, MmrHash> for Runtime {
+		fn authority_set_proof() -> sp_consensus_beefy::mmr::BeefyAuthoritySet<MmrHash> {
+			BeefyMmrLeaf::authority_set_proof()
+		}
+
+		fn next_authority_set_proof() -> sp_consensus_beefy::mmr::BeefyNextAuthoritySet<MmrHash> {
+			BeefyMmrLeaf::next_authority_set_proof()
+		}
+	}
+
+	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
+		fn authorities() -> Vec<sp_authority_discovery::AuthorityId> {
+			AuthorityDiscovery::authorities()
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
@@ -339,6 +610,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(2)]
 	impl self::AssetsApi<
 		Block,
 		AccountId,
@@ -349,6 +621,60 @@ impl_runtime_apis! {
 		fn account_balances(account: AccountId) -> Vec<(u32, Balance)> {
 			Assets::account_balances(account)
 		}
+
+		fn pool_account_balances(account: AccountId) -> Vec<(u32, Balance)> {
+			PoolAssets::account_balances(account)
+		}
+
+		fn asset_details(asset: u32) -> Option<AssetDetails<AccountId, Balance>> {
+			pallet_assets::Asset::<Runtime, Instance1>::get(asset).map(|details| AssetDetails {
+				owner: details.owner,
+				issuer: details.issuer,
+				admin: details.admin,
+				freezer: details.freezer,
+				supply: details.supply,
+				min_balance: details.min_balance,
+				is_sufficient: details.is_sufficient,
+				accounts: details.accounts,
+				sufficients: details.sufficients,
+				approvals: details.approvals,
+				status: details.status,
+			})
+		}
+
+		fn asset_metadata(asset: u32) -> Option<AssetMetadata> {
+			if !pallet_assets::Asset::<Runtime, Instance1>::contains_key(asset) {
+				return None;
+			}
+			let metadata = pallet_assets::Metadata::<Runtime, Instance1>::get(asset);
+			Some(AssetMetadata {
+				name: metadata.name.into_inner(),
+				symbol: metadata.symbol.into_inner(),
+				decimals: metadata.decimals,
+				is_frozen: metadata.is_frozen,
+			})
+		}
+
+		fn approvals(asset: u32, owner: AccountId) -> Vec<(AccountId, Balance)> {
+			pallet_assets::Approvals::<Runtime, Instance1>::iter_prefix((asset, owner))
+				.map(|(delegate, approval)| (delegate, approval.amount))
+				.collect()
+		}
+
+		fn account(asset: u32, who: AccountId) -> Option<AssetAccount<Balance>> {
+			pallet_assets::Account::<Runtime, Instance1>::get(asset, who)
+				.map(|account| AssetAccount { balance: account.balance, status: account.status })
+		}
+	}
+
+	impl self::ZkHashApi<Block> for Runtime {
+		fn poseidon(inputs: Vec<[u8; 32]>) -> Option<[u8; 32]> {
+			kora_zk_hash::poseidon::hash_scalars(&inputs)
+		}
+
+		fn mimc(inputs: Vec<[u8; 32]>, key: [u8; 32]) -> Option<[u8; 32]> {
+			kora_zk_hash::mimc::hash_scalars(&inputs, &key)
+		}
 	}
 
 	impl pallet_asset_conversion::AssetConversionApi<
@@ -370,6 +696,37 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl self::AssetTxPaymentApi<Block, Balance, NativeOrWithId<u32>> for Runtime {
+		fn query_fee_in_asset(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32,
+			asset: NativeOrWithId<u32>,
+		) -> Option<Balance> {
+			let fee = TransactionPayment::query_info(uxt, len).partial_fee;
+			if asset == NativeOrWithId::Native {
+				return Some(fee);
+			}
+			// Priced as `SwapAssetAdapter` does when withdrawing the fee.
+			AssetConversion::quote_price_tokens_for_exact_tokens(
+				asset,
+				NativeOrWithId::Native,
+				fee,
+				true,
+			)
+		}
+	}
+
+	impl self::AssetConversionRouteApi<Block, Balance, NativeOrWithId<u32>> for Runtime {
+		fn best_route(
+			asset_in: NativeOrWithId<u32>,
+			asset_out: NativeOrWithId<u32>,
+			amount: Balance,
+			exact_in: bool,
+		) -> Option<SwapRoute<Balance, NativeOrWithId<u32>>> {
+			crate::swap_routes::best_route(asset_in, asset_out, amount, exact_in)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -525,4 +882,633 @@ impl_runtime_apis! {
 			)
 		}
 	}
+
+	impl pallet_revive::ReviveApi<Block, AccountId, Balance, Nonce, BlockNumber> for Runtime
+	{
+		fn balance(address: H160) -> U256 {
+			Revive::evm_balance(&address)
+		}
+
+		fn block_gas_limit() -> U256 {
+			Revive::evm_block_gas_limit()
+		}
+
+		fn gas_price() -> U256 {
+			Revive::evm_gas_price()
+		}
+
+		fn nonce(address: H160) -> Nonce {
+			let account =
+				<Runtime as pallet_revive::Config>::AddressMapper::to_account_id(&address);
+			System::account_nonce(account)
+		}
+
+		fn eth_transact(
+			tx: pallet_revive::evm::GenericTransaction,
+		) -> Result<pallet_revive::EthTransactInfo<Balance>, pallet_revive::EthTransactError>
+		{
+			let tx_fee = |call, mut dispatch_info: DispatchInfo| {
+				let call = RuntimeCall::Revive(call);
+				dispatch_info.extension_weight =
+					EthExtraImpl::get_eth_extension(0, 0).weight(&call);
+				let uxt: UncheckedExtrinsic =
+					sp_runtime::generic::UncheckedExtrinsic::new_bare(call).into();
+				TransactionPayment::compute_fee(uxt.encoded_size() as u32, &dispatch_info, 0)
+			};
+			Revive::bare_eth_transact(tx, RuntimeBlockWeights::get().max_block, tx_fee)
+		}
+
+		fn call(
+			origin: AccountId,
+			dest: H160,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> pallet_revive::ContractResult<pallet_revive::ExecReturnValue, Balance> {
+			Revive::bare_call(
+				RuntimeOrigin::signed(origin),
+				dest,
+				value,
+				gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block),
+				pallet_revive::DepositLimit::Balance(storage_deposit_limit.unwrap_or(Balance::MAX)),
+				input_data,
+			)
+		}
+
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: pallet_revive::Code,
+			data: Vec<u8>,
+			salt: Option<[u8; 32]>,
+		) -> pallet_revive::ContractResult<pallet_revive::InstantiateReturnValue, Balance>
+		{
+			Revive::bare_instantiate(
+				RuntimeOrigin::signed(origin),
+				value,
+				gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block),
+				pallet_revive::DepositLimit::Balance(storage_deposit_limit.unwrap_or(Balance::MAX)),
+				code,
+				data,
+				salt,
+			)
+		}
+
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+		) -> pallet_revive::CodeUploadResult<Balance>
+		{
+			Revive::bare_upload_code(
+				RuntimeOrigin::signed(origin),
+				code,
+				storage_deposit_limit.unwrap_or(Balance::MAX),
+			)
+		}
+
+		fn get_storage(
+			address: H160,
+			key: [u8; 32],
+		) -> pallet_revive::GetStorageResult {
+			Revive::get_storage(
+				address,
+				key
+			)
+		}
+
+		fn trace_block(
+			block: Block,
+			tracer_type: pallet_revive::evm::TracerType,
+		) -> Vec<(u32, pallet_revive::evm::Trace)> {
+			let mut tracer = Revive::evm_tracer(tracer_type);
+			let mut traces = Vec::new();
+			let (header, extrinsics) = block.deconstruct();
+
+			Executive::initialize_block(&header);
+			for (index, ext) in extrinsics.into_iter().enumerate() {
+				pallet_revive::tracing::trace(tracer.as_tracing(), || {
+					let _ = Executive::apply_extrinsic(ext);
+				});
+				if let Some(tx_trace) = tracer.collect_trace() {
+					traces.push((index as u32, tx_trace));
+				}
+			}
+
+			traces
+		}
+
+		fn trace_tx(
+			block: Block,
+			tx_index: u32,
+			tracer_type: pallet_revive::evm::TracerType,
+		) -> Option<pallet_revive::evm::Trace> {
+			let mut tracer = Revive::evm_tracer(tracer_type);
+			let (header, extrinsics) = block.deconstruct();
+
+			Executive::initialize_block(&header);
+			for (index, ext) in extrinsics.into_iter().enumerate() {
+				if index as u32 == tx_index {
+					pallet_revive::tracing::trace(tracer.as_tracing(), || {
+						let _ = Executive::apply_extrinsic(ext);
+					});
+					break;
+				}
+				let _ = Executive::apply_extrinsic(ext);
+			}
+
+			tracer.collect_trace()
+		}
+
+		fn trace_call(
+			tx: pallet_revive::evm::GenericTransaction,
+			tracer_type: pallet_revive::evm::TracerType,
+		) -> Result<pallet_revive::evm::Trace, pallet_revive::EthTransactError>
+		{
+			let mut tracer = Revive::evm_tracer(tracer_type);
+			let result =
+				pallet_revive::tracing::trace(tracer.as_tracing(), || Self::eth_transact(tx));
+
+			match (tracer.collect_trace(), result) {
+				(Some(trace), _) => Ok(trace),
+				(None, Err(err)) => Err(err),
+				(None, Ok(_)) => Ok(tracer.empty_trace()),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{
+		assert_ok,
+		traits::{fungibles, OnInitialize},
+	};
+	use pallet_beefy_mmr::runtime_decl_for_beefy_mmr_api::BeefyMmrApi;
+	use pallet_revive::runtime_decl_for_revive_api::ReviveApi;
+	use sp_consensus_beefy::runtime_decl_for_beefy_api::BeefyApi;
+	use sp_core::{
+		ecdsa,
+		offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+		Pair, H256,
+	};
+	use sp_mmr_primitives::runtime_decl_for_mmr_api::MmrApi;
+	use sp_runtime::BuildStorage;
+
+	const ALICE: AccountId = AccountId::new([1; 32]);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 1_000 * UNIT)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Revive::map_account(RuntimeOrigin::signed(ALICE)));
+		});
+		ext
+	}
+
+	/// Dry-runs a call of the precompile at `address` with `input`, returning its output.
+	fn call_precompile(address: u64, input: Vec<u8>) -> Vec<u8> {
+		let result = <Runtime as ReviveApi<Block, AccountId, Balance, Nonce, BlockNumber>>::call(
+			ALICE,
+			H160::from_low_u64_be(address),
+			0,
+			None,
+			None,
+			input,
+		);
+		let output = result.result.expect("precompile calls succeed");
+		assert!(!output.did_revert());
+		output.data
+	}
+
+	fn hex(digits: &str) -> Vec<u8> {
+		(0..digits.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn ecrecover_is_reachable() {
+		new_test_ext().execute_with(|| {
+			// keccak256("KoraChain") signed with the secret key 0x11..11.
+			let input = hex(concat!(
+				"9129ed672beb93f1bc951f58b8c2f6324855f7d4b85abe86278196767653fd2d",
+				"000000000000000000000000000000000000000000000000000000000000001c",
+				"644cbdb1b5dab582da40b0de60c1be1460b2b7b874452fcc6ed71fc91a751d0f",
+				"72c5d8b7cea18548e9df08b8f72e35869e726d40d9d0129c8de4e6f9b574c266",
+			));
+			assert_eq!(
+				call_precompile(0x01, input),
+				hex("00000000000000000000000019e7e376e7c213b7e7e7e46cc70a5dd086daff2a")
+			);
+		});
+	}
+
+	#[test]
+	fn sha256_is_reachable() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				call_precompile(0x02, b"abc".to_vec()),
+				hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+			);
+		});
+	}
+
+	#[test]
+	fn bn128_precompiles_are_reachable() {
+		new_test_ext().execute_with(|| {
+			let generator = hex(concat!(
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"0000000000000000000000000000000000000000000000000000000000000002",
+			));
+			let double = hex(concat!(
+				"030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+				"15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+			));
+
+			assert_eq!(
+				call_precompile(0x06, [generator.clone(), generator.clone()].concat()),
+				double
+			);
+
+			let mut two = [0u8; 32];
+			two[31] = 2;
+			assert_eq!(call_precompile(0x07, [generator, two.to_vec()].concat()), double);
+
+			// The empty pairing product is one.
+			let mut one = vec![0u8; 32];
+			one[31] = 1;
+			assert_eq!(call_precompile(0x08, Vec::new()), one);
+		});
+	}
+
+	fn beefy_id() -> BeefyId {
+		ecdsa::Pair::from_seed(&[1; 32]).public().into()
+	}
+
+	/// A chain with a single BEEFY authority, where BEEFY starts at `genesis_block`.
+	fn new_beefy_ext(genesis_block: Option<BlockNumber>) -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_beefy::GenesisConfig::<Runtime> { authorities: vec![beefy_id()], genesis_block }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+		sp_io::TestExternalities::new(storage)
+	}
+
+	/// Lets the offchain database the MMR indexed its nodes into be read back.
+	fn register_offchain_ext(ext: &mut sp_io::TestExternalities) {
+		let (offchain, _) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+		ext.register_extension(OffchainDbExt::new(offchain.clone()));
+		ext.register_extension(OffchainWorkerExt::new(offchain));
+	}
+
+	/// Starts `count` blocks, each adding the leaf of its parent to the MMR.
+	fn add_blocks(count: BlockNumber) {
+		for _ in 0..count {
+			let number = System::block_number() + 1;
+			System::initialize(&number, &H256::repeat_byte(number as u8), &Default::default());
+			Mmr::on_initialize(number);
+		}
+	}
+
+	#[test]
+	fn beefy_api_reports_the_genesis_and_the_validator_set() {
+		new_beefy_ext(Some(1)).execute_with(|| {
+			assert_eq!(<Runtime as BeefyApi<Block, BeefyId>>::beefy_genesis(), Some(1));
+			let validator_set =
+				<Runtime as BeefyApi<Block, BeefyId>>::validator_set().unwrap();
+			assert_eq!(validator_set.id(), 0);
+			assert_eq!(validator_set.validators(), &[beefy_id()]);
+		});
+
+		new_beefy_ext(None).execute_with(|| {
+			assert_eq!(<Runtime as BeefyApi<Block, BeefyId>>::beefy_genesis(), None);
+		});
+	}
+
+	#[test]
+	fn beefy_mmr_api_commits_to_the_current_and_next_authorities() {
+		new_beefy_ext(Some(1)).execute_with(|| {
+			let current = <Runtime as BeefyMmrApi<Block, MmrHash>>::authority_set_proof();
+			let next = <Runtime as BeefyMmrApi<Block, MmrHash>>::next_authority_set_proof();
+			assert_eq!((current.id, current.len), (0, 1));
+			assert_eq!((next.id, next.len), (1, 1));
+			assert_eq!(next.keyset_commitment, current.keyset_commitment);
+			assert_ne!(current.keyset_commitment, MmrHash::default());
+		});
+	}
+
+	#[test]
+	fn mmr_api_proves_the_leaves_of_past_blocks() {
+		let mut ext = new_beefy_ext(Some(1));
+		let root = ext.execute_with(|| {
+			add_blocks(5);
+			assert_eq!(<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::mmr_leaf_count(), Ok(5));
+			<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::mmr_root().unwrap()
+		});
+		ext.persist_offchain_overlay();
+		register_offchain_ext(&mut ext);
+
+		ext.execute_with(|| {
+			let (leaves, proof) =
+				<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::generate_proof(vec![2, 4], None)
+					.unwrap();
+			assert_eq!(leaves.len(), 2);
+			assert_eq!(proof.leaf_count, 5);
+
+			assert_eq!(
+				<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::verify_proof(
+					leaves.clone(),
+					proof.clone()
+				),
+				Ok(())
+			);
+			assert_eq!(
+				<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::verify_proof_stateless(
+					root,
+					leaves.clone(),
+					proof.clone()
+				),
+				Ok(())
+			);
+			assert!(<Runtime as MmrApi<Block, MmrHash, BlockNumber>>::verify_proof_stateless(
+				MmrHash::default(),
+				leaves,
+				proof
+			)
+			.is_err());
+		});
+	}
+
+	fn scalar(digits: &str) -> [u8; 32] {
+		hex(digits).try_into().unwrap()
+	}
+
+	fn small(value: u8) -> [u8; 32] {
+		let mut scalar = [0u8; 32];
+		scalar[31] = value;
+		scalar
+	}
+
+	// Reference values from circomlibjs, as in the tests of `kora-zk-hash`.
+	#[test]
+	fn zk_hash_api_matches_circomlib() {
+		use self::runtime_decl_for_zk_hash_api::ZkHashApi;
+
+		let poseidon = |inputs: Vec<[u8; 32]>| <Runtime as ZkHashApi<Block>>::poseidon(inputs);
+		assert_eq!(
+			poseidon(vec![small(1)]),
+			Some(scalar("29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133"))
+		);
+		assert_eq!(
+			poseidon(vec![small(1), small(2)]),
+			Some(scalar("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"))
+		);
+		assert_eq!(
+			poseidon(vec![small(1); 5]),
+			Some(scalar("10389605ae688d4f14db853122c47d66a803c72b41589cb1bf868741b206b9bb"))
+		);
+		assert_eq!(poseidon(vec![]), None);
+		assert_eq!(poseidon(vec![small(1); 6]), None);
+		assert_eq!(poseidon(vec![[0xff; 32]]), None);
+
+		let mimc = |inputs: Vec<[u8; 32]>, key| <Runtime as ZkHashApi<Block>>::mimc(inputs, key);
+		assert_eq!(
+			mimc(vec![small(12)], [0; 32]),
+			Some(scalar("237c92644dbddb86d8a259e0e923aaab65a93f1ec5758b8799988894ac0958fd"))
+		);
+		assert_eq!(
+			mimc(vec![small(12), small(45), small(78), small(41)], [0; 32]),
+			Some(scalar("284bc1f34f335933a23a433b6ff3ee179d682cd5e5e2fcdd2d964afa85104beb"))
+		);
+		assert_eq!(mimc(vec![small(1)], [0xff; 32]), None);
+	}
+
+	type AssetKind = NativeOrWithId<u32>;
+
+	const ASSET: u32 = 1;
+	const BOB: AccountId = AccountId::new([2; 32]);
+
+	/// A chain with a sufficient asset that only `ALICE` holds.
+	fn new_assets_ext() -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 1_000 * UNIT)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		pallet_assets::GenesisConfig::<Runtime, Instance1> {
+			assets: vec![(ASSET, ALICE, true, 1)],
+			metadata: vec![(ASSET, b"Kora Dollar".to_vec(), b"KUSD".to_vec(), 6)],
+			accounts: vec![(ASSET, ALICE, 500)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn assets_api_describes_an_asset() {
+		use self::runtime_decl_for_assets_api::AssetsApi;
+
+		new_assets_ext().execute_with(|| {
+			let details =
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::asset_details(ASSET)
+					.unwrap();
+			assert_eq!((details.owner, details.issuer), (ALICE, ALICE));
+			assert_eq!((details.supply, details.min_balance), (500, 1));
+			assert!(details.is_sufficient);
+			assert_eq!(details.accounts, 1);
+			assert_eq!(details.approvals, 0);
+			assert_eq!(details.status, pallet_assets::AssetStatus::Live);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::asset_metadata(ASSET),
+				Some(AssetMetadata {
+					name: b"Kora Dollar".to_vec(),
+					symbol: b"KUSD".to_vec(),
+					decimals: 6,
+					is_frozen: false,
+				})
+			);
+
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::asset_details(2),
+				None
+			);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::asset_metadata(2),
+				None
+			);
+		});
+	}
+
+	#[test]
+	fn assets_api_describes_holdings_and_approvals() {
+		use self::runtime_decl_for_assets_api::AssetsApi;
+
+		new_assets_ext().execute_with(|| {
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::account_balances(ALICE),
+				vec![(ASSET, 500)]
+			);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::pool_account_balances(
+					ALICE
+				),
+				vec![]
+			);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::account(ASSET, ALICE),
+				Some(AssetAccount { balance: 500, status: pallet_assets::AccountStatus::Liquid })
+			);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::account(ASSET, BOB),
+				None
+			);
+
+			assert_ok!(Assets::approve_transfer(
+				RuntimeOrigin::signed(ALICE),
+				ASSET.into(),
+				BOB.into(),
+				100
+			));
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::approvals(ASSET, ALICE),
+				vec![(BOB, 100)]
+			);
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::asset_details(ASSET)
+					.unwrap()
+					.approvals,
+				1
+			);
+
+			assert_ok!(Assets::freeze(RuntimeOrigin::signed(ALICE), ASSET.into(), ALICE.into()));
+			assert_eq!(
+				<Runtime as AssetsApi<Block, AccountId, Balance, u32>>::account(ASSET, ALICE)
+					.map(|account| account.status),
+				Some(pallet_assets::AccountStatus::Frozen)
+			);
+		});
+	}
+
+	/// Creates the pool of the native token and `ASSET`, at two `ASSET` per native token.
+	fn add_native_pool() {
+		let native = Box::new(NativeOrWithId::Native);
+		let asset = Box::new(NativeOrWithId::WithId(ASSET));
+		assert_ok!(<Assets as fungibles::Mutate<_>>::mint_into(ASSET, &ALICE, 200 * UNIT));
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(ALICE),
+			native.clone(),
+			asset.clone()
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(ALICE),
+			native,
+			asset,
+			100 * UNIT,
+			200 * UNIT,
+			1,
+			1,
+			ALICE
+		));
+	}
+
+	#[test]
+	fn asset_conversion_route_api_swaps_through_the_pool() {
+		use self::runtime_decl_for_asset_conversion_route_api::AssetConversionRouteApi;
+
+		new_assets_ext().execute_with(|| {
+			let best_route = |asset_in, asset_out, amount, exact_in| {
+				<Runtime as AssetConversionRouteApi<Block, Balance, AssetKind>>::best_route(
+					asset_in, asset_out, amount, exact_in,
+				)
+			};
+			let asset = NativeOrWithId::WithId(ASSET);
+			assert_eq!(best_route(NativeOrWithId::Native, asset.clone(), UNIT, true), None);
+
+			add_native_pool();
+			let route = best_route(NativeOrWithId::Native, asset.clone(), UNIT, true).unwrap();
+			assert_eq!(route.path, vec![NativeOrWithId::Native, asset.clone()]);
+			assert_eq!(route.amount_in, UNIT);
+			assert_eq!(
+				Some(route.amount_out),
+				AssetConversion::quote_price_exact_tokens_for_tokens(
+					NativeOrWithId::Native,
+					asset.clone(),
+					UNIT,
+					true
+				)
+			);
+			assert!(route.fee > 0);
+
+			let route = best_route(asset.clone(), NativeOrWithId::Native, UNIT, false).unwrap();
+			assert_eq!(route.path, vec![asset, NativeOrWithId::Native]);
+			assert_eq!(route.amount_out, UNIT);
+			assert!(route.amount_in > 2 * UNIT);
+
+			assert_eq!(
+				best_route(NativeOrWithId::Native, NativeOrWithId::WithId(2), UNIT, true),
+				None
+			);
+		});
+	}
+
+	#[test]
+	fn asset_tx_payment_api_quotes_the_fee_in_an_asset() {
+		use self::runtime_decl_for_asset_tx_payment_api::AssetTxPaymentApi;
+
+		new_assets_ext().execute_with(|| {
+			let uxt: UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic::new_signed(
+				RuntimeCall::System(frame_system::Call::remark { remark: vec![0; 32] }),
+				ALICE.into(),
+				Signature::Sr25519(sp_core::sr25519::Signature::from_raw([0; 64])),
+				EthExtraImpl::get_eth_extension(0, 0),
+			)
+			.into();
+			let len = uxt.encoded_size() as u32;
+			let query_fee_in_asset = |asset| {
+				<Runtime as AssetTxPaymentApi<Block, Balance, AssetKind>>::query_fee_in_asset(
+					uxt.clone(),
+					len,
+					asset,
+				)
+			};
+			let fee = TransactionPayment::query_info(uxt.clone(), len).partial_fee;
+			assert!(fee > 0);
+
+			assert_eq!(query_fee_in_asset(NativeOrWithId::Native), Some(fee));
+			assert_eq!(query_fee_in_asset(NativeOrWithId::WithId(ASSET)), None);
+
+			add_native_pool();
+			let quote = AssetConversion::quote_price_tokens_for_exact_tokens(
+				NativeOrWithId::WithId(ASSET),
+				NativeOrWithId::Native,
+				fee,
+				true,
+			);
+			assert_eq!(query_fee_in_asset(NativeOrWithId::WithId(ASSET)), quote);
+			assert!(quote.unwrap() > 2 * fee);
+			assert_eq!(query_fee_in_asset(NativeOrWithId::WithId(2)), None);
+		});
+	}
 }
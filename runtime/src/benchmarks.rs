@@ -30,4 +30,16 @@ frame_benchmarking::define_benchmarks!(
 	[pallet_balances, Balances]
 	[pallet_timestamp, Timestamp]
 	[pallet_sudo, Sudo]
+	[pallet_zk_verifier, ZkVerifier]
+	[pallet_zkvm_verifier, ZkvmVerifier]
+	[pallet_shielded_pool, ShieldedPool]
+	[pallet_template, Template]
+	[pallet_revive, Revive]
+	[pallet_asset_conversion_tx_payment, AssetConversionTxPayment]
+	[pallet_mmr, Mmr]
+	[pallet_beefy_mmr, BeefyMmrLeaf]
+	[pallet_collective, TechnicalCommittee]
+	[pallet_membership, TechnicalMembership]
+	[pallet_safe_mode, SafeMode]
+	[pallet_tx_pause, TxPause]
 );
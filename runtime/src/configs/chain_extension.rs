@@ -0,0 +1,787 @@
+//! The chain extension available to ink! contracts.
+//!
+//! A contract selects a function through the `func_id` half of the extension id; the `ext_id`
+//! half is ignored since this is the only extension of the runtime. Inputs and outputs are SCALE
+//! encoded, and the `u32` handed back to the contract is a [`Status`]. Asset functions act on
+//! `pallet_assets::Instance1` on behalf of the calling contract.
+
+use super::*;
+use pallet_contracts::chain_extension::{
+	BufInBufOutState, ChainExtension, Environment, Ext, InitState, RetVal,
+};
+use frame_support::{
+	storage::with_storage_layer,
+	traits::{fungibles, tokens::Preservation, Contains},
+};
+use pallet_assets::WeightInfo as _;
+use kora_zk_hash::{mimc, poseidon, Scalar};
+use pallet_zk_verifier::{
+	EncodedProofOf, PublicInputsOf, ScalarBytes, VerificationKeyInfo, VkId, WeightInfo as _,
+};
+use pallet_zkvm_verifier::VerifiedJournalOf;
+use sp_runtime::{traits::Zero, DispatchError, TokenError};
+
+type AssetId = <Runtime as pallet_assets::Config<Instance1>>::AssetId;
+type AssetBalance = <Runtime as pallet_assets::Config<Instance1>>::Balance;
+type AssetsWeight = <Runtime as pallet_assets::Config<Instance1>>::WeightInfo;
+type ZkWeight = <Runtime as pallet_zk_verifier::Config>::WeightInfo;
+
+/// Function ids understood by [`KoraChainExtension`].
+pub mod func {
+	/// Verify a proof against a registered key.
+	///
+	/// Input: `(VkId, proof: Vec<u8>, Vec<[u8; 32]>)`, the proof in the encoding of the proving
+	/// system of the key. Output: none, the outcome is the status.
+	pub const ZK_VERIFY: u16 = 0x0000;
+	/// Read the metadata of a registered key.
+	///
+	/// Input: `VkId`. Output: `Option<VerificationKeyInfo>`.
+	pub const ZK_KEY_INFO: u16 = 0x0001;
+	/// Read the journal recorded for a verified zkVM receipt.
+	///
+	/// Input: `Hash` of the receipt. Output: `Option<VerifiedJournal>`.
+	pub const ZKVM_JOURNAL: u16 = 0x0002;
+	/// Poseidon over BN254 as circomlib's `Poseidon(n)`.
+	///
+	/// Input: `Vec<[u8; 32]>` of one to five scalars. Output: `[u8; 32]`.
+	pub const ZK_POSEIDON: u16 = 0x0003;
+	/// MiMC-7 over BN254 as circomlib's `MultiMiMC7`.
+	///
+	/// Input: `(Vec<[u8; 32]>, key: [u8; 32])`. Output: `[u8; 32]`.
+	pub const ZK_MIMC: u16 = 0x0004;
+
+	/// Balance of an account in `pallet_assets::Instance1`.
+	///
+	/// Input: `(AssetId, AccountId)`. Output: `Balance`.
+	pub const ASSETS_BALANCE_OF: u16 = 0x0100;
+	/// Total supply of an asset.
+	///
+	/// Input: `AssetId`. Output: `Balance`.
+	pub const ASSETS_TOTAL_SUPPLY: u16 = 0x0101;
+	/// Amount `spender` may transfer on behalf of `owner`.
+	///
+	/// Input: `(AssetId, owner: AccountId, spender: AccountId)`. Output: `Balance`.
+	pub const ASSETS_ALLOWANCE: u16 = 0x0102;
+	/// Transfer from the calling contract.
+	///
+	/// Input: `(AssetId, to: AccountId, Balance)`. Output: none.
+	pub const ASSETS_TRANSFER: u16 = 0x0103;
+	/// Transfer from `from` using the allowance granted to the calling contract.
+	///
+	/// Input: `(AssetId, from: AccountId, to: AccountId, Balance)`. Output: none.
+	pub const ASSETS_TRANSFER_FROM: u16 = 0x0104;
+	/// Set the allowance of `spender` over the calling contract's balance.
+	///
+	/// Input: `(AssetId, spender: AccountId, Balance)`. Output: none.
+	pub const ASSETS_APPROVE: u16 = 0x0105;
+}
+
+/// Status codes returned to the calling contract.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[repr(u32)]
+pub enum Status {
+	Success = 0,
+	/// The proof is well-formed but does not hold for the public inputs.
+	InvalidProof = 1,
+	/// No verification key is registered under the requested id.
+	UnknownVerificationKey = 2,
+	/// The proof, a public input or a value to hash could not be decoded.
+	MalformedInput = 3,
+	/// The number of public inputs does not match the verification key.
+	PublicInputCountMismatch = 4,
+	/// The asset does not exist.
+	UnknownAsset = 10,
+	/// The account does not hold enough of the asset, or would be reaped by the transfer.
+	InsufficientBalance = 11,
+	/// The allowance of the calling contract is too low.
+	InsufficientAllowance = 12,
+	/// The asset or the account is frozen, or the asset is being destroyed.
+	AssetUnavailable = 13,
+	/// The asset operation failed for another reason.
+	AssetOperationFailed = 14,
+}
+
+impl From<Status> for RetVal {
+	fn from(status: Status) -> Self {
+		RetVal::Converging(status as u32)
+	}
+}
+
+impl From<pallet_zk_verifier::Error<Runtime>> for Status {
+	fn from(error: pallet_zk_verifier::Error<Runtime>) -> Self {
+		use pallet_zk_verifier::Error;
+		match error {
+			Error::UnknownVerificationKey => Status::UnknownVerificationKey,
+			Error::PublicInputCountMismatch => Status::PublicInputCountMismatch,
+			_ => Status::MalformedInput,
+		}
+	}
+}
+
+/// The chain extension of the KoraChain runtime, dispatching on [`func`] ids.
+#[derive(Default)]
+pub struct KoraChainExtension;
+
+impl ChainExtension<Runtime> for KoraChainExtension {
+	fn call<E: Ext<T = Runtime>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+		match func_id {
+			func::ZK_VERIFY => zk_verify(&mut env),
+			func::ZK_KEY_INFO => zk_key_info(&mut env),
+			func::ZKVM_JOURNAL => zkvm_journal(&mut env),
+			func::ZK_POSEIDON => zk_poseidon(&mut env),
+			func::ZK_MIMC => zk_mimc(&mut env),
+			func::ASSETS_BALANCE_OF => assets_balance_of(&mut env),
+			func::ASSETS_TOTAL_SUPPLY => assets_total_supply(&mut env),
+			func::ASSETS_ALLOWANCE => assets_allowance(&mut env),
+			func::ASSETS_TRANSFER => assets_transfer(&mut env),
+			func::ASSETS_TRANSFER_FROM => assets_transfer_from(&mut env),
+			func::ASSETS_APPROVE => assets_approve(&mut env),
+			_ => Err(DispatchError::Other("Invalid func_id")),
+		}
+	}
+
+	fn enabled() -> bool {
+		true
+	}
+}
+
+fn zk_verify<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	// The input is only bounded once decoded and the proving system is only known from the key,
+	// so charge for the most expensive backend and largest circuit up front and refund the
+	// difference afterwards.
+	let max_inputs = <Runtime as pallet_zk_verifier::Config>::MaxPublicInputs::get();
+	let charged = env.charge_weight(ZkVerifier::max_verify_weight(max_inputs))?;
+	let (vk_id, proof, inputs): (VkId, EncodedProofOf<Runtime>, PublicInputsOf<Runtime>) =
+		env.read_as_unbounded(env.in_len())?;
+	env.adjust_weight(charged, verify_weight(vk_id, inputs.len() as u32));
+
+	Ok(do_verify(vk_id, &proof, &inputs).into())
+}
+
+fn zk_key_info<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+	let vk_id: VkId = env.read_as()?;
+
+	env.write(&do_key_info(vk_id).encode(), false, None)?;
+	Ok(Status::Success.into())
+}
+
+fn zkvm_journal<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+	let receipt_hash: Hash = env.read_as()?;
+
+	env.write(&do_zkvm_journal(receipt_hash).encode(), false, None)?;
+	Ok(Status::Success.into())
+}
+
+fn zk_poseidon<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	let max_inputs = poseidon::MAX_INPUTS as u32;
+	let charged = env.charge_weight(ZkWeight::hash_poseidon(max_inputs))?;
+	let inputs: BoundedVec<Scalar, ConstU32<{ poseidon::MAX_INPUTS as u32 }>> = env.read_as()?;
+	env.adjust_weight(charged, ZkWeight::hash_poseidon(inputs.len() as u32));
+
+	match do_poseidon(&inputs) {
+		Ok(hash) => {
+			env.write(&hash.encode(), false, None)?;
+			Ok(Status::Success.into())
+		},
+		Err(status) => Ok(status.into()),
+	}
+}
+
+fn zk_mimc<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	// Every input takes 32 bytes, so the input length bounds the work before it is decoded.
+	let charged = env.charge_weight(ZkWeight::hash_mimc(env.in_len() / 32))?;
+	let (inputs, key): (Vec<Scalar>, Scalar) = env.read_as_unbounded(env.in_len())?;
+	env.adjust_weight(charged, ZkWeight::hash_mimc(inputs.len() as u32));
+
+	match do_mimc(&inputs, &key) {
+		Ok(hash) => {
+			env.write(&hash.encode(), false, None)?;
+			Ok(Status::Success.into())
+		},
+		Err(status) => Ok(status.into()),
+	}
+}
+
+fn assets_balance_of<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+	let (asset, who): (AssetId, AccountId) = env.read_as()?;
+
+	env.write(&<Assets as fungibles::Inspect<_>>::balance(asset, &who).encode(), false, None)?;
+	Ok(Status::Success.into())
+}
+
+fn assets_total_supply<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+	let asset: AssetId = env.read_as()?;
+
+	env.write(&<Assets as fungibles::Inspect<_>>::total_issuance(asset).encode(), false, None)?;
+	Ok(Status::Success.into())
+}
+
+fn assets_allowance<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+	let (asset, owner, spender): (AssetId, AccountId, AccountId) = env.read_as()?;
+
+	let allowance =
+		<Assets as fungibles::approvals::Inspect<_>>::allowance(asset, &owner, &spender);
+	env.write(&allowance.encode(), false, None)?;
+	Ok(Status::Success.into())
+}
+
+/// Transfers `amount` of `asset` from the calling contract to `to`.
+fn assets_transfer<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	ensure_mutable(env)?;
+	env.charge_weight(AssetsWeight::transfer())?;
+	let (asset, to, amount): (AssetId, AccountId, AssetBalance) = env.read_as()?;
+	ensure_callable(
+		env,
+		RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: asset.into(),
+			target: to.clone().into(),
+			amount,
+		}),
+	)?;
+	let contract = env.ext().address().clone();
+
+	Ok(do_transfer(&contract, asset, &to, amount).into())
+}
+
+/// Transfers `amount` of `asset` from `from` to `to`, spending the allowance `from` granted to
+/// the calling contract.
+fn assets_transfer_from<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	ensure_mutable(env)?;
+	env.charge_weight(AssetsWeight::transfer_approved())?;
+	let (asset, from, to, amount): (AssetId, AccountId, AccountId, AssetBalance) =
+		env.read_as()?;
+	ensure_callable(
+		env,
+		RuntimeCall::Assets(pallet_assets::Call::transfer_approved {
+			id: asset.into(),
+			owner: from.clone().into(),
+			destination: to.clone().into(),
+			amount,
+		}),
+	)?;
+	let contract = env.ext().address().clone();
+
+	Ok(do_transfer_from(&contract, asset, &from, &to, amount).into())
+}
+
+/// Sets the allowance of `spender` over the calling contract's `asset` balance to `amount`.
+fn assets_approve<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<RetVal, DispatchError> {
+	ensure_mutable(env)?;
+	let charged = env.charge_weight(
+		AssetsWeight::cancel_approval().saturating_add(AssetsWeight::approve_transfer()),
+	)?;
+	let (asset, spender, amount): (AssetId, AccountId, AssetBalance) = env.read_as()?;
+	ensure_callable(
+		env,
+		RuntimeCall::Assets(pallet_assets::Call::approve_transfer {
+			id: asset.into(),
+			delegate: spender.clone().into(),
+			amount,
+		}),
+	)?;
+	let contract = env.ext().address().clone();
+
+	if !has_approval(asset, &contract, &spender) {
+		env.adjust_weight(charged, AssetsWeight::approve_transfer());
+	}
+
+	Ok(do_approve(&contract, asset, &spender, amount).into())
+}
+
+/// Weight of verifying a proof with `public_inputs` inputs against the key registered under
+/// `vk_id`, or of finding out that there is none.
+fn verify_weight(vk_id: VkId, public_inputs: u32) -> Weight {
+	match ZkVerifier::verification_key_info(vk_id) {
+		Some(info) => ZkVerifier::verify_weight(info.system, public_inputs),
+		None => <Runtime as frame_system::Config>::DbWeight::get().reads(1),
+	}
+}
+
+/// Checks `proof` for `inputs` against the key registered under `vk_id`.
+fn do_verify(vk_id: VkId, proof: &[u8], inputs: &[ScalarBytes]) -> Status {
+	match ZkVerifier::verify_proof(vk_id, proof, inputs) {
+		Ok(true) => Status::Success,
+		Ok(false) => Status::InvalidProof,
+		Err(error) => error.into(),
+	}
+}
+
+/// Metadata of the key registered under `vk_id`.
+fn do_key_info(vk_id: VkId) -> Option<VerificationKeyInfo> {
+	ZkVerifier::verification_key_info(vk_id)
+}
+
+/// Journal recorded for the zkVM receipt hashing to `receipt_hash`.
+fn do_zkvm_journal(receipt_hash: Hash) -> Option<VerifiedJournalOf<Runtime>> {
+	ZkvmVerifier::verified_journal(receipt_hash)
+}
+
+/// Poseidon hash of `inputs`, or [`Status::MalformedInput`] for a non-canonical scalar or too
+/// many inputs.
+fn do_poseidon(inputs: &[Scalar]) -> Result<Scalar, Status> {
+	poseidon::hash_scalars(inputs).ok_or(Status::MalformedInput)
+}
+
+/// MiMC hash of `inputs` starting from `key`, or [`Status::MalformedInput`] for a
+/// non-canonical scalar.
+fn do_mimc(inputs: &[Scalar], key: &Scalar) -> Result<Scalar, Status> {
+	mimc::hash_scalars(inputs, key).ok_or(Status::MalformedInput)
+}
+
+/// Transfers `amount` of `asset` from `contract` to `to`.
+fn do_transfer(
+	contract: &AccountId,
+	asset: AssetId,
+	to: &AccountId,
+	amount: AssetBalance,
+) -> Status {
+	let result = with_storage_layer(|| {
+		<Assets as fungibles::Mutate<_>>::transfer(
+			asset,
+			contract,
+			to,
+			amount,
+			Preservation::Expendable,
+		)
+	});
+	asset_status(result.map(|_| ()))
+}
+
+/// Transfers `amount` of `asset` from `from` to `to`, spending the allowance of `contract`.
+fn do_transfer_from(
+	contract: &AccountId,
+	asset: AssetId,
+	from: &AccountId,
+	to: &AccountId,
+	amount: AssetBalance,
+) -> Status {
+	let result = with_storage_layer(|| {
+		<Assets as fungibles::approvals::Mutate<_>>::transfer_from(asset, from, contract, to, amount)
+	});
+	asset_status(result)
+}
+
+/// Sets the allowance of `spender` over the `asset` balance of `contract` to `amount`.
+///
+/// `pallet_assets` approvals are cumulative, so an existing approval is cancelled first to give
+/// the call PSP22 `approve` semantics.
+fn do_approve(
+	contract: &AccountId,
+	asset: AssetId,
+	spender: &AccountId,
+	amount: AssetBalance,
+) -> Status {
+	let replace = has_approval(asset, contract, spender);
+	let result = with_storage_layer(|| {
+		if replace {
+			Assets::cancel_approval(
+				RuntimeOrigin::signed(contract.clone()),
+				asset.into(),
+				spender.clone().into(),
+			)?;
+		}
+		if amount.is_zero() {
+			return Ok(());
+		}
+		<Assets as fungibles::approvals::Mutate<_>>::approve(asset, contract, spender, amount)
+	});
+	asset_status(result)
+}
+
+/// Whether `owner` has approved `spender` to transfer some of its `asset`.
+fn has_approval(asset: AssetId, owner: &AccountId, spender: &AccountId) -> bool {
+	!<Assets as fungibles::approvals::Inspect<_>>::allowance(asset, owner, spender).is_zero()
+}
+
+/// Rejects state changes when the contract was called through a read-only call.
+fn ensure_mutable<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> Result<(), DispatchError> {
+	if env.ext().is_read_only() {
+		return Err(pallet_contracts::Error::<Runtime>::StateChangeDenied.into());
+	}
+	Ok(())
+}
+
+/// Rejects asset operations whose extrinsic the runtime would filter, so contracts cannot get
+/// around calls paused through `TxPause` or stopped by safe mode.
+fn ensure_callable<E: Ext<T = Runtime>>(
+	env: &mut Environment<E, BufInBufOutState>,
+	call: RuntimeCall,
+) -> Result<(), DispatchError> {
+	// Safe mode and the two pauses that may cover the call.
+	env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(3))?;
+	check_callable(&call)
+}
+
+/// Checks `call` against the runtime's call filter.
+fn check_callable(call: &RuntimeCall) -> Result<(), DispatchError> {
+	if !<Runtime as frame_system::Config>::BaseCallFilter::contains(call) {
+		return Err(frame_system::Error::<Runtime>::CallFiltered.into());
+	}
+	Ok(())
+}
+
+/// Maps the outcome of an asset operation to the status reported to the contract.
+///
+/// Operations run in their own storage layer, so a failure reported here left no changes behind.
+fn asset_status(result: Result<(), DispatchError>) -> Status {
+	use pallet_assets::Error;
+
+	let Err(error) = result else { return Status::Success };
+	if matches!(error, DispatchError::Token(TokenError::UnknownAsset)) ||
+		error == Error::<Runtime, Instance1>::Unknown.into()
+	{
+		Status::UnknownAsset
+	} else if error == Error::<Runtime, Instance1>::Unapproved.into() {
+		Status::InsufficientAllowance
+	} else if matches!(
+		error,
+		DispatchError::Token(TokenError::FundsUnavailable | TokenError::OnlyProvider)
+	) || error == Error::<Runtime, Instance1>::BalanceLow.into()
+	{
+		Status::InsufficientBalance
+	} else if matches!(error, DispatchError::Token(TokenError::Frozen)) ||
+		error == Error::<Runtime, Instance1>::Frozen.into() ||
+		error == Error::<Runtime, Instance1>::AssetNotLive.into()
+	{
+		Status::AssetUnavailable
+	} else {
+		Status::AssetOperationFailed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{configs::test_utils::technical_committee, RuntimeOrigin, SafeMode, TxPause, UNIT};
+	use frame_support::{assert_ok, BoundedVec};
+	use pallet_zk_verifier::ProvingSystem;
+	use pallet_zkvm_verifier::VerifiedJournal;
+	use sp_runtime::BuildStorage;
+
+	const ASSET: AssetId = 7;
+	const CONTRACT: AccountId = AccountId::new([1; 32]);
+	const OWNER: AccountId = AccountId::new([2; 32]);
+	const SPENDER: AccountId = AccountId::new([3; 32]);
+	const RECIPIENT: AccountId = AccountId::new([4; 32]);
+	const VK: VkId = 7;
+
+	// A Groth16 key over BN254 for public `a * b` and `a + b`, and a proof of `3 * 11 = 33` and
+	// `3 + 11 = 14`, from the seeded setup of the `pallet-zk-verifier` tests.
+	const GROTH16_KEY: &str = concat!(
+		"1b98bd7121059a54dc345ac11d9a9bc91daca40663b5a0f7ce4a2d0deb1473e50083a387fc02bb22fa7dbdb9",
+		"874e3ec198ebc304d28f796e688bee5102b339f116387d404bebaaedb9fdfd774e8f082ceebea6f8c2fc9575",
+		"360d089e1248f9a22d5649bf4f22887a88f405c54c403f71f5c3f020059fb356be83c392af33651e04f70562",
+		"268c1fa399554308159f01835284af1a6d0fccd78de1bbb477055d9f1df2871e630a4c9f32e94d5e9e23e1cc",
+		"720aa2025a8c2c622ec22f89aa9c665322812a9dcf99290dbc872f5c799257e8125b03e42ce32bed68ffdfe3",
+		"f61586bb1a9d10e6a696d31e93a990e233f6ee8a19e39a4ba72ba8b7cb364292ed2de41717a1612794a1520d",
+		"f48a2e45592e3a2923ee943abca3990a3b15252bc193caa51af62461cc049911a439590e8ad01286f5f55366",
+		"9ab746f37217d8cba17009e21e61ea1d0c50136f4af52d47a79a1b62fadc59cda66326a621c82cd4162c2e3e",
+		"2fb1be88becc810d5a5097ae1be857279db42dac5711ce5495e57b2cb7e7954705d0b059511f76d06d076b4e",
+		"a57f833e40ea923bef3e9a530f5bd7a444ff310b154d53711dd20e5c1d18ad89e74b27907417bd8af3219381",
+		"d43b3b7f8d598e272da5d8e85d91584e157433a60fa627b2a133cda58b96f777cf978a77904efd5e17b31993",
+		"afe542a0d3df5fb12e13dc906fbb5220e20eccbb43d063ffdce524de08189dee73936ce9792f37346d4e61d3",
+		"f72220c53fee49d7abddb42f98577b037301769b136e74d77d1e78f8c0088f670e58e7dc9ae98c754d257210",
+		"d4cf883c0527db64a215db065d86b6c281f9e5907ea4ef03b7020eba4d636d683c652457bb09ecb661e7f4cb",
+		"ae74724051d0b8f8a998b0cecd52880499f4af1f6a0a672e2e",
+	);
+	const GROTH16_PROOF: &str = concat!(
+		"202f1a09434d76f0ef635515b4b4357add9cc4094d0e85b52de19b567a88acdb2e6d02a7e484d2e2f47beff7",
+		"d7ab38d7f466aa56de5a5e7cfbbea1146bae48622c5290a030a0505375973c6caa0e20023455920831f0ab8c",
+		"bee312daa88076370031bbda00817b0a89f9b51f2334bfb1863e66fd18f5be7f5047bd1263c36f2b0ff6278d",
+		"aed0ceafc2a0bfb50b8d0d5159f12d0c77f86fc2dc69b78758efd6f402cfc091abcefc6b8d5a17f90d6918c7",
+		"71bec3fa9685e237acdd98246f36520202b79a66284fdccf344bfb7a2b543ef59d475810c8dc958ae878e380",
+		"afbbfa7e141baeb13769c036066f8975572c36540ec0ff92789d77711f38f811c49351be",
+	);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(CONTRACT, 1_000 * UNIT), (OWNER, 1_000 * UNIT)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		pallet_assets::GenesisConfig::<Runtime, Instance1> {
+			assets: vec![(ASSET, OWNER, true, 1)],
+			accounts: vec![(ASSET, CONTRACT, 1_000), (ASSET, OWNER, 1_000)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn hex(digits: &str) -> Vec<u8> {
+		(0..digits.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+			.collect()
+	}
+
+	fn small(value: u8) -> Scalar {
+		let mut scalar = [0u8; 32];
+		scalar[31] = value;
+		scalar
+	}
+
+	fn scalar(digits: &str) -> Scalar {
+		hex(digits).try_into().unwrap()
+	}
+
+	fn register_key() {
+		assert_ok!(ZkVerifier::register_verification_key(
+			RuntimeOrigin::root(),
+			VK,
+			ProvingSystem::Groth16Bn254,
+			hex(GROTH16_KEY).try_into().unwrap()
+		));
+	}
+
+	fn asset_transfer() -> RuntimeCall {
+		RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: ASSET.into(),
+			target: RECIPIENT.into(),
+			amount: 1,
+		})
+	}
+
+	fn balance_of(who: &AccountId) -> AssetBalance {
+		<Assets as fungibles::Inspect<_>>::balance(ASSET, who)
+	}
+
+	fn allowance(owner: &AccountId, spender: &AccountId) -> AssetBalance {
+		<Assets as fungibles::approvals::Inspect<_>>::allowance(ASSET, owner, spender)
+	}
+
+	#[test]
+	fn transfers_move_the_balance_of_the_contract() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(do_transfer(&CONTRACT, ASSET, &RECIPIENT, 400), Status::Success);
+			assert_eq!(balance_of(&CONTRACT), 600);
+			assert_eq!(balance_of(&RECIPIENT), 400);
+			assert_eq!(<Assets as fungibles::Inspect<_>>::total_issuance(ASSET), 2_000);
+
+			assert_eq!(
+				do_transfer(&CONTRACT, ASSET, &RECIPIENT, 601),
+				Status::InsufficientBalance
+			);
+			assert_eq!(do_transfer(&CONTRACT, ASSET + 1, &RECIPIENT, 1), Status::UnknownAsset);
+			assert_eq!(balance_of(&CONTRACT), 600);
+		});
+	}
+
+	#[test]
+	fn approve_replaces_the_existing_allowance() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(do_approve(&CONTRACT, ASSET, &SPENDER, 300), Status::Success);
+			assert_eq!(allowance(&CONTRACT, &SPENDER), 300);
+
+			// `pallet_assets` alone would add up to 400.
+			assert_eq!(do_approve(&CONTRACT, ASSET, &SPENDER, 100), Status::Success);
+			assert_eq!(allowance(&CONTRACT, &SPENDER), 100);
+
+			assert_eq!(do_approve(&CONTRACT, ASSET, &SPENDER, 0), Status::Success);
+			assert_eq!(allowance(&CONTRACT, &SPENDER), 0);
+			assert!(!has_approval(ASSET, &CONTRACT, &SPENDER));
+		});
+	}
+
+	#[test]
+	fn transfer_from_spends_the_allowance_of_the_contract() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(do_approve(&OWNER, ASSET, &CONTRACT, 300), Status::Success);
+
+			assert_eq!(
+				do_transfer_from(&CONTRACT, ASSET, &OWNER, &RECIPIENT, 200),
+				Status::Success
+			);
+			assert_eq!(balance_of(&OWNER), 800);
+			assert_eq!(balance_of(&RECIPIENT), 200);
+			assert_eq!(allowance(&OWNER, &CONTRACT), 100);
+
+			assert_eq!(
+				do_transfer_from(&CONTRACT, ASSET, &OWNER, &RECIPIENT, 101),
+				Status::InsufficientAllowance
+			);
+			assert_eq!(balance_of(&OWNER), 800);
+			assert_eq!(allowance(&OWNER, &CONTRACT), 100);
+		});
+	}
+
+	#[test]
+	fn transfer_from_needs_an_allowance() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				do_transfer_from(&CONTRACT, ASSET, &OWNER, &RECIPIENT, 1),
+				Status::InsufficientAllowance
+			);
+			assert_eq!(balance_of(&OWNER), 1_000);
+		});
+	}
+
+	#[test]
+	fn verify_reports_the_outcome_of_the_check() {
+		new_test_ext().execute_with(|| {
+			let proof = hex(GROTH16_PROOF);
+			let inputs = [small(33), small(14)];
+			assert_eq!(do_verify(VK, &proof, &inputs), Status::UnknownVerificationKey);
+
+			register_key();
+			assert_eq!(do_verify(VK, &proof, &inputs), Status::Success);
+			assert_eq!(do_verify(VK, &proof, &[small(34), small(14)]), Status::InvalidProof);
+			assert_eq!(do_verify(VK, &proof, &[small(33)]), Status::PublicInputCountMismatch);
+			assert_eq!(do_verify(VK, &proof[1..], &inputs), Status::MalformedInput);
+		});
+	}
+
+	#[test]
+	fn verify_refunds_down_to_the_weight_of_the_backend() {
+		new_test_ext().execute_with(|| {
+			let max_inputs = <Runtime as pallet_zk_verifier::Config>::MaxPublicInputs::get();
+			let charged = ZkVerifier::max_verify_weight(max_inputs);
+
+			let unknown = verify_weight(VK, 2);
+			assert_eq!(unknown, <Runtime as frame_system::Config>::DbWeight::get().reads(1));
+			assert!(unknown.all_lte(charged));
+
+			register_key();
+			let actual = verify_weight(VK, 2);
+			assert_eq!(actual, ZkVerifier::verify_weight(ProvingSystem::Groth16Bn254, 2));
+			assert!(actual.all_lte(charged));
+			assert!(actual.any_lt(charged));
+		});
+	}
+
+	#[test]
+	fn hashes_are_charged_for_the_largest_input() {
+		let charged = ZkWeight::hash_poseidon(poseidon::MAX_INPUTS as u32);
+		for len in 1..=poseidon::MAX_INPUTS as u32 {
+			assert!(ZkWeight::hash_poseidon(len).all_lte(charged));
+		}
+
+		// MiMC is charged from the length of the encoded input, before decoding it.
+		for len in [0, 1, 16, 100] {
+			let input = (vec![small(1); len], small(0)).encode();
+			let charged = ZkWeight::hash_mimc(input.len() as u32 / 32);
+			assert!(ZkWeight::hash_mimc(len as u32).all_lte(charged));
+		}
+	}
+
+	#[test]
+	fn key_info_describes_the_registered_key() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(do_key_info(VK), None);
+
+			register_key();
+			assert_eq!(
+				do_key_info(VK),
+				Some(VerificationKeyInfo {
+					system: ProvingSystem::Groth16Bn254,
+					public_input_count: 2,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn zkvm_journal_reads_the_recorded_journal() {
+		new_test_ext().execute_with(|| {
+			let receipt_hash = Hash::repeat_byte(1);
+			assert_eq!(do_zkvm_journal(receipt_hash), None);
+
+			let journal = VerifiedJournal {
+				image_id: [2; 32],
+				journal: BoundedVec::truncate_from(b"journal".to_vec()),
+				submitter: OWNER,
+				verified_at: 1,
+			};
+			pallet_zkvm_verifier::VerifiedJournals::<Runtime>::insert(
+				receipt_hash,
+				journal.clone(),
+			);
+			assert_eq!(do_zkvm_journal(receipt_hash), Some(journal));
+			assert_eq!(do_zkvm_journal(Hash::repeat_byte(2)), None);
+		});
+	}
+
+	// Reference values from circomlibjs, as in the tests of `kora-zk-hash`.
+	#[test]
+	fn hashes_match_circomlib() {
+		assert_eq!(
+			do_poseidon(&[small(1), small(2)]),
+			Ok(scalar("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"))
+		);
+		assert_eq!(
+			do_mimc(&[small(12), small(45)], &[0; 32]),
+			Ok(scalar("15ff7fe9793346a17c3150804bcb36d161c8662b110c50f55ccb7113948d8879"))
+		);
+
+		assert_eq!(do_poseidon(&[]), Err(Status::MalformedInput));
+		assert_eq!(do_poseidon(&[[0xff; 32]]), Err(Status::MalformedInput));
+		assert_eq!(do_mimc(&[small(1)], &[0xff; 32]), Err(Status::MalformedInput));
+	}
+
+	#[test]
+	fn filtered_calls_cannot_be_made_through_the_extension() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(check_callable(&asset_transfer()));
+
+			assert_ok!(TxPause::pause(
+				RuntimeOrigin::root(),
+				(
+					BoundedVec::truncate_from(b"Assets".to_vec()),
+					BoundedVec::truncate_from(WHOLE_PALLET.to_vec())
+				)
+			));
+			assert_eq!(
+				check_callable(&asset_transfer()),
+				Err(frame_system::Error::<Runtime>::CallFiltered.into())
+			);
+		});
+
+		new_test_ext().execute_with(|| {
+			assert_ok!(SafeMode::force_enter(technical_committee(2, 3)));
+			assert_eq!(
+				check_callable(&asset_transfer()),
+				Err(frame_system::Error::<Runtime>::CallFiltered.into())
+			);
+		});
+	}
+}
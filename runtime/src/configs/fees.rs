@@ -0,0 +1,490 @@
+//! Where transaction fees and tips go, and the bounds of the parameters setting them.
+
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{
+		dynamic_params::AggregatedKeyValue,
+		fungible, fungibles,
+		tokens::imbalance::{Imbalance, ResolveAssetTo, ResolveTo},
+		Contains, Get, OnUnbalanced,
+	},
+};
+use sp_runtime::{traits::Zero, DispatchError, Perquintill};
+
+use super::{dynamic_params, NativeAndAssets, RuntimeParameters, TreasuryAccount};
+use crate::{AccountId, Authorship, Balances, Runtime, RuntimeCall};
+
+/// Splits transaction fees between the `Treasury` pot and the block author, by
+/// `dynamic_params::transaction_payment::TreasuryFeeShare`, and gives tips to the author.
+///
+/// What the author cannot receive, all of it when there is no author, goes to the treasury
+/// instead, so no fee is burnt. Handles both the native fees of `TransactionPayment` and the
+/// fees `AssetConversionTxPayment` swapped into the native token.
+pub struct DealWithFees;
+
+impl OnUnbalanced<fungible::Credit<AccountId, Balances>> for DealWithFees {
+	fn on_unbalanceds(
+		mut fees_then_tips: impl Iterator<Item = fungible::Credit<AccountId, Balances>>,
+	) {
+		let Some(fees) = fees_then_tips.next() else { return };
+		let to_treasury =
+			dynamic_params::transaction_payment::TreasuryFeeShare::get() * fees.peek();
+		let (to_treasury, mut to_author) = fees.split(to_treasury);
+		fees_then_tips.for_each(|tip| to_author.subsume(tip));
+
+		ResolveTo::<TreasuryAccount, Balances>::on_unbalanced(to_treasury);
+		let unpaid = match Authorship::author() {
+			Some(author) => <Balances as fungible::Balanced<_>>::resolve(&author, to_author).err(),
+			None => Some(to_author),
+		};
+		if let Some(unpaid) = unpaid {
+			ResolveTo::<TreasuryAccount, Balances>::on_unbalanced(unpaid);
+		}
+	}
+}
+
+impl OnUnbalanced<fungibles::Credit<AccountId, NativeAndAssets>> for DealWithFees {
+	fn on_unbalanceds(
+		mut fees_then_tips: impl Iterator<Item = fungibles::Credit<AccountId, NativeAndAssets>>,
+	) {
+		let Some(fees) = fees_then_tips.next() else { return };
+		let to_treasury =
+			dynamic_params::transaction_payment::TreasuryFeeShare::get() * fees.peek();
+		let (to_treasury, mut to_author) = fees.split(to_treasury);
+		for tip in fees_then_tips {
+			// Fees and tips are always in the same asset, but do not lose a tip if they were not.
+			if let Err(tip) = to_author.subsume(tip) {
+				ResolveAssetTo::<TreasuryAccount, NativeAndAssets>::on_unbalanced(tip);
+			}
+		}
+
+		ResolveAssetTo::<TreasuryAccount, NativeAndAssets>::on_unbalanced(to_treasury);
+		let unpaid = match Authorship::author() {
+			Some(author) =>
+				<NativeAndAssets as fungibles::Balanced<_>>::resolve(&author, to_author).err(),
+			None => Some(to_author),
+		};
+		if let Some(unpaid) = unpaid {
+			ResolveAssetTo::<TreasuryAccount, NativeAndAssets>::on_unbalanced(unpaid);
+		}
+	}
+}
+
+/// Lets every call through, but blocks the `Parameters::set_parameter` calls that would leave
+/// the fee multiplier unable to follow the load of the chain: a target block fullness of
+/// nothing or everything, a zero adjustment variable, or a minimum above the maximum.
+///
+/// `pallet_parameters` only shows its origin check the key, so the value is checked here by
+/// setting it in a rolled-back transaction. Root bypasses the filter as for
+/// [`super::SudoRetirement`], but `GeneralAdmin` and what dispatches as it do not.
+pub struct ValidFeeParameters;
+
+impl Contains<RuntimeCall> for ValidFeeParameters {
+	fn contains(call: &RuntimeCall) -> bool {
+		let RuntimeCall::Parameters(pallet_parameters::Call::set_parameter {
+			key_value: key_value @ RuntimeParameters::TransactionPayment(_),
+		}) = call
+		else {
+			return true;
+		};
+		let (key, value) = key_value.clone().into_parts();
+		with_transaction(|| {
+			pallet_parameters::Parameters::<Runtime>::set(key, value);
+			TransactionOutcome::Rollback(Ok::<_, DispatchError>(fee_parameters_are_valid()))
+		})
+		.unwrap_or(false)
+	}
+}
+
+/// Whether the current `dynamic_params::transaction_payment` let the fee multiplier move.
+fn fee_parameters_are_valid() -> bool {
+	use dynamic_params::transaction_payment::{
+		AdjustmentVariable, MaximumMultiplier, MinimumMultiplier, TargetBlockFullness,
+	};
+
+	let target = TargetBlockFullness::get();
+	!target.is_zero() &&
+		target < Perquintill::one() &&
+		!AdjustmentVariable::get().is_zero() &&
+		MinimumMultiplier::get() <= MaximumMultiplier::get()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		configs::{
+			pallet_custom_origins::Origin, ExistentialDeposit, Native, RuntimeBlockWeights,
+			SlowAdjustingFeeUpdate,
+		},
+		AssetConversion, Assets, RuntimeEvent, RuntimeOrigin, System, TransactionPayment, UNIT,
+	};
+	use codec::Encode;
+	use frame_support::{
+		assert_ok,
+		dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo, Pays, PostDispatchInfo},
+		traits::{
+			fungible::{Balanced, Inspect},
+			tokens::{fungible::NativeOrWithId, Fortitude, Precision, Preservation},
+		},
+		weights::Weight,
+	};
+	use pallet_asset_conversion_tx_payment::ChargeAssetTxPayment;
+	use pallet_transaction_payment::Multiplier;
+	use sp_consensus_babe::{
+		digests::{PreDigest, SecondaryPlainPreDigest},
+		BABE_ENGINE_ID,
+	};
+	use sp_runtime::{
+		traits::{Convert, DispatchTransaction, Dispatchable, One, TransactionExtension},
+		BuildStorage, DigestItem, FixedPointNumber, Percent,
+	};
+
+	const PAYER: AccountId = AccountId::new([1; 32]);
+	const AUTHOR: AccountId = AccountId::new([2; 32]);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				(PAYER, 1_000 * UNIT),
+				(TreasuryAccount::get(), ExistentialDeposit::get()),
+			],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Makes `AUTHOR` the author of the current block, as BABE and `Session` would.
+	fn set_author() {
+		pallet_session::Validators::<Runtime>::put(vec![AUTHOR]);
+		let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+			authority_index: 0,
+			slot: 0.into(),
+		});
+		System::deposit_log(DigestItem::PreRuntime(BABE_ENGINE_ID, pre_digest.encode()));
+	}
+
+	fn withdraw(amount: u128) -> fungible::Credit<AccountId, Balances> {
+		Balances::withdraw(
+			&PAYER,
+			amount,
+			Precision::Exact,
+			Preservation::Preserve,
+			Fortitude::Polite,
+		)
+		.unwrap()
+	}
+
+	fn treasury_balance() -> u128 {
+		Balances::balance(&TreasuryAccount::get()) - ExistentialDeposit::get()
+	}
+
+	/// Creates `id` as a sufficient asset, gives some to `PAYER`, and seeds its pool with the
+	/// native token at two units of `id` per native unit.
+	fn seed_pool(id: u32) {
+		const LP: AccountId = AccountId::new([3; 32]);
+		let native = Box::new(Native::get());
+		let asset = Box::new(NativeOrWithId::WithId(id));
+
+		assert_ok!(<Balances as fungible::Mutate<_>>::mint_into(&LP, 10_000 * UNIT));
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), id.into(), LP.into(), true, 1));
+		assert_ok!(<Assets as fungibles::Mutate<_>>::mint_into(id, &LP, 10_000 * UNIT));
+		assert_ok!(<Assets as fungibles::Mutate<_>>::mint_into(id, &PAYER, 100 * UNIT));
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(LP),
+			native.clone(),
+			asset.clone()
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(LP),
+			native,
+			asset,
+			1_000 * UNIT,
+			2_000 * UNIT,
+			1,
+			1,
+			LP
+		));
+	}
+
+	#[test]
+	fn fees_are_split_and_tips_go_to_the_author() {
+		new_test_ext().execute_with(|| {
+			set_author();
+			let issuance = Balances::total_issuance();
+
+			DealWithFees::on_unbalanceds([withdraw(10 * UNIT), withdraw(UNIT)].into_iter());
+
+			let treasury_share = dynamic_params::transaction_payment::TreasuryFeeShare::get();
+			assert_eq!(treasury_share, Percent::from_percent(80));
+			assert_eq!(treasury_balance(), 8 * UNIT);
+			assert_eq!(Balances::balance(&AUTHOR), 3 * UNIT);
+			assert_eq!(Balances::total_issuance(), issuance);
+		});
+	}
+
+	#[test]
+	fn fees_go_to_the_treasury_without_an_author() {
+		new_test_ext().execute_with(|| {
+			let issuance = Balances::total_issuance();
+
+			DealWithFees::on_unbalanceds([withdraw(10 * UNIT), withdraw(UNIT)].into_iter());
+
+			assert_eq!(treasury_balance(), 11 * UNIT);
+			assert_eq!(Balances::total_issuance(), issuance);
+		});
+	}
+
+	#[test]
+	fn fees_below_the_existential_deposit_of_the_author_go_to_the_treasury() {
+		new_test_ext().execute_with(|| {
+			set_author();
+			let issuance = Balances::total_issuance();
+			let fee = ExistentialDeposit::get();
+
+			DealWithFees::on_unbalanceds([withdraw(fee)].into_iter());
+
+			assert_eq!(treasury_balance(), fee);
+			assert_eq!(Balances::balance(&AUTHOR), 0);
+			assert_eq!(Balances::total_issuance(), issuance);
+		});
+	}
+
+	#[test]
+	fn swapped_fees_are_split_the_same_way() {
+		new_test_ext().execute_with(|| {
+			set_author();
+			let issuance = Balances::total_issuance();
+			let withdraw = |amount| {
+				<NativeAndAssets as fungibles::Balanced<_>>::withdraw(
+					Native::get(),
+					&PAYER,
+					amount,
+					Precision::Exact,
+					Preservation::Preserve,
+					Fortitude::Polite,
+				)
+				.unwrap()
+			};
+
+			DealWithFees::on_unbalanceds([withdraw(10 * UNIT), withdraw(UNIT)].into_iter());
+
+			assert_eq!(treasury_balance(), 8 * UNIT);
+			assert_eq!(Balances::balance(&AUTHOR), 3 * UNIT);
+			assert_eq!(Balances::total_issuance(), issuance);
+		});
+	}
+
+	#[test]
+	fn fees_paid_in_an_asset_refund_the_overestimate() {
+		new_test_ext().execute_with(|| {
+			const ID: u32 = 1;
+			let asset = NativeOrWithId::WithId(ID);
+			seed_pool(ID);
+			let asset_balance = || <Assets as fungibles::Inspect<_>>::balance(ID, &PAYER);
+			let native_balance = Balances::balance(&PAYER);
+
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let info = DispatchInfo {
+				call_weight: Weight::from_parts(1_000_000_000, 0),
+				..Default::default()
+			};
+			let len = 100;
+			let estimate = TransactionPayment::compute_fee(len as u32, &info, 0);
+			let charged = AssetConversion::quote_price_tokens_for_exact_tokens(
+				asset.clone(),
+				Native::get(),
+				estimate,
+				true,
+			)
+			.unwrap();
+			let before = asset_balance();
+
+			let (pre, _) = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset.clone()))
+				.validate_and_prepare(RuntimeOrigin::signed(PAYER), &call, &info, len, 0)
+				.unwrap();
+			assert_eq!(asset_balance(), before - charged);
+
+			// The call used a quarter of its weight.
+			let mut post_info = PostDispatchInfo {
+				actual_weight: Some(info.call_weight / 4),
+				pays_fee: Pays::Yes,
+			};
+			let actual = TransactionPayment::compute_actual_fee(len as u32, &info, &post_info, 0);
+			assert!(actual < estimate);
+			let refunded = AssetConversion::quote_price_exact_tokens_for_tokens(
+				Native::get(),
+				asset.clone(),
+				estimate - actual,
+				true,
+			)
+			.unwrap();
+			assert!(refunded > 0);
+
+			assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+				pre,
+				&info,
+				&mut post_info,
+				len,
+				&Ok(())
+			));
+			assert_eq!(asset_balance(), before - charged + refunded);
+			assert_eq!(Balances::balance(&PAYER), native_balance);
+			System::assert_has_event(RuntimeEvent::AssetConversionTxPayment(
+				pallet_asset_conversion_tx_payment::Event::AssetTxFeePaid {
+					who: PAYER,
+					actual_fee: charged - refunded,
+					tip: 0,
+					asset_id: asset,
+				},
+			));
+		});
+	}
+
+	#[test]
+	fn transfer_keep_alive_costs_the_transfer_fee() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(TransactionPayment::next_fee_multiplier(), Multiplier::one());
+			let call = RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+				dest: AUTHOR.into(),
+				value: UNIT,
+			});
+			let info = call.get_dispatch_info();
+			let len = call.encoded_size() as u32;
+
+			let transfer_fee = dynamic_params::transaction_payment::TransferFee::get();
+			let weight_fee = TransactionPayment::compute_fee(0, &info, 0);
+			// The polynomial only rounds its coefficient, and the base and call weights apart.
+			assert!(weight_fee.abs_diff(transfer_fee) <= 2, "{weight_fee} != {transfer_fee}");
+
+			let byte_fee = dynamic_params::transaction_payment::TransactionByteFee::get();
+			let length_fee = TransactionPayment::compute_fee(len, &info, 0) - weight_fee;
+			assert_eq!(length_fee, len as u128 * byte_fee);
+			assert!(length_fee < transfer_fee / 5);
+		});
+	}
+
+	#[test]
+	fn the_fee_multiplier_follows_the_target_block_fullness() {
+		new_test_ext().execute_with(|| {
+			let max = RuntimeBlockWeights::get().get(DispatchClass::Normal).max_total.unwrap();
+			let next = |fullness: Perquintill, multiplier: Multiplier| {
+				let weight = Weight::from_parts(fullness * max.ref_time(), 0);
+				System::set_block_consumed_resources(weight, 0);
+				SlowAdjustingFeeUpdate::<Runtime>::convert(multiplier)
+			};
+			let target = dynamic_params::transaction_payment::TargetBlockFullness::get();
+			let above = target.saturating_add(Perquintill::from_percent(10));
+			let below = target.saturating_sub(Perquintill::from_percent(10));
+			let two = Multiplier::saturating_from_integer(2);
+
+			assert!(next(above, Multiplier::one()) > Multiplier::one());
+			assert!(next(Perquintill::one(), two) > next(above, two));
+			assert!(next(above, two) > two);
+
+			assert!(next(below, two) < two);
+			assert!(next(Perquintill::zero(), two) < next(below, two));
+			// Not below the minimum.
+			assert_eq!(
+				next(Perquintill::zero(), Multiplier::one()),
+				dynamic_params::transaction_payment::MinimumMultiplier::get()
+			);
+		});
+	}
+
+	/// Sets a fee parameter as a `GeneralAdmin` referendum would.
+	fn set_fee_parameter(
+		parameter: dynamic_params::transaction_payment::Parameters,
+	) -> Result<(), DispatchError> {
+		RuntimeCall::Parameters(pallet_parameters::Call::set_parameter {
+			key_value: RuntimeParameters::TransactionPayment(parameter),
+		})
+		.dispatch(Origin::GeneralAdmin.into())
+		.map(|_| ())
+		.map_err(|e| e.error)
+	}
+
+	#[test]
+	fn fee_parameters_that_stop_the_multiplier_are_rejected() {
+		use dynamic_params::transaction_payment::{
+			AdjustmentVariable, MaximumMultiplier, MinimumMultiplier, Parameters,
+			TargetBlockFullness,
+		};
+
+		new_test_ext().execute_with(|| {
+			let filtered: Result<(), DispatchError> =
+				Err(frame_system::Error::<Runtime>::CallFiltered.into());
+			let two = Multiplier::saturating_from_integer(2);
+
+			for target in [Perquintill::zero(), Perquintill::one()] {
+				assert_eq!(
+					set_fee_parameter(Parameters::TargetBlockFullness(
+						TargetBlockFullness,
+						Some(target)
+					)),
+					filtered
+				);
+			}
+			assert_eq!(
+				set_fee_parameter(Parameters::AdjustmentVariable(
+					AdjustmentVariable,
+					Some(Multiplier::zero())
+				)),
+				filtered
+			);
+			let above_the_maximum = MaximumMultiplier::get() + Multiplier::one();
+			assert_eq!(
+				set_fee_parameter(Parameters::MinimumMultiplier(
+					MinimumMultiplier,
+					Some(above_the_maximum)
+				)),
+				filtered
+			);
+			assert_eq!(
+				set_fee_parameter(Parameters::MaximumMultiplier(
+					MaximumMultiplier,
+					Some(Multiplier::from_rational(1, 2))
+				)),
+				filtered
+			);
+			assert!(fee_parameters_are_valid());
+
+			assert_ok!(set_fee_parameter(Parameters::TargetBlockFullness(
+				TargetBlockFullness,
+				Some(Perquintill::from_percent(50))
+			)));
+			assert_eq!(TargetBlockFullness::get(), Perquintill::from_percent(50));
+			assert_ok!(set_fee_parameter(Parameters::MaximumMultiplier(
+				MaximumMultiplier,
+				Some(two)
+			)));
+			assert_ok!(set_fee_parameter(Parameters::MinimumMultiplier(
+				MinimumMultiplier,
+				Some(two)
+			)));
+			assert_eq!(MinimumMultiplier::get(), MaximumMultiplier::get());
+
+			// Removing a value checks the default it falls back to.
+			let above_the_default = Multiplier::saturating_from_integer(1_000_000);
+			assert_ok!(set_fee_parameter(Parameters::MaximumMultiplier(
+				MaximumMultiplier,
+				Some(above_the_default)
+			)));
+			assert_ok!(set_fee_parameter(Parameters::MinimumMultiplier(
+				MinimumMultiplier,
+				Some(above_the_default)
+			)));
+			assert_eq!(
+				set_fee_parameter(Parameters::MaximumMultiplier(MaximumMultiplier, None)),
+				filtered
+			);
+			assert_eq!(MaximumMultiplier::get(), above_the_default);
+		});
+	}
+}
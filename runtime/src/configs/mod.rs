@@ -5,14 +5,14 @@ use alloc::borrow::Cow;
 use alloc::vec;
 use frame_support::{derive_impl, ord_parameter_types, parameter_types, traits::{ConstU128, ConstU32, ConstU64, ConstU8, VariantCountOf, KeyOwnerProofSystem}, weights::{
 	constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
-	IdentityFee, Weight,
+	ConstantMultiplier, Weight,
 }};
 use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
-use frame_system::{limits::{BlockLength, BlockWeights}, EnsureRoot, EnsureSigned, EnsureSignedBy, EnsureWithSuccess};
-use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
+use frame_system::{limits::{BlockLength, BlockWeights}, EnsureRoot, EnsureRootWithSuccess, EnsureSigned, EnsureSignedBy};
+use pallet_transaction_payment::{FungibleAdapter, Multiplier, TargetedFeeAdjustment};
 use sp_runtime::{curve::PiecewiseLinear, traits::{
 	OpaqueKeys, One, AccountIdConversion
-}, transaction_validity::{TransactionPriority}, FixedU128, MultiSigner, Perbill, Percent, Permill, RuntimeDebug, str_array};
+}, transaction_validity::{TransactionPriority}, FixedPointNumber, FixedU128, MultiSigner, Perbill, Percent, Permill, Perquintill, RuntimeDebug, str_array};
 use pallet_election_provider_multi_phase::{GeometricDepositBase, SolutionAccuracyOf};
 use sp_version::RuntimeVersion;
 use frame_support::{
@@ -24,8 +24,9 @@ use frame_support::{
 	},
 	weights::{
 		constants::{
-			BlockExecutionWeight
+			BlockExecutionWeight, ExtrinsicBaseWeight
 		},
+		WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
 	},
 	PalletId, BoundedVec
 };
@@ -33,25 +34,63 @@ use frame_election_provider_support::{
 	bounds::{ElectionBounds, ElectionBoundsBuilder},
 	onchain, BalancingConfig, ElectionDataProvider, SequentialPhragmen, VoteWeight,
 };
-use frame_support::__private::log;
 use frame_support::dynamic_params::{dynamic_pallet_params, dynamic_params };
 use frame_support::instances::{Instance1, Instance2};
-use frame_support::traits::{AsEnsureOriginWithArg, EnsureOriginWithArg, EqualPrivilegeOnly, InstanceFilter, LinearStoragePrice, WithdrawReasons};
+use frame_support::traits::{AsEnsureOriginWithArg, EitherOf, EnsureOrigin, EnsureOriginWithArg, EqualPrivilegeOnly, Equals, InsideBoth, InstanceFilter, LinearStoragePrice, MapSuccess, Nothing, WithdrawReasons};
 use frame_support::traits::fungible::{HoldConsideration, NativeFromLeft, NativeOrWithId, UnionOf};
 use frame_support::traits::tokens::imbalance::ResolveAssetTo;
 use frame_support::traits::tokens::pay::PayAssetFromAccount;
+use frame_support::traits::tokens::UnityOrOuterConversion;
 use pallet_asset_conversion::{AccountIdConverter, Ascending, Chain, WithFirstAsset};
+use pallet_asset_conversion_tx_payment::SwapAssetAdapter;
 use pallet_identity::legacy::IdentityInfo;
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use sp_consensus_beefy::{ecdsa_crypto::AuthorityId as BeefyId, mmr::MmrLeafVersion};
 use sp_core::crypto::KeyTypeId;
-use sp_runtime::traits::{ConstBool, ConvertInto, Get, IdentityLookup};
+use sp_runtime::traits::{ConstBool, ConvertInto, Get, IdentityLookup, Keccak256, Replace};
 #[cfg(feature = "runtime-benchmarks")]
 use sp_core::crypto::FromEntropy;
 // Local module imports
 use super::*;
 
+mod chain_extension;
+pub use chain_extension::KoraChainExtension;
+mod fees;
+pub use fees::{DealWithFees, ValidFeeParameters};
+mod origins;
+pub use origins::pallet_custom_origins;
+use pallet_custom_origins::{
+	GeneralAdmin, ReferendumCanceller, ReferendumKiller, Spender, WhitelistedCaller,
+};
+mod pause;
+pub use pause::{NotPaused, SafeModeWhitelistedCalls, TxPauseWhitelistedCalls, WHOLE_PALLET};
+mod sudo;
+pub use sudo::{sudo_retired, SudoRetirement};
+#[cfg(test)]
+mod test_utils;
+
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 
+/// The origin of administrative calls: root, or a referendum on the `general_admin` track.
+pub type EnsureRootOrGeneralAdmin = EitherOf<EnsureRoot<AccountId>, GeneralAdmin>;
+
+/// The collective instance of the technical committee.
+pub type TechnicalCollective = pallet_collective::Instance1;
+
+/// More than half of the technical committee.
+pub type TechnicalCommitteeMajority =
+	pallet_collective::EnsureProportionMoreThan<AccountId, TechnicalCollective, 1, 2>;
+
+/// The origin that answers incidents: root, a `general_admin` referendum, or a technical
+/// committee majority, which can act faster than a referendum.
+pub type EnsureEmergency = EitherOf<EnsureRootOrGeneralAdmin, TechnicalCommitteeMajority>;
+
+/// [`EnsureEmergency`], succeeding with `Duration`.
+pub type EnsureEmergencyWithSuccess<Duration> = EitherOf<
+	EnsureRootWithSuccess<AccountId, Duration>,
+	MapSuccess<EitherOf<GeneralAdmin, TechnicalCommitteeMajority>, Replace<Duration>>,
+>;
+
 pub const fn deposit(items: u32, bytes: u32) -> Balance {
 	items as Balance * 15 * MILLI_UNIT + (bytes as Balance) * 6 * MILLI_UNIT
 }
@@ -97,6 +136,12 @@ impl frame_system::Config for Runtime {
 	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
 	type SS58Prefix = SS58Prefix;
 	type MaxConsumers = ConstU32<16>;
+	/// Blocks `Sudo` once it is retired, fee parameters that would stop the fee multiplier, every
+	/// call but the whitelisted ones in safe mode, and the calls paused through `TxPause`.
+	type BaseCallFilter = InsideBoth<
+		InsideBoth<SudoRetirement, ValidFeeParameters>,
+		InsideBoth<SafeMode, NotPaused>,
+	>;
 }
 
 parameter_types! {
@@ -147,6 +192,10 @@ impl pallet_im_online::Config for Runtime {
 	type WeightInfo = pallet_im_online::weights::SubstrateWeight<Runtime>;
 }
 
+impl pallet_authority_discovery::Config for Runtime {
+	type MaxAuthorities = MaxAuthorities;
+}
+
 impl pallet_authorship::Config for Runtime {
 	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Babe>;
 	type EventHandler = (Staking, ImOnline);
@@ -352,7 +401,7 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type Fallback = onchain::OnChainExecution<OnChainSeqPhragmen>;
 	type GovernanceFallback = onchain::OnChainExecution<OnChainSeqPhragmen>;
 	type Solver = SequentialPhragmen<AccountId, SolutionAccuracyOf<Self>, OffchainRandomBalancing>;
-	type ForceOrigin = EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrGeneralAdmin;
 	type BenchmarkingConfig = ElectionProviderBenchmarkConfig;
 	type WeightInfo = pallet_election_provider_multi_phase::weights::SubstrateWeight<Self>;
 }
@@ -453,7 +502,7 @@ impl pallet_nomination_pools::Config for Runtime {
 	pallet_nomination_pools::adapter::DelegateStake<Self, Staking, DelegatedStaking>;
 	type PostUnbondingPoolsWindow = PostUnbondPoolsWindow;
 	type MaxMetadataLen = ConstU32<256>;
-    type AdminOrigin = EnsureRoot<AccountId>;
+    type AdminOrigin = EnsureRootOrGeneralAdmin;
     type BlockNumberProvider = System;
     type Filter = ();
 }
@@ -481,7 +530,7 @@ impl pallet_staking::Config for Runtime {
 	type SessionsPerEra = SessionsPerEra;
 	type BondingDuration = BondingDuration;
 	type SlashDeferDuration = SlashDeferDuration;
-	type AdminOrigin = EnsureRoot<AccountId>;
+	type AdminOrigin = EnsureRootOrGeneralAdmin;
 	type SessionInterface = Self;
 	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type NextNewSession = Session;
@@ -555,7 +604,7 @@ parameter_types! {
 
 impl pallet_treasury::Config for Runtime {
 	type Currency = Balances;
-	type RejectOrigin = EnsureRoot<AccountId>;
+	type RejectOrigin = EnsureRootOrGeneralAdmin;
 	type RuntimeEvent = RuntimeEvent;
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
@@ -564,13 +613,15 @@ impl pallet_treasury::Config for Runtime {
 	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
 	type SpendFunds = Bounties;
 	type MaxApprovals = MaxApprovals;
-	// Allow signed accounts to spend up to a certain amount
-	type SpendOrigin = EnsureWithSuccess<EnsureSigned<AccountId>, AccountId, MaxBalance>;
+	// Root spends without a limit, spender referenda up to the ceiling of their track. Spends of
+	// assets are valued in KORA through `AssetRate` before being checked against it.
+	type SpendOrigin = EitherOf<EnsureRootWithSuccess<AccountId, MaxBalance>, Spender>;
 	type AssetKind = NativeOrWithId<u32>;
 	type Beneficiary = AccountId;
 	type BeneficiaryLookup = IdentityLookup<Self::Beneficiary>;
 	type Paymaster = PayAssetFromAccount<NativeAndAssets, TreasuryAccount>;
-	type BalanceConverter = AssetRate;
+	// KORA is worth itself, and other assets what `AssetRate` says.
+	type BalanceConverter = UnityOrOuterConversion<Equals<Native>, AssetRate>;
 	type PayoutPeriod = SpendPayoutPeriod;
 	#[cfg(feature = "runtime-benchmarks")]
 	type BenchmarkHelper = PalletTreasuryArguments;
@@ -592,6 +643,44 @@ impl pallet_grandpa::Config for Runtime {
 	pallet_grandpa::EquivocationReportSystem<Self, Offences, Historical, ReportLongevity>;
 }
 
+impl pallet_beefy::Config for Runtime {
+	type BeefyId = BeefyId;
+	type MaxAuthorities = MaxAuthorities;
+	type MaxNominators = MaxNominators;
+	type MaxSetIdSessionEntries = MaxSetIdSessionEntries;
+	type OnNewValidatorSet = BeefyMmrLeaf;
+	type AncestryHelper = BeefyMmrLeaf;
+	type WeightInfo = ();
+	type KeyOwnerProof = sp_session::MembershipProof;
+	type EquivocationReportSystem =
+		pallet_beefy::EquivocationReportSystem<Self, Offences, Historical, ReportLongevity>;
+}
+
+impl pallet_mmr::Config for Runtime {
+	const INDEXING_PREFIX: &'static [u8] = sp_mmr_primitives::INDEXING_PREFIX;
+	// Keccak, so that Ethereum contracts can check MMR proofs cheaply.
+	type Hashing = Keccak256;
+	type LeafData = BeefyMmrLeaf;
+	type OnNewRoot = pallet_beefy_mmr::DepositBeefyDigest<Runtime>;
+	type BlockHashProvider = pallet_mmr::DefaultBlockHashProvider<Runtime>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+parameter_types! {
+	pub LeafVersion: MmrLeafVersion = MmrLeafVersion::new(0, 0);
+}
+
+impl pallet_beefy_mmr::Config for Runtime {
+	type LeafVersion = LeafVersion;
+	// Ethereum addresses, so that Ethereum contracts can check BEEFY signatures with `ecrecover`.
+	type BeefyAuthorityToMerkleLeaf = pallet_beefy_mmr::BeefyEcdsaToEthereum;
+	type LeafExtra = Vec<u8>;
+	type BeefyDataProvider = ();
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	// difference of 26 bytes on-chain for the registration and 9 bytes on-chain for the identity
 	// information, already accounted for by the byte deposit
@@ -615,11 +704,11 @@ impl pallet_identity::Config for Runtime {
 	type IdentityInformation = IdentityInfo<MaxAdditionalFields>;
 	type MaxRegistrars = MaxRegistrars;
 	type Slashed = Treasury;
-	type ForceOrigin = EnsureRoot<AccountId>;
-	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrGeneralAdmin;
+	type RegistrarOrigin = EnsureRootOrGeneralAdmin;
 	type OffchainSignature = Signature;
 	type SigningPublicKey = <Signature as Verify>::Signer;
-	type UsernameAuthorityOrigin = EnsureRoot<Self::AccountId>;
+	type UsernameAuthorityOrigin = EnsureRootOrGeneralAdmin;
 	type PendingUsernameExpiration = ConstU32<{ 7 * DAYS }>;
 	type UsernameGracePeriod = ConstU32<{ 30 * DAYS }>;
 	type MaxSuffixLength = ConstU32<7>;
@@ -641,8 +730,10 @@ impl pallet_child_bounties::Config for Runtime {
 impl pallet_whitelist::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
-	type WhitelistOrigin = EnsureRoot<AccountId>;
-	type DispatchWhitelistedOrigin = EnsureRoot<AccountId>;
+	// A majority of the technical committee fast-tracks a call by whitelisting it, after which a
+	// referendum on the short `whitelisted_caller` track dispatches it.
+	type WhitelistOrigin = EitherOf<EnsureRoot<AccountId>, TechnicalCommitteeMajority>;
+	type DispatchWhitelistedOrigin = EitherOf<EnsureRoot<AccountId>, WhitelistedCaller>;
 	type Preimages = Preimage;
 	type WeightInfo = pallet_whitelist::weights::SubstrateWeight<Runtime>;
 }
@@ -700,7 +791,8 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 				c,
 				RuntimeCall::Referenda(..) |
 					RuntimeCall::Bounties(..) |
-					RuntimeCall::Treasury(..)
+					RuntimeCall::Treasury(..) |
+					RuntimeCall::TechnicalCommittee(..)
 			),
 			ProxyType::Staking => {
 				matches!(c, RuntimeCall::Staking(..))
@@ -745,7 +837,7 @@ impl pallet_scheduler::Config for Runtime {
 	type PalletsOrigin = OriginCaller;
 	type RuntimeCall = RuntimeCall;
 	type MaximumWeight = MaximumSchedulerWeight;
-	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type ScheduleOrigin = EnsureRootOrGeneralAdmin;
 	type OriginPrivilegeCmp = EqualPrivilegeOnly;
 	type MaxScheduledPerBlock = ConstU32<50>;
 	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
@@ -900,6 +992,75 @@ pub mod dynamic_params {
 					},
 				},
 			},
+			// Small Spender Track - Treasury spends of up to `SMALL_SPEND`
+			pallet_referenda::Track {
+				id: 5u16,
+				info: pallet_referenda::TrackInfo {
+					name: str_array("small_spender"),
+					max_deciding: 50,
+					decision_deposit: 100 * UNIT,
+					prepare_period: 1 * HOURS,
+					decision_period: 7 * DAYS,
+					confirm_period: 12 * HOURS,
+					min_enactment_period: 1 * DAYS,
+					min_approval: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(50),
+						ceil: Perbill::from_percent(100),
+					},
+					min_support: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(5),
+						ceil: Perbill::from_percent(25),
+					},
+				},
+			},
+			// Medium Spender Track - Treasury spends of up to `MEDIUM_SPEND`
+			pallet_referenda::Track {
+				id: 6u16,
+				info: pallet_referenda::TrackInfo {
+					name: str_array("medium_spender"),
+					max_deciding: 50,
+					decision_deposit: 200 * UNIT,
+					prepare_period: 2 * HOURS,
+					decision_period: 14 * DAYS,
+					confirm_period: 1 * DAYS,
+					min_enactment_period: 1 * DAYS,
+					min_approval: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(50),
+						ceil: Perbill::from_percent(100),
+					},
+					min_support: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(10),
+						ceil: Perbill::from_percent(50),
+					},
+				},
+			},
+			// Big Spender Track - Treasury spends of up to `BIG_SPEND`
+			pallet_referenda::Track {
+				id: 7u16,
+				info: pallet_referenda::TrackInfo {
+					name: str_array("big_spender"),
+					max_deciding: 50,
+					decision_deposit: 400 * UNIT,
+					prepare_period: 4 * HOURS,
+					decision_period: 14 * DAYS,
+					confirm_period: 2 * DAYS,
+					min_enactment_period: 1 * DAYS,
+					min_approval: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(50),
+						ceil: Perbill::from_percent(100),
+					},
+					min_support: pallet_referenda::Curve::LinearDecreasing {
+						length: Perbill::from_percent(100),
+						floor: Perbill::from_percent(20),
+						ceil: Perbill::from_percent(50),
+					},
+				},
+			},
 		]);
 
 		/// A list mapping every origin with a track Id
@@ -907,9 +1068,58 @@ pub mod dynamic_params {
 		pub static Origins: BoundedVec<(OriginCaller, u16), ConstU32<100>> =
 			BoundedVec::truncate_from(vec![
 				(OriginCaller::system(frame_system::RawOrigin::Root), 0),
-				// Add mapping for other origins when you define them
+				(OriginCaller::Origins(pallet_custom_origins::Origin::GeneralAdmin), 1),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::ReferendumCanceller), 2),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::ReferendumKiller), 3),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::WhitelistedCaller), 4),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::SmallSpender), 5),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::MediumSpender), 6),
+				(OriginCaller::Origins(pallet_custom_origins::Origin::BigSpender), 7),
 			]);
 	}
+
+	#[dynamic_pallet_params]
+	#[codec(index = 2)]
+	pub mod transaction_payment {
+		/// The fee of a `transfer_keep_alive` at a fee multiplier of one, its length fee
+		/// excluded, which [`WeightToFee`] is calibrated to.
+		#[codec(index = 0)]
+		pub static TransferFee: Balance = 10 * MILLI_UNIT;
+
+		/// The block fullness the fee multiplier steers towards.
+		#[codec(index = 1)]
+		pub static TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+
+		/// How fast the fee multiplier moves away from the target.
+		#[codec(index = 2)]
+		pub static AdjustmentVariable: Multiplier = Multiplier::from_rational(75, 1_000_000);
+
+		/// The lowest the fee multiplier can go.
+		#[codec(index = 3)]
+		pub static MinimumMultiplier: Multiplier = Multiplier::one();
+
+		/// The highest the fee multiplier can go.
+		#[codec(index = 4)]
+		pub static MaximumMultiplier: Multiplier = Multiplier::saturating_from_integer(100_000);
+
+		/// The share of transaction fees, tips excluded, that goes to the treasury rather than
+		/// the block author.
+		#[codec(index = 5)]
+		pub static TreasuryFeeShare: Percent = Percent::from_percent(80);
+
+		/// The fee of every byte of a transaction, so that the length of a `transfer_keep_alive`
+		/// adds about a tenth to its fee.
+		#[codec(index = 6)]
+		pub static TransactionByteFee: Balance = 10 * MICRO_UNIT;
+	}
+
+	#[dynamic_pallet_params]
+	#[codec(index = 3)]
+	pub mod sudo {
+		/// The block from which `Sudo` can only remove its key, if any.
+		#[codec(index = 0)]
+		pub static RetirementBlock: Option<BlockNumber> = None;
+	}
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -926,7 +1136,7 @@ impl pallet_preimage::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
 	type Currency = Balances;
-	type ManagerOrigin = EnsureRoot<AccountId>;
+	type ManagerOrigin = EnsureRootOrGeneralAdmin;
 	type Consideration = HoldConsideration<
 		AccountId,
 		Balances,
@@ -955,7 +1165,7 @@ impl pallet_assets::Config<Instance1> for Runtime {
 	type AssetIdParameter = codec::Compact<u32>;
 	type Currency = Balances;
 	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
-	type ForceOrigin = EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrGeneralAdmin;
 	type AssetDeposit = AssetDeposit;
 	type AssetAccountDeposit = ConstU128<UNIT>;
 	type MetadataDepositBase = MetadataDepositBase;
@@ -983,7 +1193,7 @@ impl pallet_assets::Config<Instance2> for Runtime {
 	type AssetIdParameter = codec::Compact<u32>;
 	type Currency = Balances;
 	type CreateOrigin = AsEnsureOriginWithArg<EnsureSignedBy<AssetConversionOrigin, AccountId>>;
-	type ForceOrigin = EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrGeneralAdmin;
 	type AssetDeposit = AssetDeposit;
 	type AssetAccountDeposit = ConstU128<UNIT>;
 	type MetadataDepositBase = MetadataDepositBase;
@@ -1046,12 +1256,70 @@ impl pallet_asset_conversion::Config for Runtime {
 	type BenchmarkHelper = ();
 }
 
+/// Fees paid in an asset are swapped for the native token through its `AssetConversion` pool,
+/// and what is left after refunding any overestimate is handled like native fees.
+impl pallet_asset_conversion_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetId = NativeOrWithId<u32>;
+	type OnChargeAssetTransaction =
+		SwapAssetAdapter<Native, NativeAndAssets, AssetConversion, DealWithFees>;
+	type WeightInfo = pallet_asset_conversion_tx_payment::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = AssetConversionTxHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct AssetConversionTxHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_asset_conversion_tx_payment::BenchmarkHelperTrait<
+	AccountId,
+	NativeOrWithId<u32>,
+	NativeOrWithId<u32>,
+> for AssetConversionTxHelper
+{
+	fn create_asset_id_parameter(seed: u32) -> (NativeOrWithId<u32>, NativeOrWithId<u32>) {
+		(NativeOrWithId::WithId(seed), NativeOrWithId::WithId(seed))
+	}
+
+	fn setup_balances_and_pool(asset_id: NativeOrWithId<u32>, account: AccountId) {
+		use alloc::boxed::Box;
+		use frame_support::{assert_ok, traits::fungible::Mutate as _, traits::fungibles::Mutate};
+
+		let NativeOrWithId::WithId(id) = asset_id else { return };
+		assert_ok!(Assets::force_create(
+			RuntimeOrigin::root(),
+			id.into(),
+			account.clone().into(),
+			true,
+			1,
+		));
+		let liquidity = u64::MAX as Balance;
+		assert_ok!(Balances::mint_into(&account, liquidity * 100));
+		assert_ok!(Assets::mint_into(id, &account, liquidity * 100));
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(account.clone()),
+			Box::new(NativeOrWithId::Native),
+			Box::new(asset_id.clone()),
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(account.clone()),
+			Box::new(NativeOrWithId::Native),
+			Box::new(asset_id),
+			liquidity,
+			liquidity,
+			1,
+			1,
+			account,
+		));
+	}
+}
+
 impl pallet_asset_rate::Config for Runtime {
 	type WeightInfo = pallet_asset_rate::weights::SubstrateWeight<Runtime>;
 	type RuntimeEvent = RuntimeEvent;
-	type CreateOrigin = EnsureRoot<AccountId>;
-	type RemoveOrigin = EnsureRoot<AccountId>;
-	type UpdateOrigin = EnsureRoot<AccountId>;
+	type CreateOrigin = EnsureRootOrGeneralAdmin;
+	type RemoveOrigin = EnsureRootOrGeneralAdmin;
+	type UpdateOrigin = EnsureRootOrGeneralAdmin;
 	type Currency = Balances;
 	type AssetKind = NativeOrWithId<u32>;
 	#[cfg(feature = "runtime-benchmarks")]
@@ -1165,6 +1433,88 @@ impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
 	}
 }
 
+impl pallet_custom_origins::Config for Runtime {}
+
+parameter_types! {
+	pub const TechnicalMotionDuration: BlockNumber = 3 * DAYS;
+	pub const TechnicalMaxProposals: u32 = 100;
+	pub const TechnicalMaxMembers: u32 = 100;
+	pub MaxTechnicalProposalWeight: Weight =
+		Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = TechnicalMaxProposals;
+	type MaxMembers = TechnicalMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	// Members are managed through `TechnicalMembership`.
+	type SetMembersOrigin = EnsureRoot<AccountId>;
+	type MaxProposalWeight = MaxTechnicalProposalWeight;
+	type DisapproveOrigin = EnsureRootOrGeneralAdmin;
+	type KillOrigin = EnsureRootOrGeneralAdmin;
+	type Consideration = ();
+}
+
+/// Who sits on the technical committee, as decided by referenda on the `general_admin` track.
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = EnsureRootOrGeneralAdmin;
+	type RemoveOrigin = EnsureRootOrGeneralAdmin;
+	type SwapOrigin = EnsureRootOrGeneralAdmin;
+	type ResetOrigin = EnsureRootOrGeneralAdmin;
+	type PrimeOrigin = EnsureRootOrGeneralAdmin;
+	type MembershipInitialized = TechnicalCommittee;
+	type MembershipChanged = TechnicalCommittee;
+	type MaxMembers = TechnicalMaxMembers;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const SafeModeEnterDuration: BlockNumber = 4 * HOURS;
+	pub const SafeModeExtendDuration: BlockNumber = 2 * HOURS;
+	// Only emergency origins can enter or extend safe mode, never a deposit.
+	pub const SafeModeEnterDepositAmount: Option<Balance> = None;
+	pub const SafeModeExtendDepositAmount: Option<Balance> = None;
+	pub const SafeModeReleaseDelay: Option<BlockNumber> = None;
+	pub const SafeModeForceEnterDuration: BlockNumber = 1 * DAYS;
+	pub const SafeModeForceExtendDuration: BlockNumber = 1 * DAYS;
+}
+
+/// Stops every call but [`SafeModeWhitelistedCalls`] until the safe mode expires or is exited.
+impl pallet_safe_mode::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type WhitelistedCalls = SafeModeWhitelistedCalls;
+	type EnterDuration = SafeModeEnterDuration;
+	type EnterDepositAmount = SafeModeEnterDepositAmount;
+	type ExtendDuration = SafeModeExtendDuration;
+	type ExtendDepositAmount = SafeModeExtendDepositAmount;
+	type ForceEnterOrigin = EnsureEmergencyWithSuccess<SafeModeForceEnterDuration>;
+	type ForceExtendOrigin = EnsureEmergencyWithSuccess<SafeModeForceExtendDuration>;
+	type ForceExitOrigin = EnsureEmergency;
+	type ForceDepositOrigin = EnsureRootOrGeneralAdmin;
+	type ReleaseDelay = SafeModeReleaseDelay;
+	type Notify = ();
+	type WeightInfo = pallet_safe_mode::weights::SubstrateWeight<Runtime>;
+}
+
+/// Pauses single calls, or whole pallets under [`WHOLE_PALLET`], until they are unpaused.
+impl pallet_tx_pause::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PauseOrigin = EnsureEmergency;
+	type UnpauseOrigin = EnsureEmergency;
+	type WhitelistedCalls = TxPauseWhitelistedCalls;
+	type MaxNameLen = ConstU32<256>;
+	type WeightInfo = pallet_tx_pause::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_referenda::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 	type RuntimeEvent = RuntimeEvent;
@@ -1172,8 +1522,12 @@ impl pallet_referenda::Config for Runtime {
 	type Scheduler = Scheduler;
 	type Currency = pallet_balances::Pallet<Self>;
 	type SubmitOrigin = EnsureSigned<AccountId>;
-	type CancelOrigin = EnsureRoot<AccountId>;
-	type KillOrigin = EnsureRoot<AccountId>;
+	// The technical committee can veto a referendum without waiting for an emergency one.
+	type CancelOrigin = EitherOf<
+		EitherOf<EnsureRoot<AccountId>, ReferendumCanceller>,
+		TechnicalCommitteeMajority,
+	>;
+	type KillOrigin = EitherOf<EnsureRoot<AccountId>, ReferendumKiller>;
 	type Slash = ();
 	type Votes = pallet_conviction_voting::VotesOf<Runtime>;
 	type Tally = pallet_conviction_voting::TallyOf<Runtime>;
@@ -1186,16 +1540,47 @@ impl pallet_referenda::Config for Runtime {
 	type BlockNumberProvider = System;
 }
 
-parameter_types! {
-	pub FeeMultiplier: Multiplier = Multiplier::one();
+/// Converts weight to fee linearly, so that a `transfer_keep_alive` costs
+/// `dynamic_params::transaction_payment::TransferFee` before the fee multiplier is applied.
+pub struct WeightToFee;
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		use pallet_balances::WeightInfo;
+
+		let fee = dynamic_params::transaction_payment::TransferFee::get();
+		let weight = ExtrinsicBaseWeight::get().saturating_add(
+			<Runtime as pallet_balances::Config>::WeightInfo::transfer_keep_alive(),
+		);
+		let weight = Balance::from(weight.ref_time()).max(1);
+		vec![WeightToFeeCoefficient {
+			coeff_integer: fee / weight,
+			coeff_frac: Perbill::from_rational(fee % weight, weight),
+			negative: false,
+			degree: 1,
+		}]
+		.into()
+	}
 }
 
+/// Raises fees while blocks are fuller than the target and lowers them while they are emptier,
+/// within the bounds governed through `dynamic_params::transaction_payment`.
+pub type SlowAdjustingFeeUpdate<R> = TargetedFeeAdjustment<
+	R,
+	dynamic_params::transaction_payment::TargetBlockFullness,
+	dynamic_params::transaction_payment::AdjustmentVariable,
+	dynamic_params::transaction_payment::MinimumMultiplier,
+	dynamic_params::transaction_payment::MaximumMultiplier,
+>;
+
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = FungibleAdapter<Balances, ()>;
-	type WeightToFee = IdentityFee<Balance>;
-	type LengthToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+	type OnChargeTransaction = FungibleAdapter<Balances, DealWithFees>;
+	type WeightToFee = WeightToFee;
+	type LengthToFee =
+		ConstantMultiplier<Balance, dynamic_params::transaction_payment::TransactionByteFee>;
+	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightInfo = pallet_transaction_payment::weights::SubstrateWeight<Runtime>;
 }
@@ -1211,7 +1596,7 @@ where
 	RuntimeCall: From<LocalCall>,
 {
 	fn create_inherent(call: RuntimeCall) -> UncheckedExtrinsic {
-		UncheckedExtrinsic::new_bare(call).into()
+		sp_runtime::generic::UncheckedExtrinsic::new_bare(call).into()
 	}
 }
 
@@ -1223,48 +1608,6 @@ where
 	type RuntimeCall = RuntimeCall;
 }
 
-use pallet_contracts::chain_extension::{
-	ChainExtension,
-	Environment,
-	Ext,
-	InitState,
-	RetVal,
-	SysConfig,
-};
-use sp_core::crypto::UncheckedFrom;
-use sp_runtime::DispatchError;
-
-#[derive(Default)]
-pub struct KoraChainExtension;
-
-impl ChainExtension<Runtime> for KoraChainExtension {
-	fn call<E: Ext>(
-		&mut self,
-		env: Environment<E, InitState>,
-	) -> Result<RetVal, DispatchError>
-	where
-		<E::T as SysConfig>::AccountId:
-		UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
-	{
-		let func_id = env.func_id();
-		match func_id {
-			0 => {
-				log::info!("KoraChainExtension::call");
-				Ok(RetVal::Converging(0))
-			},
-			1 => {
-
-				Ok(RetVal::Converging(0))
-			},
-			_ => Err(DispatchError::Other("Invalid func_id")),
-		}
-	}
-
-	fn enabled() -> bool {
-		true
-	}
-}
-
 parameter_types! {
 	pub const DepositPerItem: Balance = deposit(1, 0);
 	pub const DepositPerByte: Balance = deposit(0, 1);
@@ -1315,6 +1658,112 @@ impl pallet_contracts::Config for Runtime {
 	type Xcm = ();
 }
 
+parameter_types! {
+	/// The EIP-155 chain id of the EVM-compatible contracts of `Revive`, the SS58 prefix of the
+	/// chain.
+	pub const ReviveChainId: u64 = 1270;
+}
+
+/// Ethereum-compatible contracts, compiled from Solidity for PolkaVM.
+///
+/// Contracts and externally owned accounts use H160 addresses. Accounts derived from an Ethereum
+/// key map to an H160 directly, and any other `AccountId` gets one by calling `map_account`. The
+/// Ethereum precompiles live at their usual addresses, including ecrecover, sha256 and the BN254
+/// `ecAdd`, `ecMul` and `ecPairing` used by snarkjs verifier contracts.
+impl pallet_revive::Config for Runtime {
+	type Time = Timestamp;
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	/// Contracts may not dispatch runtime calls, as with `pallet_contracts`.
+	type CallFilter = Nothing;
+	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
+	type WeightInfo = pallet_revive::weights::SubstrateWeight<Self>;
+	type ChainExtension = ();
+	type FindAuthor = <Runtime as pallet_authorship::Config>::FindAuthor;
+	type DepositPerByte = DepositPerByte;
+	type DepositPerItem = DepositPerItem;
+	type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+	type AddressMapper = pallet_revive::AccountId32Mapper<Self>;
+	type UnsafeUnstableInterface = ConstBool<false>;
+	type UploadOrigin = EnsureSigned<Self::AccountId>;
+	type InstantiateOrigin = EnsureSigned<Self::AccountId>;
+	type Xcm = ();
+	type RuntimeMemory = ConstU32<{ 128 * 1024 * 1024 }>;
+	type PVFMemory = ConstU32<{ 512 * 1024 * 1024 }>;
+	type ChainId = ReviveChainId;
+	/// ETH has 18 decimals and the native token 12.
+	type NativeToEthRatio = ConstU32<1_000_000>;
+	type EthGasEncoder = ();
+}
+
+parameter_types! {
+	pub const ZkMaxPublicInputs: u32 = 64;
+	pub const ZkMaxKeyLen: u32 = 16 * 1024;
+	pub const ZkMaxProofLen: u32 = 1024;
+}
+
+/// Where the zk verifiers compute pairings and multi-scalar multiplications.
+///
+/// `Host` runs them natively through the `kora_zk_crypto` host functions, which the node
+/// registers with its executor. `pallet_zk_verifier::ops::Native` runs them in Wasm and is the
+/// fallback for enacting the runtime on nodes that do not provide them.
+type ZkCurveOps = pallet_zk_verifier::ops::Host;
+
+impl pallet_zk_verifier::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ManagerOrigin = EnsureRootOrGeneralAdmin;
+	type Backends = (
+		pallet_zk_verifier::groth16::Groth16Bn254<ZkCurveOps>,
+		pallet_zk_verifier::groth16::Groth16Bls12_381<ZkCurveOps>,
+		pallet_zk_verifier::plonk::Plonk<ZkCurveOps>,
+		pallet_zk_verifier::fflonk::Fflonk<ZkCurveOps>,
+	);
+	type MaxPublicInputs = ZkMaxPublicInputs;
+	type MaxKeyLen = ZkMaxKeyLen;
+	type MaxProofLen = ZkMaxProofLen;
+	type WeightInfo = pallet_zk_verifier::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const ZkvmMaxJournalLen: u32 = 4 * 1024;
+}
+
+impl pallet_zkvm_verifier::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ManagerOrigin = EnsureRootOrGeneralAdmin;
+	type ProofVerifier = ZkVerifier;
+	type MaxJournalLen = ZkvmMaxJournalLen;
+	type WeightInfo = pallet_zkvm_verifier::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const ShieldedPoolPalletId: PalletId = PalletId(*b"py/shpol");
+	/// Id the withdraw circuit's key is registered under in `ZkVerifier`.
+	pub const ShieldedPoolWithdrawKey: pallet_zk_verifier::VkId = 1;
+	pub const ShieldedPoolTreeDepth: u32 = 20;
+	pub const ShieldedPoolRootHistorySize: u32 = 30;
+}
+
+impl pallet_shielded_pool::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = ShieldedPoolPalletId;
+	type Currency = Balances;
+	type Assets = Assets;
+	type ProofVerifier = ZkVerifier;
+	type WithdrawKey = ShieldedPoolWithdrawKey;
+	type TreeDepth = ShieldedPoolTreeDepth;
+	type RootHistorySize = ShieldedPoolRootHistorySize;
+	type MaxProofLen = ZkMaxProofLen;
+	type WeightInfo = pallet_shielded_pool::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_template::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+}
+
 /// Upper thresholds delimiting the bag list.
 pub const VOTER_BAG_THRESHOLDS: [u64; 200] = [
 	100_000_000_000_000,
@@ -1529,13 +1978,22 @@ impl EnsureOriginWithArg<RuntimeOrigin, RuntimeParametersKey> for DynamicParamet
 	) -> Result<Self::Success, RuntimeOrigin> {
 		 match key {
 			RuntimeParametersKey::Storage(_) => {
-				frame_system::ensure_root(origin.clone()).map_err(|_| origin)?;
+				EnsureRootOrGeneralAdmin::try_origin(origin)?;
 				Ok(())
 			},
+			// The tracks decide who can reach every other origin, so only root changes them.
 			RuntimeParametersKey::Referenda(_) => {
 				frame_system::ensure_root(origin.clone()).map_err(|_| origin)?;
 				Ok(())
 			},
+			RuntimeParametersKey::TransactionPayment(_) => {
+				EnsureRootOrGeneralAdmin::try_origin(origin)?;
+				Ok(())
+			},
+			RuntimeParametersKey::Sudo(_) => {
+				frame_system::ensure_root(origin.clone()).map_err(|_| origin)?;
+				Ok(())
+			},
 		}
 	}
 
@@ -0,0 +1,511 @@
+//! Origins of the referendum tracks other than root.
+
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+	use frame_support::{pallet_prelude::*, traits::OriginTrait};
+
+	use crate::{Balance, UNIT};
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The origin a referendum dispatches its call with, one for each track but the root one.
+	#[derive(
+		PartialEq,
+		Eq,
+		Clone,
+		MaxEncodedLen,
+		Encode,
+		Decode,
+		DecodeWithMemTracking,
+		TypeInfo,
+		RuntimeDebug,
+	)]
+	#[pallet::origin]
+	pub enum Origin {
+		/// Origin for general administrative changes, such as the treasury.
+		GeneralAdmin,
+		/// Origin able to cancel referenda.
+		ReferendumCanceller,
+		/// Origin able to kill referenda and slash their deposits.
+		ReferendumKiller,
+		/// Origin able to dispatch whitelisted calls.
+		WhitelistedCaller,
+		/// Origin able to spend up to [`SMALL_SPEND`] from the treasury at once.
+		SmallSpender,
+		/// Origin able to spend up to [`MEDIUM_SPEND`] from the treasury at once.
+		MediumSpender,
+		/// Origin able to spend up to [`BIG_SPEND`] from the treasury at once.
+		BigSpender,
+	}
+
+	/// The most KORA a [`Origin::SmallSpender`] referendum can spend, assets being valued
+	/// through `AssetRate`.
+	pub const SMALL_SPEND: Balance = 10_000 * UNIT;
+	/// The most KORA a [`Origin::MediumSpender`] referendum can spend.
+	pub const MEDIUM_SPEND: Balance = 100_000 * UNIT;
+	/// The most KORA a [`Origin::BigSpender`] referendum can spend.
+	pub const BIG_SPEND: Balance = 1_000_000 * UNIT;
+
+	macro_rules! decl_unit_ensures {
+		( $name:ident: $success_type:ty = $success:expr ) => {
+			#[doc = concat!("Ensures the origin is [`Origin::", stringify!($name), "`].")]
+			pub struct $name;
+			impl<O: OriginTrait + From<Origin>> EnsureOrigin<O> for $name
+			where
+				for<'a> &'a O::PalletsOrigin: TryInto<&'a Origin>,
+			{
+				type Success = $success_type;
+				fn try_origin(o: O) -> Result<Self::Success, O> {
+					match o.caller().try_into() {
+						Ok(Origin::$name) => Ok($success),
+						_ => Err(o),
+					}
+				}
+				#[cfg(feature = "runtime-benchmarks")]
+				fn try_successful_origin() -> Result<O, ()> {
+					Ok(O::from(Origin::$name))
+				}
+			}
+		};
+		( $name:ident ) => { decl_unit_ensures! { $name: () = () } };
+		( $name:ident, $( $rest:tt )* ) => {
+			decl_unit_ensures! { $name }
+			decl_unit_ensures! { $( $rest )* }
+		};
+		() => {}
+	}
+	decl_unit_ensures!(GeneralAdmin, ReferendumCanceller, ReferendumKiller, WhitelistedCaller,);
+
+	macro_rules! decl_ensure {
+		(
+			$( #[$attr:meta] )*
+			$vis:vis type $name:ident: EnsureOrigin<Success = $success_type:ty> {
+				$( $item:ident = $success:expr, )*
+			}
+		) => {
+			$( #[$attr] )*
+			$vis struct $name;
+			impl<O: OriginTrait + From<Origin>> EnsureOrigin<O> for $name
+			where
+				for<'a> &'a O::PalletsOrigin: TryInto<&'a Origin>,
+			{
+				type Success = $success_type;
+				fn try_origin(o: O) -> Result<Self::Success, O> {
+					match o.caller().try_into() {
+						$( Ok(Origin::$item) => Ok($success), )*
+						_ => Err(o),
+					}
+				}
+				#[cfg(feature = "runtime-benchmarks")]
+				fn try_successful_origin() -> Result<O, ()> {
+					// The last origin is the most privileged one, so the likeliest to succeed.
+					let _result: Result<O, ()> = Err(());
+					$( let _result: Result<O, ()> = Ok(O::from(Origin::$item)); )*
+					_result
+				}
+			}
+		};
+	}
+	decl_ensure! {
+		/// Ensures the origin is a spender one, and yields the most it can spend.
+		pub type Spender: EnsureOrigin<Success = Balance> {
+			SmallSpender = SMALL_SPEND,
+			MediumSpender = MEDIUM_SPEND,
+			BigSpender = BIG_SPEND,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::pallet_custom_origins::{Origin, BIG_SPEND, MEDIUM_SPEND, SMALL_SPEND};
+	use crate::{
+		configs::{
+			dynamic_params,
+			test_utils::{new_test_ext, technical_committee},
+			TechnicalCollective,
+		},
+		AccountId, AssetRate, Balances, BlockNumber, ConvictionVoting, OriginCaller, Preimage,
+		Referenda, Runtime, RuntimeCall, RuntimeOrigin, Scheduler, System, TechnicalMembership,
+		Treasury, Whitelist, UNIT,
+	};
+	use frame_support::{
+		assert_noop, assert_ok,
+		traits::{fungible::NativeOrWithId, schedule::DispatchTime, OnInitialize, StorePreimage},
+	};
+	use pallet_conviction_voting::{AccountVote, Conviction, Vote};
+	use pallet_referenda::ReferendumInfo;
+	use sp_runtime::{traits::Hash, DispatchError, FixedPointNumber, FixedU128};
+
+	const PROPOSER: AccountId = AccountId::new([1; 32]);
+	/// Holds nearly all the issuance, so its vote alone passes any referendum.
+	const VOTER: AccountId = AccountId::new([3; 32]);
+	const BENEFICIARY: AccountId = AccountId::new([4; 32]);
+	const VOTE: u128 = 1_000_000_000 * UNIT;
+	const BALANCES: [(AccountId, u128); 2] = [(PROPOSER, 10_000 * UNIT), (VOTER, VOTE + UNIT)];
+
+	/// Submits a referendum that dispatches a remark with `origin` and returns its index.
+	fn submit(origin: OriginCaller) -> u32 {
+		submit_call(origin, RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+	}
+
+	/// Submits a referendum that dispatches `call` with `origin` and returns its index.
+	fn submit_call(origin: OriginCaller, call: RuntimeCall) -> u32 {
+		assert_ok!(Referenda::submit(
+			RuntimeOrigin::signed(PROPOSER),
+			Box::new(origin),
+			Preimage::bound(call).unwrap(),
+			DispatchTime::After(0),
+		));
+		pallet_referenda::ReferendumCount::<Runtime>::get() - 1
+	}
+
+	/// Runs the scheduler, which drives referenda and enacts them, for `count` blocks.
+	fn run_blocks(count: BlockNumber) {
+		for _ in 0..count {
+			let now = System::block_number() + 1;
+			System::set_block_number(now);
+			Scheduler::on_initialize(now);
+		}
+	}
+
+	/// Takes a referendum dispatching `call` with `origin` through its decision deposit, a
+	/// passing vote, its decision and its enactment.
+	fn enact(origin: OriginCaller, call: RuntimeCall) {
+		let index = submit_call(origin, call);
+		let track = dynamic_params::referenda::Tracks::get()
+			.into_iter()
+			.find(|track| track.id == track_of(index))
+			.unwrap()
+			.info;
+
+		assert_ok!(Referenda::place_decision_deposit(RuntimeOrigin::signed(PROPOSER), index));
+		assert_ok!(ConvictionVoting::vote(
+			RuntimeOrigin::signed(VOTER),
+			index,
+			AccountVote::Standard {
+				vote: Vote { aye: true, conviction: Conviction::Locked1x },
+				balance: VOTE,
+			},
+		));
+		run_blocks(track.prepare_period + track.confirm_period + track.min_enactment_period + 5);
+
+		assert!(
+			matches!(
+				pallet_referenda::ReferendumInfoFor::<Runtime>::get(index),
+				Some(ReferendumInfo::Approved(..))
+			),
+			"referendum {index} was not approved"
+		);
+	}
+
+	fn force_set_balance(amount: u128) -> RuntimeCall {
+		RuntimeCall::Balances(pallet_balances::Call::force_set_balance {
+			who: BENEFICIARY.into(),
+			new_free: amount,
+		})
+	}
+
+	/// Enacts a spend of `amount` with `origin`, and whether it was recorded.
+	fn enact_spend(origin: Origin, amount: u128) -> bool {
+		let call = RuntimeCall::Treasury(pallet_treasury::Call::spend {
+			asset_kind: Box::new(NativeOrWithId::Native),
+			amount,
+			beneficiary: Box::new(BENEFICIARY),
+			valid_from: None,
+		});
+		enact(OriginCaller::Origins(origin), call);
+		pallet_treasury::Spends::<Runtime>::iter_values().any(|spend| spend.amount == amount)
+	}
+
+	/// Spends `amount` of `asset` from the treasury to `PROPOSER`.
+	fn spend(
+		origin: RuntimeOrigin,
+		asset: NativeOrWithId<u32>,
+		amount: u128,
+	) -> sp_runtime::DispatchResult {
+		Treasury::spend(origin, Box::new(asset), amount, Box::new(PROPOSER), None)
+	}
+
+	fn track_of(index: u32) -> u16 {
+		match pallet_referenda::ReferendumInfoFor::<Runtime>::get(index) {
+			Some(pallet_referenda::ReferendumInfo::Ongoing(status)) => status.track,
+			_ => panic!("referendum {index} is not ongoing"),
+		}
+	}
+
+	#[test]
+	fn every_track_has_an_origin() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let origins = dynamic_params::referenda::Origins::get();
+			for track in dynamic_params::referenda::Tracks::get() {
+				assert!(origins.iter().any(|(_, id)| *id == track.id), "track {}", track.id);
+			}
+		});
+	}
+
+	#[test]
+	fn referenda_go_to_the_track_of_their_origin() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let tracks = [
+				(OriginCaller::system(frame_system::RawOrigin::Root), 0),
+				(OriginCaller::Origins(Origin::GeneralAdmin), 1),
+				(OriginCaller::Origins(Origin::ReferendumCanceller), 2),
+				(OriginCaller::Origins(Origin::ReferendumKiller), 3),
+				(OriginCaller::Origins(Origin::WhitelistedCaller), 4),
+				(OriginCaller::Origins(Origin::SmallSpender), 5),
+				(OriginCaller::Origins(Origin::MediumSpender), 6),
+				(OriginCaller::Origins(Origin::BigSpender), 7),
+			];
+			for (origin, track) in tracks {
+				let index = submit(origin);
+				assert_eq!(track_of(index), track);
+			}
+		});
+	}
+
+	#[test]
+	fn signed_origins_have_no_track() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			assert_noop!(
+				Referenda::submit(
+					RuntimeOrigin::signed(PROPOSER),
+					Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(PROPOSER))),
+					Preimage::bound(call).unwrap(),
+					DispatchTime::After(0),
+				),
+				pallet_referenda::Error::<Runtime>::NoTrack
+			);
+		});
+	}
+
+	#[test]
+	fn only_the_canceller_and_root_can_cancel() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let index = submit(OriginCaller::Origins(Origin::GeneralAdmin));
+
+			assert_noop!(
+				Referenda::cancel(RuntimeOrigin::signed(PROPOSER), index),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Referenda::cancel(Origin::ReferendumKiller.into(), index),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Referenda::cancel(Origin::ReferendumCanceller.into(), index));
+		});
+	}
+
+	#[test]
+	fn only_the_killer_and_root_can_kill() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let index = submit(OriginCaller::Origins(Origin::GeneralAdmin));
+
+			assert_noop!(
+				Referenda::kill(Origin::ReferendumCanceller.into(), index),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Referenda::kill(Origin::ReferendumKiller.into(), index));
+		});
+	}
+
+	#[test]
+	fn a_technical_committee_majority_whitelists_calls() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let call_hash = <Runtime as frame_system::Config>::Hashing::hash(b"call");
+
+			assert_noop!(
+				Whitelist::whitelist_call(technical_committee(1, 2), call_hash),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Whitelist::whitelist_call(technical_committee(2, 3), call_hash));
+		});
+	}
+
+	#[test]
+	fn a_technical_committee_majority_vetoes_referenda() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let index = submit(OriginCaller::Origins(Origin::GeneralAdmin));
+
+			assert_noop!(
+				Referenda::cancel(technical_committee(1, 2), index),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Referenda::cancel(technical_committee(2, 3), index));
+		});
+	}
+
+	#[test]
+	fn governance_decides_the_technical_committee() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let member = AccountId::new([2; 32]);
+
+			assert_noop!(
+				TechnicalMembership::add_member(
+					RuntimeOrigin::signed(PROPOSER),
+					member.clone().into()
+				),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(TechnicalMembership::add_member(
+				Origin::GeneralAdmin.into(),
+				member.clone().into()
+			));
+			assert_eq!(
+				pallet_collective::Members::<Runtime, TechnicalCollective>::get(),
+				vec![member]
+			);
+		});
+	}
+
+	#[test]
+	fn signed_origins_cannot_spend_from_the_treasury() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			assert_noop!(
+				spend(RuntimeOrigin::signed(PROPOSER), NativeOrWithId::Native, UNIT),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				spend(Origin::GeneralAdmin.into(), NativeOrWithId::Native, UNIT),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn spenders_are_capped_by_their_track() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			assert_noop!(
+				spend(Origin::SmallSpender.into(), NativeOrWithId::Native, SMALL_SPEND + 1),
+				pallet_treasury::Error::<Runtime>::InsufficientPermission
+			);
+			assert_ok!(spend(Origin::SmallSpender.into(), NativeOrWithId::Native, SMALL_SPEND));
+			assert_ok!(spend(Origin::MediumSpender.into(), NativeOrWithId::Native, SMALL_SPEND + 1));
+			assert_ok!(spend(RuntimeOrigin::root(), NativeOrWithId::Native, u128::MAX));
+		});
+	}
+
+	#[test]
+	fn asset_spends_are_capped_by_their_value_in_kora() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let asset = NativeOrWithId::WithId(1);
+			// One unit of the asset is worth two KORA.
+			assert_ok!(AssetRate::create(
+				RuntimeOrigin::root(),
+				Box::new(asset.clone()),
+				FixedU128::saturating_from_integer(2),
+			));
+
+			assert_noop!(
+				spend(Origin::SmallSpender.into(), asset.clone(), SMALL_SPEND / 2 + 1),
+				pallet_treasury::Error::<Runtime>::InsufficientPermission
+			);
+			assert_ok!(spend(Origin::SmallSpender.into(), asset, SMALL_SPEND / 2));
+		});
+	}
+
+	#[test]
+	fn root_referenda_dispatch_as_root() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			enact(OriginCaller::system(frame_system::RawOrigin::Root), force_set_balance(7 * UNIT));
+			assert_eq!(Balances::free_balance(BENEFICIARY), 7 * UNIT);
+		});
+	}
+
+	#[test]
+	fn general_admin_referenda_dispatch_as_the_general_admin() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let call = RuntimeCall::TechnicalMembership(pallet_membership::Call::add_member {
+				who: BENEFICIARY.into(),
+			});
+			enact(OriginCaller::Origins(Origin::GeneralAdmin), call);
+			assert_eq!(
+				pallet_collective::Members::<Runtime, TechnicalCollective>::get(),
+				vec![BENEFICIARY]
+			);
+		});
+	}
+
+	#[test]
+	fn the_general_admin_manages_the_scheduler_and_preimages() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let remark =
+				|| Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }));
+			assert_ok!(Scheduler::schedule(Origin::GeneralAdmin.into(), 10, None, 0, remark()));
+			assert_noop!(
+				Scheduler::schedule(RuntimeOrigin::signed(PROPOSER), 10, None, 0, remark()),
+				DispatchError::BadOrigin
+			);
+
+			let preimage = vec![1, 2, 3];
+			let hash = <Runtime as frame_system::Config>::Hashing::hash(&preimage);
+			assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(PROPOSER), preimage));
+			assert_noop!(
+				Preimage::request_preimage(RuntimeOrigin::signed(PROPOSER), hash),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Preimage::request_preimage(Origin::GeneralAdmin.into(), hash));
+			assert_ok!(Preimage::unrequest_preimage(Origin::GeneralAdmin.into(), hash));
+		});
+	}
+
+	#[test]
+	fn canceller_referenda_cancel_other_referenda() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let target = submit(OriginCaller::Origins(Origin::GeneralAdmin));
+			let call = RuntimeCall::Referenda(pallet_referenda::Call::cancel { index: target });
+			enact(OriginCaller::Origins(Origin::ReferendumCanceller), call);
+			assert!(matches!(
+				pallet_referenda::ReferendumInfoFor::<Runtime>::get(target),
+				Some(ReferendumInfo::Cancelled(..))
+			));
+		});
+	}
+
+	#[test]
+	fn killer_referenda_kill_other_referenda() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let target = submit(OriginCaller::Origins(Origin::GeneralAdmin));
+			let call = RuntimeCall::Referenda(pallet_referenda::Call::kill { index: target });
+			enact(OriginCaller::Origins(Origin::ReferendumKiller), call);
+			assert!(matches!(
+				pallet_referenda::ReferendumInfoFor::<Runtime>::get(target),
+				Some(ReferendumInfo::Killed(..))
+			));
+		});
+	}
+
+	#[test]
+	fn whitelisted_caller_referenda_dispatch_whitelisted_calls_as_root() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			let call = force_set_balance(7 * UNIT);
+			let call_hash = <Runtime as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Whitelist::whitelist_call(technical_committee(2, 3), call_hash));
+
+			let call = RuntimeCall::Whitelist(
+				pallet_whitelist::Call::dispatch_whitelisted_call_with_preimage {
+					call: Box::new(call),
+				},
+			);
+			enact(OriginCaller::Origins(Origin::WhitelistedCaller), call);
+			assert_eq!(Balances::free_balance(BENEFICIARY), 7 * UNIT);
+		});
+	}
+
+	#[test]
+	fn spender_referenda_spend_up_to_their_cap() {
+		new_test_ext(&BALANCES).execute_with(|| {
+			assert!(enact_spend(Origin::SmallSpender, SMALL_SPEND));
+			assert!(!enact_spend(Origin::SmallSpender, SMALL_SPEND + 1));
+			assert!(enact_spend(Origin::MediumSpender, MEDIUM_SPEND));
+			assert!(!enact_spend(Origin::MediumSpender, MEDIUM_SPEND + 1));
+			assert!(enact_spend(Origin::BigSpender, BIG_SPEND));
+			assert!(!enact_spend(Origin::BigSpender, BIG_SPEND + 1));
+		});
+	}
+}
@@ -0,0 +1,239 @@
+//! Stopping calls during an incident, through `TxPause` and `SafeMode`.
+
+use frame_support::traits::{Contains, GetCallMetadata};
+use pallet_tx_pause::RuntimeCallNameOf;
+
+use crate::{Runtime, RuntimeCall, TxPause};
+
+/// The call name that pauses every call of a pallet in `TxPause`.
+pub const WHOLE_PALLET: &[u8] = b"*";
+
+/// Lets through the calls that `TxPause` has not paused, either by name or by pausing every call
+/// of their pallet under the name [`WHOLE_PALLET`].
+pub struct NotPaused;
+
+impl Contains<RuntimeCall> for NotPaused {
+	fn contains(call: &RuntimeCall) -> bool {
+		let metadata = call.get_call_metadata();
+		let (pallet, function) = (metadata.pallet_name.as_bytes(), metadata.function_name.as_bytes());
+		if is_whitelisted(pallet, function) {
+			return true;
+		}
+		!TxPause::is_paused_unbound(pallet.to_vec(), function.to_vec()) &&
+			!TxPause::is_paused_unbound(pallet.to_vec(), WHOLE_PALLET.to_vec())
+	}
+}
+
+/// The calls `TxPause` cannot pause, the same as those [`SafeModeWhitelistedCalls`] lets
+/// through.
+pub struct TxPauseWhitelistedCalls;
+
+impl Contains<RuntimeCallNameOf<Runtime>> for TxPauseWhitelistedCalls {
+	fn contains((pallet, function): &RuntimeCallNameOf<Runtime>) -> bool {
+		is_whitelisted(pallet, function)
+	}
+}
+
+/// The calls that still work in safe mode and cannot be paused: block production and finality,
+/// governance, and leaving staking.
+pub struct SafeModeWhitelistedCalls;
+
+impl Contains<RuntimeCall> for SafeModeWhitelistedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		let metadata = call.get_call_metadata();
+		is_whitelisted(metadata.pallet_name.as_bytes(), metadata.function_name.as_bytes())
+	}
+}
+
+/// Whether the call `function` of `pallet` is kept available during an incident.
+///
+/// Pausing a whole pallet under [`WHOLE_PALLET`] is only refused for the pallets whitelisted in
+/// full; the whitelisted calls of the others keep working while the rest is paused.
+fn is_whitelisted(pallet: &[u8], function: &[u8]) -> bool {
+	match pallet {
+		b"System" |
+		b"Timestamp" |
+		b"Babe" |
+		b"Grandpa" |
+		b"Beefy" |
+		b"ImOnline" |
+		b"Session" |
+		b"ElectionProviderMultiPhase" => true,
+		b"Referenda" |
+		b"ConvictionVoting" |
+		b"Whitelist" |
+		b"TechnicalCommittee" |
+		b"Preimage" |
+		b"Utility" |
+		b"Sudo" |
+		b"SafeMode" |
+		b"TxPause" => true,
+		b"Staking" => matches!(function, b"unbond" | b"withdraw_unbonded" | b"chill"),
+		b"NominationPools" => matches!(function, b"unbond" | b"withdraw_unbonded"),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		configs::{
+			pallet_custom_origins::Origin,
+			test_utils::{new_test_ext, technical_committee},
+			SafeModeForceEnterDuration,
+		},
+		AccountId, RuntimeOrigin, SafeMode,
+	};
+	use frame_support::{assert_noop, assert_ok, BoundedVec};
+	use sp_runtime::DispatchError;
+
+	const ALICE: AccountId = AccountId::new([1; 32]);
+
+	fn name(pallet: &[u8], function: &[u8]) -> RuntimeCallNameOf<Runtime> {
+		(BoundedVec::truncate_from(pallet.to_vec()), BoundedVec::truncate_from(function.to_vec()))
+	}
+
+	fn filtered(call: &RuntimeCall) -> bool {
+		!<Runtime as frame_system::Config>::BaseCallFilter::contains(call)
+	}
+
+	fn transfer() -> RuntimeCall {
+		RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+			dest: ALICE.into(),
+			value: 1,
+		})
+	}
+
+	fn transfer_all() -> RuntimeCall {
+		RuntimeCall::Balances(pallet_balances::Call::transfer_all {
+			dest: ALICE.into(),
+			keep_alive: false,
+		})
+	}
+
+	fn unbond() -> RuntimeCall {
+		RuntimeCall::Staking(pallet_staking::Call::unbond { value: 1 })
+	}
+
+	#[test]
+	fn paused_calls_are_filtered() {
+		new_test_ext(&[]).execute_with(|| {
+			assert_ok!(TxPause::pause(
+				Origin::GeneralAdmin.into(),
+				name(b"Balances", b"transfer_keep_alive")
+			));
+			assert!(filtered(&transfer()));
+			assert!(!filtered(&transfer_all()));
+
+			assert_ok!(TxPause::unpause(
+				technical_committee(2, 3),
+				name(b"Balances", b"transfer_keep_alive")
+			));
+			assert!(!filtered(&transfer()));
+		});
+	}
+
+	#[test]
+	fn whole_pallets_are_paused_by_the_wildcard() {
+		new_test_ext(&[]).execute_with(|| {
+			assert_ok!(TxPause::pause(RuntimeOrigin::root(), name(b"Balances", WHOLE_PALLET)));
+			assert!(filtered(&transfer()));
+			assert!(filtered(&transfer_all()));
+			assert!(!filtered(&unbond()));
+		});
+	}
+
+	#[test]
+	fn governance_cannot_be_paused() {
+		new_test_ext(&[]).execute_with(|| {
+			let pallets = [
+				&b"Referenda"[..],
+				b"TechnicalCommittee",
+				b"Preimage",
+				b"SafeMode",
+				b"TxPause",
+				b"System",
+				b"ImOnline",
+				b"Session",
+				b"ElectionProviderMultiPhase",
+			];
+			for pallet in pallets {
+				assert_noop!(
+					TxPause::pause(RuntimeOrigin::root(), name(pallet, WHOLE_PALLET)),
+					pallet_tx_pause::Error::<Runtime>::Unpausable
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn leaving_staking_cannot_be_paused() {
+		new_test_ext(&[]).execute_with(|| {
+			let calls = [
+				name(b"Staking", b"unbond"),
+				name(b"Staking", b"withdraw_unbonded"),
+				name(b"Staking", b"chill"),
+				name(b"NominationPools", b"unbond"),
+				name(b"NominationPools", b"withdraw_unbonded"),
+				name(b"Utility", WHOLE_PALLET),
+				name(b"Sudo", WHOLE_PALLET),
+			];
+			for call in calls {
+				assert_noop!(
+					TxPause::pause(RuntimeOrigin::root(), call),
+					pallet_tx_pause::Error::<Runtime>::Unpausable
+				);
+			}
+
+			// Pausing the rest of staking leaves the way out open.
+			assert_ok!(TxPause::pause(RuntimeOrigin::root(), name(b"Staking", b"bond_extra")));
+			assert_ok!(TxPause::pause(RuntimeOrigin::root(), name(b"Staking", WHOLE_PALLET)));
+			assert!(filtered(&RuntimeCall::Staking(pallet_staking::Call::bond_extra {
+				max_additional: 1
+			})));
+			assert!(!filtered(&unbond()));
+		});
+	}
+
+	#[test]
+	fn signed_origins_cannot_pause() {
+		new_test_ext(&[]).execute_with(|| {
+			assert_noop!(
+				TxPause::pause(RuntimeOrigin::signed(ALICE), name(b"Balances", WHOLE_PALLET)),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				TxPause::pause(technical_committee(1, 3), name(b"Balances", WHOLE_PALLET)),
+				DispatchError::BadOrigin
+			);
+			// Without a deposit, safe mode cannot be entered permissionlessly.
+			assert_noop!(
+				SafeMode::enter(RuntimeOrigin::signed(ALICE)),
+				pallet_safe_mode::Error::<Runtime>::NotConfigured
+			);
+			assert_noop!(
+				SafeMode::force_enter(RuntimeOrigin::signed(ALICE)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn safe_mode_only_lets_whitelisted_calls_through_until_it_expires() {
+		new_test_ext(&[]).execute_with(|| {
+			assert_ok!(SafeMode::force_enter(technical_committee(2, 3)));
+			let until = pallet_safe_mode::EnteredUntil::<Runtime>::get().unwrap();
+			assert_eq!(until, 1 + SafeModeForceEnterDuration::get());
+
+			assert!(filtered(&transfer()));
+			assert!(!filtered(&unbond()));
+			assert!(!filtered(&RuntimeCall::Referenda(pallet_referenda::Call::cancel {
+				index: 0
+			})));
+
+			assert_ok!(SafeMode::force_exit(Origin::GeneralAdmin.into()));
+			assert!(!filtered(&transfer()));
+		});
+	}
+}
@@ -0,0 +1,113 @@
+//! Retiring `Sudo` in favour of governance.
+
+use frame_support::traits::Contains;
+
+use super::dynamic_params;
+use crate::{RuntimeCall, System};
+
+/// Whether `dynamic_params::sudo::RetirementBlock` has been reached.
+pub fn sudo_retired() -> bool {
+	dynamic_params::sudo::RetirementBlock::get()
+		.is_some_and(|retirement| System::block_number() >= retirement)
+}
+
+/// Lets every call through, but blocks the `Sudo` calls other than `remove_key` once
+/// [`sudo_retired`].
+///
+/// Root bypasses the filter, so referenda on the root track keep working; only the sudo key
+/// loses its power. It can still remove itself, and `migrations::RetireSudo` removes it on the
+/// next runtime upgrade otherwise.
+pub struct SudoRetirement;
+
+impl Contains<RuntimeCall> for SudoRetirement {
+	fn contains(call: &RuntimeCall) -> bool {
+		match call {
+			RuntimeCall::Sudo(pallet_sudo::Call::remove_key {}) => true,
+			RuntimeCall::Sudo(_) => !sudo_retired(),
+			_ => true,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		configs::{pallet_custom_origins::Origin, test_utils::new_test_ext, RuntimeParameters},
+		AccountId, Parameters, Runtime, RuntimeOrigin,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use sp_runtime::{traits::Dispatchable, DispatchError};
+
+	const KEY: AccountId = AccountId::new([1; 32]);
+
+	/// Externalities where `KEY` holds the sudo key.
+	fn with_sudo_key() -> sp_io::TestExternalities {
+		let mut ext = new_test_ext(&[]);
+		ext.execute_with(|| pallet_sudo::Key::<Runtime>::put(KEY));
+		ext
+	}
+
+	fn retire_at(block: u32) {
+		assert_ok!(Parameters::set_parameter(
+			RuntimeOrigin::root(),
+			RuntimeParameters::Sudo(dynamic_params::sudo::Parameters::RetirementBlock(
+				dynamic_params::sudo::RetirementBlock,
+				Some(Some(block)),
+			)),
+		));
+	}
+
+	fn remark() -> Box<RuntimeCall> {
+		Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+	}
+
+	#[test]
+	fn sudo_works_until_its_retirement_block() {
+		with_sudo_key().execute_with(|| {
+			retire_at(10);
+			System::set_block_number(9);
+			assert!(!sudo_retired());
+			assert_ok!(RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: remark() })
+				.dispatch(RuntimeOrigin::signed(KEY)));
+
+			System::set_block_number(10);
+			assert!(sudo_retired());
+			assert_noop!(
+				RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: remark() })
+					.dispatch(RuntimeOrigin::signed(KEY))
+					.map_err(|e| e.error),
+				frame_system::Error::<Runtime>::CallFiltered
+			);
+		});
+	}
+
+	#[test]
+	fn a_retired_key_can_still_be_removed() {
+		with_sudo_key().execute_with(|| {
+			retire_at(1);
+			assert!(sudo_retired());
+
+			assert_ok!(RuntimeCall::Sudo(pallet_sudo::Call::remove_key {})
+				.dispatch(RuntimeOrigin::signed(KEY)));
+			assert_eq!(pallet_sudo::Key::<Runtime>::get(), None);
+		});
+	}
+
+	#[test]
+	fn only_root_sets_the_retirement_block() {
+		with_sudo_key().execute_with(|| {
+			assert_noop!(
+				Parameters::set_parameter(
+					Origin::GeneralAdmin.into(),
+					RuntimeParameters::Sudo(dynamic_params::sudo::Parameters::RetirementBlock(
+						dynamic_params::sudo::RetirementBlock,
+						Some(Some(1)),
+					)),
+				),
+				DispatchError::BadOrigin
+			);
+			assert!(!sudo_retired());
+		});
+	}
+}
@@ -0,0 +1,22 @@
+//! Externalities and origins shared by the tests of the runtime configuration.
+
+use sp_runtime::BuildStorage;
+
+use super::TechnicalCollective;
+use crate::{AccountId, Balance, Runtime, RuntimeOrigin, System};
+
+/// Externalities at block 1 of a chain where only `balances` are endowed.
+pub fn new_test_ext(balances: &[(AccountId, Balance)]) -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> { balances: balances.to_vec(), ..Default::default() }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// The origin of a technical committee motion passed by `ayes` of its `members`.
+pub fn technical_committee(ayes: u32, members: u32) -> RuntimeOrigin {
+	pallet_collective::RawOrigin::<AccountId, TechnicalCollective>::Members(ayes, members).into()
+}
@@ -1,10 +1,12 @@
-use crate::{AccountId, Balance, BalancesConfig, RuntimeGenesisConfig, SessionConfig, SessionKeys, StakingConfig, SudoConfig, UNIT};
+use crate::{migrations::placeholder_beefy_id, AccountId, Balance, BalancesConfig, BeefyConfig, BlockNumber, RuntimeGenesisConfig, SessionConfig, SessionKeys, StakingConfig, SudoConfig, TechnicalMembershipConfig, UNIT};
 use alloc::{vec, vec::Vec};
 use frame_support::build_struct_json_patch;
 use sp_consensus_babe::AuthorityId as BabeId;
 use serde_json::Value;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
+use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
 use sp_core::crypto::{get_public_from_string_or_panic, Ss58Codec};
 use sp_core::sr25519;
 use sp_genesis_builder::{self, PresetId};
@@ -36,8 +38,10 @@ pub fn session_keys(
 	grandpa: GrandpaId,
 	babe: BabeId,
 	im_online: ImOnlineId,
+	authority_discovery: AuthorityDiscoveryId,
+	beefy: BeefyId,
 ) -> SessionKeys {
-	SessionKeys { grandpa, babe, im_online }
+	SessionKeys { grandpa, babe, im_online, authority_discovery, beefy }
 }
 
 pub fn session_keys_from_seed(seed: &str) -> SessionKeys {
@@ -45,14 +49,23 @@ pub fn session_keys_from_seed(seed: &str) -> SessionKeys {
 		get_public_from_string_or_panic::<GrandpaId>(seed),
 		get_public_from_string_or_panic::<BabeId>(seed),
 		get_public_from_string_or_panic::<ImOnlineId>(seed),
+		get_public_from_string_or_panic::<AuthorityDiscoveryId>(seed),
+		get_public_from_string_or_panic::<BeefyId>(seed),
 	)
 }
 
+/// Session keys from the sr25519 and ed25519 addresses of a validator.
+///
+/// There is no ecdsa address to take the BEEFY key from, so the validator gets a placeholder one
+/// and must set its real keys with `session.set_keys` before BEEFY can finalize anything. Chains
+/// built from such keys start without BEEFY, until root calls `beefy.set_new_genesis`.
 pub fn session_keys_from_address(sr_addr: &str, ed_addr: &str) -> SessionKeys {
 	session_keys(
 		GrandpaId::from_ss58check(ed_addr).expect("Bad ss58 address"),
 		BabeId::from_ss58check(sr_addr).expect("Bad ss58 address"),
 		ImOnlineId::from_ss58check(sr_addr).expect("Bad ss58 address"),
+		AuthorityDiscoveryId::from_ss58check(sr_addr).expect("Bad ss58 address"),
+		placeholder_beefy_id(&AccountId::from_ss58check(sr_addr).expect("Bad ss58 address")),
 	)
 }
 
@@ -72,11 +85,17 @@ pub fn authority_keys_from_address(sr_addr: &str, ed_addr: &str) -> (AccountId,
 }
 
 // Returns the genesis config presets populated with given parameters.
+//
+// A chain built with a `root_key` keeps sudo until `dynamic_params::sudo::RetirementBlock`, which
+// is unset at genesis, so only the development preset gets one; the others are governed through
+// referenda from their first block.
 fn generate_genesis_config(
 	initial_authorities: Vec<(AccountId, AccountId, SessionKeys)>,
-	root_key: AccountId,
+	root_key: Option<AccountId>,
+	technical_committee: Vec<AccountId>,
 	endowed_accounts: Vec<AccountId>,
 	stakers: Vec<Staker>,
+	beefy_genesis_block: Option<BlockNumber>,
 ) -> Value {
 	let validator_count = initial_authorities.len() as u32;
 
@@ -88,6 +107,10 @@ fn generate_genesis_config(
 		babe: pallet_babe::GenesisConfig {
 			epoch_config: crate::apis::BABE_GENESIS_EPOCH_CONFIG,
 		},
+		beefy: BeefyConfig {
+			genesis_block: beefy_genesis_block,
+			..Default::default()
+		},
 		session: SessionConfig {
 			keys: initial_authorities
 				.iter()
@@ -109,7 +132,13 @@ fn generate_genesis_config(
 			stakers,
 			..Default::default()
 		},
-		sudo: SudoConfig { key: Some(root_key) },
+		sudo: SudoConfig { key: root_key },
+		technical_membership: TechnicalMembershipConfig {
+			members: technical_committee
+				.try_into()
+				.expect("Too many technical committee members: upper limit is TechnicalMaxMembers"),
+			..Default::default()
+		},
 	})
 }
 
@@ -120,12 +149,14 @@ pub fn development_config_genesis() -> Value {
 
 	generate_genesis_config(
 		vec![(alice.clone(), alice.clone(), alice_session_keys)],
-		alice.clone(),
+		Some(alice.clone()),
+		vec![alice.clone()],
 		vec![alice.clone(), bob.clone()],
 		vec![
 			validator(alice.clone()),
 			nominator(bob.clone(),vec![alice.clone()]),
 		],
+		Some(1),
 	)
 }
 
@@ -140,13 +171,15 @@ pub fn local_config_genesis() -> Value {
 			(alice.clone(), alice.clone(), alice_session_keys),
 			(bob.clone(), bob.clone(), bob_session_keys)
 		],
-		alice.clone(),
+		None,
+		vec![alice.clone(), bob.clone()],
 		vec![alice.clone(), bob.clone(), charlie.clone()],
 		vec![
 			validator(alice.clone()),
 			validator(bob.clone()),
 			nominator(charlie.clone(), vec![alice.clone(), bob.clone()]),
 		],
+		Some(1),
 	)
 }
 
@@ -157,9 +190,12 @@ pub fn chanto_testnet_config_genesis() -> Value {
 
 	generate_genesis_config(
 		vec![(default_validator.clone(), default_validator.clone(), default_validator_session_keys)],
-		default_validator.clone(),
+		None,
+		vec![default_validator.clone()],
 		vec![default_validator.clone()],
 		vec![validator(default_validator.clone())],
+		// Its BEEFY key is a placeholder.
+		None,
 	)
 }
 
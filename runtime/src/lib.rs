@@ -9,11 +9,14 @@ pub mod apis;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
 pub mod configs;
+mod migrations;
+mod swap_routes;
 
 extern crate alloc;
 extern crate core;
 
 use alloc::vec::Vec;
+use configs::pallet_custom_origins;
 use sp_runtime::{
 	generic, impl_opaque_keys,
 	traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -60,6 +63,8 @@ impl_opaque_keys! {
 		pub babe: Babe,
 		pub grandpa: Grandpa,
 		pub im_online: ImOnline,
+		pub authority_discovery: AuthorityDiscovery,
+		pub beefy: Beefy,
 	}
 }
 
@@ -75,10 +80,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 102,
+	spec_version: 113,
 	impl_version: 3,
 	apis: apis::RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 3,
 	system_version: 1,
 };
 
@@ -164,14 +169,52 @@ pub type TxExtension = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_asset_conversion_tx_payment::ChargeAssetTxPayment<Runtime>,
 	frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
 	frame_system::WeightReclaim<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
+///
+/// Besides regular extrinsics, it accepts Ethereum transactions wrapped in an unsigned
+/// `Revive::eth_transact`, checked with the extension built by [`EthExtraImpl`].
 pub type UncheckedExtrinsic =
-	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, TxExtension>;
+	pallet_revive::evm::runtime::UncheckedExtrinsic<Address, Signature, EthExtraImpl>;
+
+/// Builds the transaction extension of Ethereum transactions submitted through `Revive`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EthExtraImpl;
+
+impl pallet_revive::evm::runtime::EthExtra for EthExtraImpl {
+	type Config = Runtime;
+	type Extension = TxExtension;
+
+	fn get_eth_extension(nonce: Nonce, tip: Balance) -> Self::Extension {
+		(
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::Immortal),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None),
+			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
+			frame_system::WeightReclaim::<Runtime>::new(),
+		)
+	}
+}
+
+impl TryFrom<RuntimeCall> for pallet_revive::Call<Runtime> {
+	type Error = ();
+
+	fn try_from(call: RuntimeCall) -> Result<Self, Self::Error> {
+		match call {
+			RuntimeCall::Revive(call) => Ok(call),
+			_ => Err(()),
+		}
+	}
+}
 
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, TxExtension>;
@@ -180,7 +223,7 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, TxExtension>;
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (migrations::UpgradeSessionKeys, migrations::RetireSudo);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -320,4 +363,49 @@ mod runtime {
 
 	#[runtime::pallet_index(36)]
 	pub type Contracts = pallet_contracts::Pallet<Runtime>;
+
+	#[runtime::pallet_index(37)]
+	pub type ZkVerifier = pallet_zk_verifier::Pallet<Runtime>;
+
+	#[runtime::pallet_index(38)]
+	pub type ZkvmVerifier = pallet_zkvm_verifier::Pallet<Runtime>;
+
+	#[runtime::pallet_index(39)]
+	pub type ShieldedPool = pallet_shielded_pool::Pallet<Runtime>;
+
+	#[runtime::pallet_index(40)]
+	pub type Template = pallet_template::Pallet<Runtime>;
+
+	#[runtime::pallet_index(41)]
+	pub type Revive = pallet_revive::Pallet<Runtime>;
+
+	#[runtime::pallet_index(42)]
+	pub type AssetConversionTxPayment = pallet_asset_conversion_tx_payment::Pallet<Runtime>;
+
+	#[runtime::pallet_index(43)]
+	pub type AuthorityDiscovery = pallet_authority_discovery::Pallet<Runtime>;
+
+	#[runtime::pallet_index(44)]
+	pub type Mmr = pallet_mmr::Pallet<Runtime>;
+
+	#[runtime::pallet_index(45)]
+	pub type Beefy = pallet_beefy::Pallet<Runtime>;
+
+	#[runtime::pallet_index(46)]
+	pub type BeefyMmrLeaf = pallet_beefy_mmr::Pallet<Runtime>;
+
+	#[runtime::pallet_index(47)]
+	pub type Origins = pallet_custom_origins::Pallet<Runtime>;
+
+	#[runtime::pallet_index(48)]
+	pub type TechnicalCommittee = pallet_collective::Pallet<Runtime, Instance1>;
+
+	#[runtime::pallet_index(49)]
+	pub type TechnicalMembership = pallet_membership::Pallet<Runtime, Instance1>;
+
+	#[runtime::pallet_index(50)]
+	pub type SafeMode = pallet_safe_mode::Pallet<Runtime>;
+
+	#[runtime::pallet_index(51)]
+	pub type TxPause = pallet_tx_pause::Pallet<Runtime>;
 }
@@ -0,0 +1,267 @@
+//! Runtime upgrades that are not owned by a single pallet.
+
+use alloc::vec::Vec;
+use codec::{DecodeAll, Encode};
+use frame_support::{
+	storage::unhashed,
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
+use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
+use sp_core::{ecdsa, U256, U512};
+use sp_io::hashing::blake2_256;
+use sp_runtime::impl_opaque_keys;
+
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+use crate::{
+	configs::{sudo_retired, RuntimeBlockWeights},
+	AccountId, Babe, Grandpa, ImOnline, Runtime, RuntimeEvent, Session, SessionKeys, System,
+};
+
+impl_opaque_keys! {
+	/// The session keys before `authority_discovery` was added.
+	pub struct SessionKeysV1 {
+		pub babe: Babe,
+		pub grandpa: Grandpa,
+		pub im_online: ImOnline,
+	}
+}
+
+/// Adds `authority_discovery` and `beefy` keys to the session keys of every validator, so they
+/// still decode after `AuthorityDiscovery` and `Beefy` joined `SessionKeys`.
+///
+/// The runtime cannot know the real keys, so every validator gets its `im_online` public key,
+/// which is unique and of the same sr25519 kind, for authority discovery, and a BEEFY key nobody
+/// holds, made from its account. These only keep the keys decodable: the node looks for its
+/// authority discovery key under the `audi` key type, not `imon`, so a validator publishes no
+/// addresses until its operator generates new keys with `author_rotateKeys` and registers them
+/// with `session.set_keys`, which takes effect two sessions later. BEEFY only starts once root
+/// calls `beefy.set_new_genesis`, which should wait for the validators to have set real keys.
+/// Keys that already decode as `SessionKeys` are left alone, so running the upgrade again is a
+/// no-op.
+pub struct UpgradeSessionKeys;
+
+impl OnRuntimeUpgrade for UpgradeSessionKeys {
+	fn on_runtime_upgrade() -> Weight {
+		// Decode the raw keys rather than `get` them, which would log the old ones as corrupted.
+		let upgraded = pallet_session::NextKeys::<Runtime>::iter_keys().next().is_none_or(|who| {
+			let key = pallet_session::NextKeys::<Runtime>::hashed_key_for(who);
+			unhashed::get_raw(&key).is_some_and(|raw| SessionKeys::decode_all(&mut &raw[..]).is_ok())
+		});
+		if upgraded {
+			return Weight::zero();
+		}
+
+		Session::upgrade_keys::<SessionKeysV1, _>(|validator, old| SessionKeys {
+			authority_discovery: AuthorityDiscoveryId::from(old.im_online.clone().into_inner()),
+			babe: old.babe,
+			beefy: placeholder_beefy_id(&validator),
+			grandpa: old.grandpa,
+			im_online: old.im_online,
+		});
+		RuntimeBlockWeights::get().max_block
+	}
+}
+
+/// A BEEFY key unique to `validator`, but not a point of the curve anyone holds the secret of.
+///
+/// The key is the point with an even `y` over the first hash of `validator` and a counter that
+/// is an `x` of secp256k1, so it is a valid compressed key without being derived from a secret.
+pub(crate) fn placeholder_beefy_id(validator: &AccountId) -> BeefyId {
+	let mut counter = 0u32;
+	loop {
+		let x = blake2_256(&(b"kora/beefy-placeholder", validator, counter).encode());
+		if is_secp256k1_x(&x) {
+			let mut raw = [0u8; 33];
+			raw[0] = 0x02;
+			raw[1..].copy_from_slice(&x);
+			return BeefyId::from(ecdsa::Public::from_raw(raw));
+		}
+		counter += 1;
+	}
+}
+
+/// The order of the base field of secp256k1, `2^256 - 2^32 - 977`.
+const SECP256K1_P: U256 = U256([0xFFFF_FFFE_FFFF_FC2F, u64::MAX, u64::MAX, u64::MAX]);
+
+/// Whether the big-endian `x` is the x-coordinate of a point of secp256k1, that is whether
+/// `x^3 + 7` is a square modulo [`SECP256K1_P`] by Euler's criterion.
+fn is_secp256k1_x(x: &[u8; 32]) -> bool {
+	let x = U256::from_big_endian(x);
+	if x >= SECP256K1_P {
+		return false;
+	}
+	let mul = |a: U256, b: U256| {
+		U256::try_from(a.full_mul(b) % U512::from(SECP256K1_P)).expect("reduced modulo p; qed")
+	};
+
+	let mut base = (mul(mul(x, x), x) + 7) % SECP256K1_P;
+	let mut exponent = (SECP256K1_P - 1) >> 1;
+	let mut power = U256::one();
+	while !exponent.is_zero() {
+		if exponent.bit(0) {
+			power = mul(power, base);
+		}
+		base = mul(base, base);
+		exponent = exponent >> 1;
+	}
+	power == U256::one()
+}
+
+/// Removes the sudo key once `dynamic_params::sudo::RetirementBlock` has passed, in case its
+/// holder did not call `sudo.remove_key` themselves.
+///
+/// Does nothing before the retirement block or once the key is gone, so it can stay in
+/// [`crate::Migrations`] across upgrades until it has run once after the retirement.
+pub struct RetireSudo;
+
+impl OnRuntimeUpgrade for RetireSudo {
+	fn on_runtime_upgrade() -> Weight {
+		let db = <Runtime as frame_system::Config>::DbWeight::get();
+		if !sudo_retired() || pallet_sudo::Key::<Runtime>::get().is_none() {
+			return db.reads(2);
+		}
+
+		pallet_sudo::Key::<Runtime>::kill();
+		System::deposit_event(RuntimeEvent::Sudo(pallet_sudo::Event::KeyRemoved));
+		db.reads_writes(2, 2)
+	}
+
+	/// Checks that governance, rather than the sudo key, can reach every admin origin, and that
+	/// referenda with the root and general admin origins can be enacted.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		use crate::{
+			configs::{dynamic_params::referenda, pallet_custom_origins::Origin},
+			OriginCaller, RuntimeCall,
+		};
+		use frame_support::storage::{with_transaction, TransactionOutcome};
+		use sp_runtime::{traits::Dispatchable, DispatchError};
+
+		let tracks = referenda::Tracks::get();
+		let origins = referenda::Origins::get();
+		frame_support::ensure!(
+			origins.iter().all(|(_, track)| tracks.iter().any(|t| t.id == *track)),
+			"a governance origin is mapped to a track that does not exist"
+		);
+		let reachable = |origin: OriginCaller| origins.iter().any(|(o, _)| *o == origin);
+		frame_support::ensure!(
+			reachable(OriginCaller::system(frame_system::RawOrigin::Root)),
+			"no referendum track dispatches as root"
+		);
+		frame_support::ensure!(
+			reachable(OriginCaller::Origins(Origin::GeneralAdmin)),
+			"no referendum track dispatches as the general admin"
+		);
+
+		// Dispatch a call only the origin may make, as the scheduler enacts a referendum, and
+		// roll it back.
+		let enacts = |origin: OriginCaller, call: RuntimeCall| {
+			with_transaction(|| {
+				let dispatched = call.dispatch(origin.into()).is_ok();
+				TransactionOutcome::Rollback(Ok::<_, DispatchError>(dispatched))
+			})
+			.unwrap_or(false)
+		};
+		frame_support::ensure!(
+			enacts(
+				OriginCaller::system(frame_system::RawOrigin::Root),
+				RuntimeCall::System(frame_system::Call::set_storage {
+					items: alloc::vec![(b":kora:retire_sudo".to_vec(), Vec::new())],
+				}),
+			),
+			"a root referendum cannot be enacted"
+		);
+		frame_support::ensure!(
+			enacts(
+				OriginCaller::Origins(Origin::GeneralAdmin),
+				RuntimeCall::TechnicalMembership(pallet_membership::Call::add_member {
+					who: AccountId::new([0xff; 32]).into(),
+				}),
+			),
+			"a general admin referendum cannot be enacted"
+		);
+		frame_support::ensure!(
+			!sudo_retired() || pallet_sudo::Key::<Runtime>::get().is_none(),
+			"sudo is retired but still has a key"
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_beefy_mmr::BeefyEcdsaToEthereum;
+	use sp_runtime::{traits::Convert, BuildStorage};
+
+	use crate::genesis_config_presets::session_keys_from_seed;
+
+	#[test]
+	fn session_keys_gain_authority_discovery_and_beefy_keys() {
+		let validator = AccountId::new([1; 32]);
+		let keys = session_keys_from_seed("Alice");
+		let old = SessionKeysV1 {
+			babe: keys.babe.clone(),
+			grandpa: keys.grandpa.clone(),
+			im_online: keys.im_online.clone(),
+		};
+		let storage = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		sp_io::TestExternalities::new(storage).execute_with(|| {
+			let key = pallet_session::NextKeys::<Runtime>::hashed_key_for(&validator);
+			sp_io::storage::set(&key, &old.encode());
+
+			UpgradeSessionKeys::on_runtime_upgrade();
+
+			let upgraded = pallet_session::NextKeys::<Runtime>::get(&validator).unwrap();
+			assert_eq!(upgraded.babe, keys.babe);
+			assert_eq!(upgraded.im_online, keys.im_online);
+			assert_eq!(
+				upgraded.authority_discovery,
+				AuthorityDiscoveryId::from(keys.im_online.into_inner())
+			);
+			assert_eq!(upgraded.beefy, placeholder_beefy_id(&validator));
+			// A valid compressed point, so the MMR can commit to it as an Ethereum address.
+			assert_eq!(BeefyEcdsaToEthereum::convert(upgraded.beefy.clone()).len(), 20);
+			assert_ne!(upgraded.beefy, placeholder_beefy_id(&AccountId::new([2; 32])));
+			assert_eq!(UpgradeSessionKeys::on_runtime_upgrade(), Weight::zero());
+		});
+	}
+
+	#[test]
+	fn sudo_key_is_removed_once_retired() {
+		use crate::{
+			configs::{dynamic_params::sudo, RuntimeParameters},
+			Parameters, RuntimeOrigin,
+		};
+
+		let key = AccountId::new([1; 32]);
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_sudo::GenesisConfig::<Runtime> { key: Some(key.clone()) }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+		sp_io::TestExternalities::new(storage).execute_with(|| {
+			System::set_block_number(5);
+			Parameters::set_parameter(
+				RuntimeOrigin::root(),
+				RuntimeParameters::Sudo(sudo::Parameters::RetirementBlock(
+					sudo::RetirementBlock,
+					Some(Some(10)),
+				)),
+			)
+			.unwrap();
+
+			RetireSudo::on_runtime_upgrade();
+			assert_eq!(pallet_sudo::Key::<Runtime>::get(), Some(key));
+
+			System::set_block_number(10);
+			RetireSudo::on_runtime_upgrade();
+			assert_eq!(pallet_sudo::Key::<Runtime>::get(), None);
+			System::assert_last_event(RuntimeEvent::Sudo(pallet_sudo::Event::KeyRemoved));
+		});
+	}
+}
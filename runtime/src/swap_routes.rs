@@ -0,0 +1,285 @@
+//! Best-route search over the `AssetConversion` pools, for
+//! [`AssetConversionRouteApi`](crate::apis::AssetConversionRouteApi).
+//!
+//! Routes are paths of at most `MaxSwapPathLength` distinct assets through pools with liquidity.
+//! Each path is priced exactly as `swap_exact_tokens_for_tokens` or
+//! `swap_tokens_for_exact_tokens` would execute it, and the one giving the most out, or taking
+//! the least in, wins, the shorter one on ties. Every path through the first `MAX_POOLS` pools
+//! is tried, following from each asset only the pools that hold it.
+
+use alloc::{vec, vec::Vec};
+use frame_support::traits::{fungible::NativeOrWithId, Get};
+use sp_runtime::{traits::Zero, Permill};
+
+use crate::{apis::SwapRoute, AssetConversion, Balance, Runtime};
+
+type AssetKind = NativeOrWithId<u32>;
+type MaxSwapPathLength = <Runtime as pallet_asset_conversion::Config>::MaxSwapPathLength;
+type LPFee = <Runtime as pallet_asset_conversion::Config>::LPFee;
+
+/// Number of pools read by [`best_route`], which bounds its cost as pools are added.
+const MAX_POOLS: usize = 256;
+
+/// A path, with its amounts in and out.
+type Candidate = (Vec<AssetKind>, Balance, Balance);
+
+/// The best route to swap `asset_in` for `asset_out`, where `amount` is the exact amount in if
+/// `exact_in`, and the exact amount out otherwise.
+pub fn best_route(
+	asset_in: AssetKind,
+	asset_out: AssetKind,
+	amount: Balance,
+	exact_in: bool,
+) -> Option<SwapRoute<Balance, AssetKind>> {
+	if asset_in == asset_out || amount.is_zero() {
+		return None;
+	}
+
+	let pools: Vec<_> =
+		pallet_asset_conversion::Pools::<Runtime>::iter_keys().take(MAX_POOLS).collect();
+	let mut best: Option<Candidate> = None;
+	search(&pools, &asset_out, &mut vec![asset_in], &mut |path| {
+		let Some((amount_in, amount_out)) = price(path, amount, exact_in) else { return };
+		if is_better(best.as_ref(), path, amount_in, amount_out, exact_in) {
+			best = Some((path.to_vec(), amount_in, amount_out));
+		}
+	});
+	let (path, amount_in, amount_out) = best?;
+
+	// What is left of the amount in after the fee of every pool, in the asset in.
+	let keep = Permill::from_perthousand(1000 - <LPFee as Get<u32>>::get());
+	let after_fees = path.windows(2).fold(amount_in, |amount, _| keep * amount);
+	// What that would buy at the pools' current prices, without moving them.
+	let spot_out = path.windows(2).try_fold(after_fees, |amount, hop| {
+		let (reserve_in, reserve_out) =
+			AssetConversion::get_reserves(hop[0].clone(), hop[1].clone()).ok()?;
+		AssetConversion::quote(&amount, &reserve_in, &reserve_out).ok()
+	})?;
+	let price_impact = match spot_out.checked_sub(amount_out) {
+		Some(shortfall) if !spot_out.is_zero() => Permill::from_rational(shortfall, spot_out),
+		_ => Permill::zero(),
+	};
+
+	Some(SwapRoute { path, amount_in, amount_out, fee: amount_in - after_fees, price_impact })
+}
+
+/// Whether swapping `amount_in` for `amount_out` along `path` beats `best`: more out, or less in,
+/// and then fewer hops.
+fn is_better(
+	best: Option<&Candidate>,
+	path: &[AssetKind],
+	amount_in: Balance,
+	amount_out: Balance,
+	exact_in: bool,
+) -> bool {
+	best.is_none_or(|(best_path, best_in, best_out)| {
+		let ordering = if exact_in { amount_out.cmp(best_out) } else { best_in.cmp(&amount_in) };
+		ordering.then(best_path.len().cmp(&path.len())).is_gt()
+	})
+}
+
+/// Calls `visit` with every path from the last asset of `path` to `target` through `pools`.
+fn search(
+	pools: &[(AssetKind, AssetKind)],
+	target: &AssetKind,
+	path: &mut Vec<AssetKind>,
+	visit: &mut impl FnMut(&[AssetKind]),
+) {
+	let Some(last) = path.last().cloned() else { return };
+	if last == *target {
+		return visit(path);
+	}
+	if path.len() as u32 >= <MaxSwapPathLength as Get<u32>>::get() {
+		return;
+	}
+	for (asset1, asset2) in pools {
+		let next = match (*asset1 == last, *asset2 == last) {
+			(true, _) => asset2,
+			(_, true) => asset1,
+			_ => continue,
+		};
+		if path.contains(next) {
+			continue;
+		}
+		path.push(next.clone());
+		search(pools, target, path, visit);
+		path.pop();
+	}
+}
+
+/// The amounts in and out of swapping along `path`, where `amount` is the exact amount in if
+/// `exact_in`, and the exact amount out otherwise.
+fn price(path: &[AssetKind], amount: Balance, exact_in: bool) -> Option<(Balance, Balance)> {
+	let reserves = |hop: &[AssetKind]| {
+		AssetConversion::get_reserves(hop[0].clone(), hop[1].clone()).ok()
+	};
+	if exact_in {
+		let amount_out = path.windows(2).try_fold(amount, |amount, hop| {
+			let (reserve_in, reserve_out) = reserves(hop)?;
+			AssetConversion::get_amount_out(&amount, &reserve_in, &reserve_out).ok()
+		})?;
+		(!amount_out.is_zero()).then_some((amount, amount_out))
+	} else {
+		let amount_in = path.windows(2).rev().try_fold(amount, |amount, hop| {
+			let (reserve_in, reserve_out) = reserves(hop)?;
+			AssetConversion::get_amount_in(&amount, &reserve_in, &reserve_out).ok()
+		})?;
+		Some((amount_in, amount))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{AccountId, RuntimeOrigin, System, UNIT};
+	use frame_support::assert_ok;
+	use pallet_asset_conversion::Swap;
+	use sp_runtime::BuildStorage;
+
+	const LP: AccountId = AccountId::new([1; 32]);
+	const TRADER: AccountId = AccountId::new([2; 32]);
+
+	const NATIVE: AssetKind = NativeOrWithId::Native;
+	const ASSET1: AssetKind = NativeOrWithId::WithId(1);
+	const ASSET2: AssetKind = NativeOrWithId::WithId(2);
+	const ASSET3: AssetKind = NativeOrWithId::WithId(3);
+	const ASSET4: AssetKind = NativeOrWithId::WithId(4);
+
+	/// Assets 1 to 4, with pools native/1 and native/2 twice as deep in 1 as in 2, a shallower
+	/// 1/2 pool at a worse price, and an empty native/3 pool.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(LP, 100_000 * UNIT), (TRADER, 1_000 * UNIT)],
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		pallet_assets::GenesisConfig::<Runtime, pallet_assets::Instance1> {
+			assets: (1..=4).map(|id| (id, LP, false, 1)).collect(),
+			accounts: (1..=4)
+				.map(|id| (id, LP, 100_000 * UNIT))
+				.chain([(1, TRADER, 1_000 * UNIT)])
+				.collect(),
+			..Default::default()
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			add_pool(NATIVE, ASSET1, 1_000 * UNIT, 2_000 * UNIT);
+			add_pool(NATIVE, ASSET2, 1_000 * UNIT, 500 * UNIT);
+			add_pool(ASSET1, ASSET2, 1_000 * UNIT, 100 * UNIT);
+			add_pool(NATIVE, ASSET3, 0, 0);
+		});
+		ext
+	}
+
+	/// Creates the pool of `asset1` and `asset2`, and adds the given liquidity unless zero.
+	fn add_pool(asset1: AssetKind, asset2: AssetKind, amount1: Balance, amount2: Balance) {
+		let origin = RuntimeOrigin::signed(LP);
+		assert_ok!(AssetConversion::create_pool(
+			origin.clone(),
+			Box::new(asset1.clone()),
+			Box::new(asset2.clone())
+		));
+		if !amount1.is_zero() {
+			assert_ok!(AssetConversion::add_liquidity(
+				origin,
+				Box::new(asset1),
+				Box::new(asset2),
+				amount1,
+				amount2,
+				1,
+				1,
+				LP
+			));
+		}
+	}
+
+	#[test]
+	fn exact_in_routes_through_the_native_token_when_it_pays_more() {
+		new_test_ext().execute_with(|| {
+			let route = best_route(ASSET1, ASSET2, 10 * UNIT, true).unwrap();
+			assert_eq!(route.path, vec![ASSET1, NATIVE, ASSET2]);
+			assert_eq!(route.amount_in, 10 * UNIT);
+			// Two 0.3% fees.
+			let keep = Permill::from_perthousand(997);
+			assert_eq!(route.fee, 10 * UNIT - keep * (keep * (10 * UNIT)));
+
+			let direct = price(&[ASSET1, ASSET2], 10 * UNIT, true).unwrap().1;
+			assert!(route.amount_out > direct);
+
+			let received = <AssetConversion as Swap<_>>::swap_exact_tokens_for_tokens(
+				TRADER,
+				route.path,
+				route.amount_in,
+				None,
+				TRADER,
+				true,
+			)
+			.unwrap();
+			assert_eq!(received, route.amount_out);
+		});
+	}
+
+	#[test]
+	fn exact_out_routes_through_the_native_token_when_it_costs_less() {
+		new_test_ext().execute_with(|| {
+			let route = best_route(ASSET1, ASSET2, UNIT, false).unwrap();
+			assert_eq!(route.path, vec![ASSET1, NATIVE, ASSET2]);
+			assert_eq!(route.amount_out, UNIT);
+
+			let direct = price(&[ASSET1, ASSET2], UNIT, false).unwrap().0;
+			assert!(route.amount_in < direct);
+
+			let paid = <AssetConversion as Swap<_>>::swap_tokens_for_exact_tokens(
+				TRADER,
+				route.path,
+				route.amount_out,
+				None,
+				TRADER,
+				true,
+			)
+			.unwrap();
+			assert_eq!(paid, route.amount_in);
+		});
+	}
+
+	#[test]
+	fn ties_go_to_the_route_with_fewer_hops() {
+		let direct = vec![ASSET1, ASSET2];
+		let two_hops = vec![ASSET1, NATIVE, ASSET2];
+
+		for exact_in in [true, false] {
+			let best = (direct.clone(), 100, 50);
+			assert!(!is_better(Some(&best), &two_hops, 100, 50, exact_in));
+			let best = (two_hops.clone(), 100, 50);
+			assert!(is_better(Some(&best), &direct, 100, 50, exact_in));
+		}
+
+		// Price comes first.
+		let best = (direct.clone(), 100, 50);
+		assert!(is_better(Some(&best), &two_hops, 100, 51, true));
+		assert!(is_better(Some(&best), &two_hops, 99, 50, false));
+		assert!(!is_better(Some(&best), &two_hops, 99, 50, true));
+	}
+
+	#[test]
+	fn there_is_no_route_without_liquidity() {
+		new_test_ext().execute_with(|| {
+			// An empty pool.
+			assert!(best_route(NATIVE, ASSET3, UNIT, true).is_none());
+			assert!(best_route(ASSET1, ASSET3, UNIT, false).is_none());
+			// No pool.
+			assert!(best_route(ASSET1, ASSET4, UNIT, true).is_none());
+			// More out than the pools hold.
+			assert!(best_route(NATIVE, ASSET2, 500 * UNIT, false).is_none());
+
+			assert!(best_route(ASSET1, ASSET1, UNIT, true).is_none());
+			assert!(best_route(ASSET1, ASSET2, 0, true).is_none());
+		});
+	}
+}